@@ -1,40 +1,100 @@
 const COMMANDS: &[&str] = &[
     "git_status",
+    "git_watch",
+    "git_unwatch",
     "git_log",
+    "git_graph",
     "git_commit",
     "git_stage",
     "git_unstage",
     "git_stage_all",
     "git_unstage_all",
+    "git_stage_hunk",
+    "git_unstage_hunk",
+    "git_discard_hunk",
     "git_diff",
     "git_diff_file",
+    "git_diff_commits",
     "git_branches",
     "git_create_branch",
     "git_checkout",
     "git_delete_branch",
+    "git_rename_branch",
+    "git_set_upstream",
     "git_merge",
+    "git_merge_continue",
+    "git_merge_abort",
+    "git_rebase",
+    "git_rebase_continue",
+    "git_rebase_skip",
+    "git_rebase_abort",
+    "git_rebase_interactive",
+    "git_sequencer_continue",
+    "git_sequencer_skip",
+    "git_sequencer_abort",
+    "git_sequencer_state",
+    "git_conflicts",
+    "git_resolve_conflict",
+    "git_operation_state",
     "git_fetch",
     "git_pull",
     "git_push",
+    "git_cancel_operation",
     "git_remotes",
     "git_add_remote",
     "git_remove_remote",
+    "git_rename_remote",
+    "git_set_remote_url",
+    "git_set_remote_refspecs",
+    "git_prune_remote",
     "git_stash",
     "git_stash_list",
     "git_stash_apply",
     "git_stash_pop",
     "git_stash_drop",
+    "git_stash_show",
     "git_reset",
+    "git_reflog",
+    "git_undo_preview",
+    "git_undo",
+    "git_bisect_start",
+    "git_bisect_mark",
+    "git_bisect_state",
+    "git_bisect_run",
+    "git_bisect_reset",
+    "git_format_patch",
+    "git_apply_patch",
+    "git_am",
     "git_revert",
     "git_cherry_pick",
     "git_tags",
     "git_create_tag",
     "git_delete_tag",
+    "git_push_tags",
+    "git_worktrees",
+    "git_worktree_add",
+    "git_worktree_lock",
+    "git_worktree_unlock",
+    "git_worktree_prune",
+    "git_worktree_remove",
+    "git_submodules",
+    "git_submodule_init",
+    "git_submodule_update",
+    "git_submodule_sync",
     "git_clone",
     "git_init",
+    "git_sparse_checkout",
+    "git_sparse_checkout_set",
+    "git_sparse_checkout_disable",
     "git_config_get",
     "git_config_set",
+    "git_config_get_all",
+    "git_config_list",
+    "git_config_unset",
+    "git_config",
+    "git_config_update",
     "git_blame",
+    "git_search_history",
     "git_show_commit",
 ];
 
@@ -4,14 +4,26 @@ import { invoke } from '@tauri-apps/api/core';
 // This plugin focuses on providing the raw API functions
 
 // Status and info
-export async function gitStatus(repoPath: string): Promise<any> {
-  return await invoke('plugin:git|git_status', { repoPath });
+export async function gitStatus(repoPath: string, options: any = {}): Promise<any> {
+  return await invoke('plugin:git|git_status', { repoPath, options });
+}
+
+export async function gitWatch(repoPath: string, options: any = {}): Promise<string> {
+  return await invoke('plugin:git|git_watch', { repoPath, options });
+}
+
+export async function gitUnwatch(watchId: string): Promise<boolean> {
+  return await invoke('plugin:git|git_unwatch', { watchId });
 }
 
 export async function gitLog(repoPath: string, options: any = {}): Promise<any[]> {
   return await invoke('plugin:git|git_log', { repoPath, options });
 }
 
+export async function gitGraph(repoPath: string, options: any = {}): Promise<any> {
+  return await invoke('plugin:git|git_graph', { repoPath, options });
+}
+
 // Staging
 export async function gitStage(repoPath: string, filePath: string): Promise<void> {
   return await invoke('plugin:git|git_stage', { repoPath, filePath });
@@ -29,23 +41,39 @@ export async function gitUnstageAll(repoPath: string): Promise<void> {
   return await invoke('plugin:git|git_unstage_all', { repoPath });
 }
 
+export async function gitStageHunk(repoPath: string, selection: any): Promise<void> {
+  return await invoke('plugin:git|git_stage_hunk', { repoPath, selection });
+}
+
+export async function gitUnstageHunk(repoPath: string, selection: any): Promise<void> {
+  return await invoke('plugin:git|git_unstage_hunk', { repoPath, selection });
+}
+
+export async function gitDiscardHunk(repoPath: string, selection: any): Promise<void> {
+  return await invoke('plugin:git|git_discard_hunk', { repoPath, selection });
+}
+
 // Commits
 export async function gitCommit(repoPath: string, options: any): Promise<string> {
   return await invoke('plugin:git|git_commit', { repoPath, options });
 }
 
 // Diffs
-export async function gitDiff(repoPath: string, cached: boolean = false): Promise<any[]> {
-  return await invoke('plugin:git|git_diff', { repoPath, cached });
+export async function gitDiff(repoPath: string, cached: boolean = false, options?: any): Promise<any[]> {
+  return await invoke('plugin:git|git_diff', { repoPath, cached, options });
 }
 
 export async function gitDiffFile(repoPath: string, filePath: string, cached: boolean = false): Promise<any> {
   return await invoke('plugin:git|git_diff_file', { repoPath, filePath, cached });
 }
 
+export async function gitDiffCommits(repoPath: string, fromRef: string, toRef: string, options?: any): Promise<any[]> {
+  return await invoke('plugin:git|git_diff_commits', { repoPath, fromRef, toRef, options });
+}
+
 // Branches
-export async function gitBranches(repoPath: string): Promise<any[]> {
-  return await invoke('plugin:git|git_branches', { repoPath });
+export async function gitBranches(repoPath: string, options: any = {}): Promise<any[]> {
+  return await invoke('plugin:git|git_branches', { repoPath, options });
 }
 
 export async function gitCreateBranch(repoPath: string, branchName: string, fromRef?: string): Promise<void> {
@@ -60,10 +88,60 @@ export async function gitDeleteBranch(repoPath: string, branchName: string, forc
   return await invoke('plugin:git|git_delete_branch', { repoPath, branchName, force });
 }
 
-export async function gitMerge(repoPath: string, options: any): Promise<void> {
+export async function gitRenameBranch(repoPath: string, branchName: string, newName: string, force: boolean = false): Promise<void> {
+  return await invoke('plugin:git|git_rename_branch', { repoPath, branchName, newName, force });
+}
+
+export async function gitSetUpstream(repoPath: string, branchName?: string, upstream?: string): Promise<void> {
+  return await invoke('plugin:git|git_set_upstream', { repoPath, branchName, upstream });
+}
+
+export async function gitMerge(repoPath: string, options: any): Promise<any> {
   return await invoke('plugin:git|git_merge', { repoPath, options });
 }
 
+export async function gitMergeContinue(repoPath: string, message?: string): Promise<string> {
+  return await invoke('plugin:git|git_merge_continue', { repoPath, message });
+}
+
+export async function gitMergeAbort(repoPath: string): Promise<void> {
+  return await invoke('plugin:git|git_merge_abort', { repoPath });
+}
+
+// Rebase
+export async function gitRebase(repoPath: string, options: any): Promise<any> {
+  return await invoke('plugin:git|git_rebase', { repoPath, options });
+}
+
+export async function gitRebaseContinue(repoPath: string): Promise<any> {
+  return await invoke('plugin:git|git_rebase_continue', { repoPath });
+}
+
+export async function gitRebaseSkip(repoPath: string): Promise<any> {
+  return await invoke('plugin:git|git_rebase_skip', { repoPath });
+}
+
+export async function gitRebaseAbort(repoPath: string): Promise<void> {
+  return await invoke('plugin:git|git_rebase_abort', { repoPath });
+}
+
+export async function gitRebaseInteractive(repoPath: string, options: { onto: string; todo: any[] }): Promise<any> {
+  return await invoke('plugin:git|git_rebase_interactive', { repoPath, options });
+}
+
+// Conflicts
+export async function gitConflicts(repoPath: string): Promise<any[]> {
+  return await invoke('plugin:git|git_conflicts', { repoPath });
+}
+
+export async function gitResolveConflict(repoPath: string, filePath: string, resolution: 'ours' | 'theirs' | 'both'): Promise<void> {
+  return await invoke('plugin:git|git_resolve_conflict', { repoPath, filePath, resolution });
+}
+
+export async function gitOperationState(repoPath: string): Promise<any> {
+  return await invoke('plugin:git|git_operation_state', { repoPath });
+}
+
 // Remote operations
 export async function gitFetch(repoPath: string, options: any = {}): Promise<void> {
   return await invoke('plugin:git|git_fetch', { repoPath, options });
@@ -77,12 +155,16 @@ export async function gitPush(repoPath: string, options: any = {}): Promise<void
   return await invoke('plugin:git|git_push', { repoPath, options });
 }
 
+export async function gitCancelOperation(operationId: string): Promise<boolean> {
+  return await invoke('plugin:git|git_cancel_operation', { operationId });
+}
+
 // Remotes
 export async function gitRemotes(repoPath: string): Promise<any[]> {
   return await invoke('plugin:git|git_remotes', { repoPath });
 }
 
-export async function gitAddRemote(repoPath: string, name: string, url: string): Promise<void> {
+export async function gitAddRemote(repoPath: string, name: string, url: string): Promise<any> {
   return await invoke('plugin:git|git_add_remote', { repoPath, name, url });
 }
 
@@ -90,8 +172,24 @@ export async function gitRemoveRemote(repoPath: string, name: string): Promise<v
   return await invoke('plugin:git|git_remove_remote', { repoPath, name });
 }
 
+export async function gitRenameRemote(repoPath: string, name: string, newName: string): Promise<string[]> {
+  return await invoke('plugin:git|git_rename_remote', { repoPath, name, newName });
+}
+
+export async function gitSetRemoteUrl(repoPath: string, name: string, options: any = {}): Promise<any> {
+  return await invoke('plugin:git|git_set_remote_url', { repoPath, name, options });
+}
+
+export async function gitSetRemoteRefspecs(repoPath: string, name: string, refspecs: any = {}): Promise<any> {
+  return await invoke('plugin:git|git_set_remote_refspecs', { repoPath, name, refspecs });
+}
+
+export async function gitPruneRemote(repoPath: string, name: string, options: any = {}): Promise<string[]> {
+  return await invoke('plugin:git|git_prune_remote', { repoPath, name, options });
+}
+
 // Stash
-export async function gitStash(repoPath: string, options: any = {}): Promise<void> {
+export async function gitStash(repoPath: string, options: any = {}): Promise<string> {
   return await invoke('plugin:git|git_stash', { repoPath, options });
 }
 
@@ -111,17 +209,81 @@ export async function gitStashDrop(repoPath: string, stashId: string): Promise<v
   return await invoke('plugin:git|git_stash_drop', { repoPath, stashId });
 }
 
+export async function gitStashShow(repoPath: string, stashId: string, options?: any): Promise<any[]> {
+  return await invoke('plugin:git|git_stash_show', { repoPath, stashId, options });
+}
+
 // Reset/Revert
 export async function gitReset(repoPath: string, options: any): Promise<void> {
   return await invoke('plugin:git|git_reset', { repoPath, options });
 }
 
-export async function gitRevert(repoPath: string, commit: string): Promise<void> {
-  return await invoke('plugin:git|git_revert', { repoPath, commit });
+export async function gitReflog(repoPath: string, options?: any): Promise<any[]> {
+  return await invoke('plugin:git|git_reflog', { repoPath, options });
+}
+
+export async function gitUndoPreview(repoPath: string, options?: any): Promise<any> {
+  return await invoke('plugin:git|git_undo_preview', { repoPath, options });
+}
+
+export async function gitUndo(repoPath: string, options?: any): Promise<any> {
+  return await invoke('plugin:git|git_undo', { repoPath, options });
+}
+
+export async function gitBisectStart(repoPath: string, options?: any): Promise<any> {
+  return await invoke('plugin:git|git_bisect_start', { repoPath, options });
+}
+
+export async function gitBisectMark(repoPath: string, options: any): Promise<any> {
+  return await invoke('plugin:git|git_bisect_mark', { repoPath, options });
+}
+
+export async function gitBisectState(repoPath: string): Promise<any> {
+  return await invoke('plugin:git|git_bisect_state', { repoPath });
 }
 
-export async function gitCherryPick(repoPath: string, commit: string): Promise<void> {
-  return await invoke('plugin:git|git_cherry_pick', { repoPath, commit });
+export async function gitBisectRun(repoPath: string, options: any): Promise<any> {
+  return await invoke('plugin:git|git_bisect_run', { repoPath, options });
+}
+
+export async function gitBisectReset(repoPath: string): Promise<void> {
+  return await invoke('plugin:git|git_bisect_reset', { repoPath });
+}
+
+export async function gitFormatPatch(repoPath: string, options: any): Promise<any> {
+  return await invoke('plugin:git|git_format_patch', { repoPath, options });
+}
+
+export async function gitApplyPatch(repoPath: string, options: any): Promise<any> {
+  return await invoke('plugin:git|git_apply_patch', { repoPath, options });
+}
+
+export async function gitAm(repoPath: string, options: any): Promise<any[]> {
+  return await invoke('plugin:git|git_am', { repoPath, options });
+}
+
+export async function gitRevert(repoPath: string, options: { commits: string[] }): Promise<any> {
+  return await invoke('plugin:git|git_revert', { repoPath, options });
+}
+
+export async function gitCherryPick(repoPath: string, options: { commits: string[]; recordOrigin?: boolean }): Promise<any> {
+  return await invoke('plugin:git|git_cherry_pick', { repoPath, options });
+}
+
+export async function gitSequencerContinue(repoPath: string): Promise<any> {
+  return await invoke('plugin:git|git_sequencer_continue', { repoPath });
+}
+
+export async function gitSequencerSkip(repoPath: string): Promise<any> {
+  return await invoke('plugin:git|git_sequencer_skip', { repoPath });
+}
+
+export async function gitSequencerAbort(repoPath: string): Promise<void> {
+  return await invoke('plugin:git|git_sequencer_abort', { repoPath });
+}
+
+export async function gitSequencerState(repoPath: string): Promise<any | null> {
+  return await invoke('plugin:git|git_sequencer_state', { repoPath });
 }
 
 // Tags
@@ -129,37 +291,119 @@ export async function gitTags(repoPath: string): Promise<any[]> {
   return await invoke('plugin:git|git_tags', { repoPath });
 }
 
-export async function gitCreateTag(repoPath: string, tagName: string, message?: string): Promise<void> {
-  return await invoke('plugin:git|git_create_tag', { repoPath, tagName, message });
+export async function gitCreateTag(repoPath: string, tagName: string, message?: string, options: any = {}): Promise<string> {
+  return await invoke('plugin:git|git_create_tag', { repoPath, tagName, message, options });
 }
 
 export async function gitDeleteTag(repoPath: string, tagName: string): Promise<void> {
   return await invoke('plugin:git|git_delete_tag', { repoPath, tagName });
 }
 
+export async function gitPushTags(repoPath: string, options: any): Promise<void> {
+  return await invoke('plugin:git|git_push_tags', { repoPath, options });
+}
+
+// Worktrees
+export async function gitWorktrees(repoPath: string): Promise<any[]> {
+  return await invoke('plugin:git|git_worktrees', { repoPath });
+}
+
+export async function gitWorktreeAdd(repoPath: string, options: any): Promise<any> {
+  return await invoke('plugin:git|git_worktree_add', { repoPath, options });
+}
+
+export async function gitWorktreeLock(repoPath: string, worktree: string, reason?: string): Promise<void> {
+  return await invoke('plugin:git|git_worktree_lock', { repoPath, worktree, reason });
+}
+
+export async function gitWorktreeUnlock(repoPath: string, worktree: string): Promise<void> {
+  return await invoke('plugin:git|git_worktree_unlock', { repoPath, worktree });
+}
+
+export async function gitWorktreePrune(repoPath: string): Promise<string[]> {
+  return await invoke('plugin:git|git_worktree_prune', { repoPath });
+}
+
+export async function gitWorktreeRemove(repoPath: string, worktree: string, force?: boolean): Promise<void> {
+  return await invoke('plugin:git|git_worktree_remove', { repoPath, worktree, force });
+}
+
+// Submodules
+export async function gitSubmodules(repoPath: string): Promise<any[]> {
+  return await invoke('plugin:git|git_submodules', { repoPath });
+}
+
+export async function gitSubmoduleInit(repoPath: string, paths?: string[]): Promise<void> {
+  return await invoke('plugin:git|git_submodule_init', { repoPath, paths });
+}
+
+export async function gitSubmoduleUpdate(repoPath: string, options: any = {}): Promise<void> {
+  return await invoke('plugin:git|git_submodule_update', { repoPath, options });
+}
+
+export async function gitSubmoduleSync(repoPath: string, paths?: string[], recursive?: boolean): Promise<void> {
+  return await invoke('plugin:git|git_submodule_sync', { repoPath, paths, recursive });
+}
+
 // Repository
-export async function gitClone(url: string, path: string): Promise<void> {
-  return await invoke('plugin:git|git_clone', { url, path });
+export async function gitClone(url: string, path: string, options: any = {}): Promise<void> {
+  return await invoke('plugin:git|git_clone', { url, path, options });
+}
+
+export async function gitInit(path: string, options: any = {}): Promise<void> {
+  return await invoke('plugin:git|git_init', { path, options });
+}
+
+export async function gitSparseCheckout(repoPath: string): Promise<any> {
+  return await invoke('plugin:git|git_sparse_checkout', { repoPath });
+}
+
+export async function gitSparseCheckoutSet(repoPath: string, paths: string[]): Promise<any> {
+  return await invoke('plugin:git|git_sparse_checkout_set', { repoPath, paths });
 }
 
-export async function gitInit(path: string): Promise<void> {
-  return await invoke('plugin:git|git_init', { path });
+export async function gitSparseCheckoutDisable(repoPath: string): Promise<void> {
+  return await invoke('plugin:git|git_sparse_checkout_disable', { repoPath });
 }
 
 // Config
-export async function gitConfigGet(repoPath: string, key: string): Promise<string> {
-  return await invoke('plugin:git|git_config_get', { repoPath, key });
+export async function gitConfigGet(repoPath: string, key: string, scope?: string): Promise<string | null> {
+  return await invoke('plugin:git|git_config_get', { repoPath, key, scope });
+}
+
+export async function gitConfigGetAll(repoPath: string, key: string, scope?: string): Promise<any[]> {
+  return await invoke('plugin:git|git_config_get_all', { repoPath, key, scope });
+}
+
+export async function gitConfigList(repoPath: string, scope?: string): Promise<any[]> {
+  return await invoke('plugin:git|git_config_list', { repoPath, scope });
 }
 
-export async function gitConfigSet(repoPath: string, key: string, value: string): Promise<void> {
-  return await invoke('plugin:git|git_config_set', { repoPath, key, value });
+export async function gitConfigSet(repoPath: string, key: string, value: string | number | boolean, options: any = {}): Promise<void> {
+  return await invoke('plugin:git|git_config_set', { repoPath, key, value, options });
+}
+
+export async function gitConfigUnset(repoPath: string, key: string, options: any = {}): Promise<void> {
+  return await invoke('plugin:git|git_config_unset', { repoPath, key, options });
+}
+
+export async function gitConfig(repoPath: string): Promise<any> {
+  return await invoke('plugin:git|git_config', { repoPath });
+}
+
+export async function gitConfigUpdate(repoPath: string, config: any, scope?: string): Promise<any> {
+  return await invoke('plugin:git|git_config_update', { repoPath, config, scope });
 }
 
 // Blame
-export async function gitBlame(repoPath: string, filePath: string): Promise<any> {
-  return await invoke('plugin:git|git_blame', { repoPath, filePath });
+export async function gitBlame(repoPath: string, filePath: string, options?: any): Promise<any> {
+  return await invoke('plugin:git|git_blame', { repoPath, filePath, options });
+}
+
+export async function gitSearchHistory(repoPath: string, options: any): Promise<any[]> {
+  return await invoke('plugin:git|git_search_history', { repoPath, options });
 }
 
-export async function gitShowCommit(repoPath: string, commit: string): Promise<any> {
-  return await invoke('plugin:git|git_show_commit', { repoPath, commit });
+export async function gitShowCommit(repoPath: string, commit: string, options?: any): Promise<any> {
+  return await invoke('plugin:git|git_show_commit', { repoPath, commit, options });
 }
\ No newline at end of file
@@ -0,0 +1,450 @@
+use crate::{
+    error::{Error, Result},
+    hooks::read_lines,
+    merge::ensure_clean_state,
+    models::*,
+    repository::{GitManager, Operation},
+    sequencer::ensure_clean_worktree,
+    utils::*,
+};
+use git2::{build::CheckoutBuilder, Oid, Repository, Sort};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+// The session is stored the way `git bisect` stores it, so either tool can continue it and
+// libgit2 reports the repository as bisecting
+const START_FILE: &str = "BISECT_START";
+const LOG_FILE: &str = "BISECT_LOG";
+const TERMS_FILE: &str = "BISECT_TERMS";
+const BAD_REF: &str = "refs/bisect/bad";
+
+/// Exit status of `git bisect run` commands that cannot test the commit.
+const SKIP_EXIT_CODE: i32 = 125;
+
+impl GitManager {
+    /// Start bisecting between `bad` and the `good` revisions, checking out the first commit to
+    /// test once both are known.
+    pub fn bisect_start(&self, repo_path: &str, options: GitBisectStartOptions) -> Result<GitBisectState> {
+        let repo = self.get_repo(repo_path)?;
+        ensure_clean_state(&repo)?;
+        ensure_clean_worktree(&repo)?;
+
+        let head = repo.head()?;
+        let original_head = match head.shorthand() {
+            Some(branch) if head.is_branch() => branch.to_string(),
+            _ => head.peel_to_commit()?.id().to_string(),
+        };
+        let bad = resolve_commit(&repo, options.bad.as_deref().unwrap_or("HEAD"))?;
+        let good = options
+            .good
+            .unwrap_or_default()
+            .iter()
+            .map(|revision| resolve_commit(&repo, revision))
+            .collect::<Result<Vec<_>>>()?;
+
+        std::fs::write(repo.path().join(START_FILE), format!("{}\n", original_head))?;
+        std::fs::write(repo.path().join(TERMS_FILE), "bad\ngood\n")?;
+        std::fs::write(repo.path().join(LOG_FILE), "git bisect start\n")?;
+        mark_commit(&repo, GitBisectMark::Bad, bad)?;
+        for oid in good {
+            mark_commit(&repo, GitBisectMark::Good, oid)?;
+        }
+        next_step(&repo)
+    }
+
+    /// Mark a commit good, bad or untestable and check out the next commit to test.
+    pub fn bisect_mark(&self, repo_path: &str, options: GitBisectMarkOptions) -> Result<GitBisectState> {
+        let repo = self.get_repo(repo_path)?;
+        ensure_bisecting(&repo)?;
+        let oid = resolve_commit(&repo, options.commit.as_deref().unwrap_or("HEAD"))?;
+        mark_commit(&repo, options.mark, oid)?;
+        next_step(&repo)
+    }
+
+    /// The bisect session in progress, if any.
+    pub fn bisect_state(&self, repo_path: &str) -> Result<Option<GitBisectState>> {
+        let repo = self.get_repo(repo_path)?;
+        if !repo.path().join(START_FILE).exists() {
+            return Ok(None);
+        }
+        evaluate(&repo).map(Some)
+    }
+
+    /// Test each commit with a shell command until the first bad commit is found, passing each
+    /// line it prints to `on_output` and the outcome of each step to `on_step`.
+    pub fn bisect_run<O, S>(&self, repo_path: &str, options: GitBisectRunOptions, on_output: O, on_step: S) -> Result<GitBisectState>
+    where
+        O: Fn(GitBisectOutput),
+        S: Fn(GitBisectStep),
+    {
+        let repo = self.get_repo(repo_path)?;
+        ensure_bisecting(&repo)?;
+        let operation = self.begin_operation(options.operation_id.as_deref());
+
+        let mut state = evaluate(&repo)?;
+        if state.current.is_none() && state.first_bad_commit.is_none() && state.possible_first_bad.is_empty() {
+            return Err(Error::InvalidState("bisect needs a good and a bad commit before it can run".to_string()));
+        }
+        while let Some(current) = state.current.take() {
+            let oid = Oid::from_str(&current.hash)?;
+            if repo.head()?.target() != Some(oid) {
+                check_out(&repo, oid)?;
+            }
+
+            let exit_code = run_command(&repo, &options.command, &current.hash, &operation, &on_output)?;
+            let mark = match exit_code {
+                0 => GitBisectMark::Good,
+                SKIP_EXIT_CODE => GitBisectMark::Skip,
+                1..=127 => GitBisectMark::Bad,
+                _ => {
+                    return Err(Error::Bisect(format!(
+                        "'{}' exited with status {} on {}",
+                        options.command, exit_code, current.hash
+                    )))
+                }
+            };
+            mark_commit(&repo, mark, oid)?;
+            state = next_step(&repo)?;
+            on_step(GitBisectStep {
+                commit: current.hash,
+                exit_code,
+                mark,
+            });
+        }
+        Ok(state)
+    }
+
+    /// End the bisect session and check out the branch or commit it started from.
+    pub fn bisect_reset(&self, repo_path: &str) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let original_head = std::fs::read_to_string(repo.path().join(START_FILE))
+            .map_err(|_| Error::InvalidState("no bisect in progress".to_string()))?;
+        let original_head = original_head.trim();
+
+        let branch = format!("refs/heads/{}", original_head);
+        let (commit, branch) = match repo.find_reference(&branch) {
+            Ok(reference) => (reference.peel_to_commit()?, Some(branch)),
+            Err(_) => (repo.find_commit(resolve_commit(&repo, original_head)?)?, None),
+        };
+        repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))?;
+        match branch {
+            Some(branch) => repo.set_head(&branch)?,
+            None => repo.set_head_detached(commit.id())?,
+        }
+
+        for reference in repo.references_glob("refs/bisect/*")? {
+            reference?.delete()?;
+        }
+        for file in [START_FILE, LOG_FILE, TERMS_FILE, "BISECT_EXPECTED_REV", "BISECT_ANCESTORS_OK", "BISECT_NAMES"] {
+            let _ = std::fs::remove_file(repo.path().join(file));
+        }
+        Ok(())
+    }
+}
+
+fn ensure_bisecting(repo: &Repository) -> Result<()> {
+    if repo.path().join(START_FILE).exists() {
+        Ok(())
+    } else {
+        Err(Error::InvalidState("no bisect in progress".to_string()))
+    }
+}
+
+fn resolve_commit(repo: &Repository, revision: &str) -> Result<Oid> {
+    repo.revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map(|commit| commit.id())
+        .map_err(|_| Error::CommitNotFound(revision.to_string()))
+}
+
+/// Record a mark as a `refs/bisect/` reference and in the bisect log.
+fn mark_commit(repo: &Repository, mark: GitBisectMark, oid: Oid) -> Result<()> {
+    let (refname, term) = match mark {
+        GitBisectMark::Bad => (BAD_REF.to_string(), "bad"),
+        GitBisectMark::Good => (format!("refs/bisect/good-{}", oid), "good"),
+        GitBisectMark::Skip => (format!("refs/bisect/skip-{}", oid), "skip"),
+    };
+    repo.reference(&refname, oid, true, &format!("bisect {}", term))?;
+
+    let mut log = std::fs::OpenOptions::new().append(true).create(true).open(repo.path().join(LOG_FILE))?;
+    writeln!(log, "git bisect {} {}", term, oid)?;
+    Ok(())
+}
+
+fn marked_commits(repo: &Repository, prefix: &str) -> Result<Vec<Oid>> {
+    let mut commits = Vec::new();
+    for reference in repo.references_glob(&format!("refs/bisect/{}-*", prefix))? {
+        if let Some(oid) = reference?.target() {
+            commits.push(oid);
+        }
+    }
+    Ok(commits)
+}
+
+/// Work out the state of the session and check out the commit to test next.
+fn next_step(repo: &Repository) -> Result<GitBisectState> {
+    let state = evaluate(repo)?;
+    if let Some(current) = &state.current {
+        check_out(repo, Oid::from_str(&current.hash)?)?;
+    }
+    Ok(state)
+}
+
+fn check_out(repo: &Repository, oid: Oid) -> Result<()> {
+    let commit = repo.find_commit(oid)?;
+    repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))?;
+    repo.set_head_detached(oid)?;
+    Ok(())
+}
+
+fn evaluate(repo: &Repository) -> Result<GitBisectState> {
+    let original_head = std::fs::read_to_string(repo.path().join(START_FILE))?.trim().to_string();
+    let bad = repo.find_reference(BAD_REF).ok().and_then(|reference| reference.target());
+    let good = marked_commits(repo, "good")?;
+    let skipped = marked_commits(repo, "skip")?;
+    let to_strings = |oids: &[Oid]| oids.iter().map(Oid::to_string).collect::<Vec<_>>();
+
+    let mut state = GitBisectState {
+        original_head,
+        bad: bad.map(|oid| oid.to_string()),
+        good: to_strings(&good),
+        skipped: to_strings(&skipped),
+        current: None,
+        remaining: 0,
+        remaining_steps: 0,
+        first_bad_commit: None,
+        possible_first_bad: Vec::new(),
+    };
+    let Some(bad) = bad.filter(|_| !good.is_empty()) else {
+        return Ok(state);
+    };
+
+    // Suspects are the commits the bad one contains and no good one does, children first
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL)?;
+    revwalk.push(bad)?;
+    for oid in &good {
+        revwalk.hide(*oid)?;
+    }
+    let suspects = revwalk.collect::<std::result::Result<Vec<_>, _>>()?;
+    if suspects.is_empty() {
+        return Err(Error::InvalidState(format!("the bad commit {} is an ancestor of a good commit", bad)));
+    }
+    state.remaining = suspects.len();
+    state.remaining_steps = estimate_steps(suspects.len());
+
+    let skipped: HashSet<Oid> = skipped.into_iter().collect();
+    match best_candidate(repo, &suspects, &skipped)? {
+        Some(candidate) => state.current = Some(convert_commit(&repo.find_commit(candidate)?)?),
+        None if suspects.iter().any(|oid| skipped.contains(oid)) => {
+            for oid in &suspects {
+                state.possible_first_bad.push(convert_commit(&repo.find_commit(*oid)?)?);
+            }
+        }
+        None => state.first_bad_commit = Some(convert_commit(&repo.find_commit(bad)?)?),
+    }
+    Ok(state)
+}
+
+/// Number of halvings needed to narrow `suspects` commits down to one.
+fn estimate_steps(suspects: usize) -> usize {
+    (usize::BITS - suspects.saturating_sub(1).leading_zeros()) as usize
+}
+
+/// The untested suspect whose ancestors make up closest to half of the suspects, so that
+/// either answer rules out as many commits as possible.
+fn best_candidate(repo: &Repository, suspects: &[Oid], skipped: &HashSet<Oid>) -> Result<Option<Oid>> {
+    let positions: HashMap<Oid, usize> = suspects.iter().enumerate().map(|(i, oid)| (*oid, i)).collect();
+    let words = (suspects.len() + 63) / 64;
+    let mut ancestors = vec![vec![0u64; words]; suspects.len()];
+
+    // Parents come after their children, so walking backwards sees every parent first
+    for i in (0..suspects.len()).rev() {
+        let mut bits = vec![0u64; words];
+        bits[i / 64] |= 1 << (i % 64);
+        for parent in repo.find_commit(suspects[i])?.parent_ids() {
+            if let Some(&p) = positions.get(&parent) {
+                for (word, parent_word) in bits.iter_mut().zip(&ancestors[p]) {
+                    *word |= parent_word;
+                }
+            }
+        }
+        ancestors[i] = bits;
+    }
+
+    // The bad commit itself needs no testing
+    let total = suspects.len();
+    Ok(suspects
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, oid)| !skipped.contains(oid))
+        .map(|(i, oid)| {
+            let count = ancestors[i].iter().map(|word| word.count_ones() as usize).sum::<usize>();
+            (count.min(total - count), *oid)
+        })
+        .fold(None, |best: Option<(usize, Oid)>, candidate| match best {
+            Some(best) if best.0 >= candidate.0 => Some(best),
+            _ => Some(candidate),
+        })
+        .map(|(_, oid)| oid))
+}
+
+/// Run the command in a shell from the top of the working tree and return its exit status.
+fn run_command(
+    repo: &Repository,
+    command: &str,
+    commit: &str,
+    operation: &Operation,
+    on_output: &dyn Fn(GitBisectOutput),
+) -> Result<i32> {
+    let mut child = shell_command(command)
+        .current_dir(repo.workdir().unwrap_or(repo.path()))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let (sender, receiver) = mpsc::channel();
+    let readers = [
+        read_lines(child.stdout.take(), GitHookStream::Stdout, sender.clone()),
+        read_lines(child.stderr.take(), GitHookStream::Stderr, sender),
+    ];
+    loop {
+        match receiver.recv_timeout(Duration::from_millis(100)) {
+            Ok((stream, line)) => on_output(GitBisectOutput {
+                commit: commit.to_string(),
+                stream,
+                line,
+            }),
+            Err(RecvTimeoutError::Timeout) if operation.is_cancelled() => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Error::Cancelled);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let status = child.wait()?;
+    status
+        .code()
+        .ok_or_else(|| Error::Bisect(format!("'{}' was terminated by a signal on {}", command, commit)))
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::commit_file;
+    use std::sync::Mutex;
+
+    /// Commit `state.txt` once per entry, returning the commits oldest first.
+    fn commit_history(repo: &Repository, states: &[&str]) -> Vec<Oid> {
+        states
+            .iter()
+            .enumerate()
+            .map(|(i, state)| commit_file(repo, "state.txt", &format!("{}\n", state), &format!("commit {}", i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_bisect_manual_and_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+        let commits = commit_history(&repo, &["ok", "ok", "ok", "skip", "ok", "ok", "bug", "bug", "bug", "bug"]);
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        let options = GitBisectStartOptions {
+            bad: None,
+            good: Some(vec![commits[0].to_string()]),
+        };
+        let mut state = manager.bisect_start(repo_path, options.clone()).unwrap();
+        assert_eq!(state.original_head, branch);
+        assert_eq!((state.remaining, state.remaining_steps), (9, 4));
+        assert!(matches!(repo.state(), git2::RepositoryState::Bisect));
+
+        // Test by hand, reading the file of each candidate checked out
+        while let Some(current) = &state.current {
+            assert_eq!(repo.head().unwrap().target().unwrap().to_string(), current.hash);
+            let mark = match std::fs::read_to_string(dir.path().join("state.txt")).unwrap().trim() {
+                "bug" => GitBisectMark::Bad,
+                "skip" => GitBisectMark::Skip,
+                _ => GitBisectMark::Good,
+            };
+            let options = GitBisectMarkOptions { mark, commit: None };
+            state = manager.bisect_mark(repo_path, options).unwrap();
+        }
+        assert_eq!(state.first_bad_commit.unwrap().hash, commits[6].to_string());
+        assert!(manager.bisect_state(repo_path).unwrap().unwrap().current.is_none());
+
+        manager.bisect_reset(repo_path).unwrap();
+        assert_eq!(repo.head().unwrap().shorthand(), Some(branch.as_str()));
+        assert!(manager.bisect_state(repo_path).unwrap().is_none());
+        assert!(repo.references_glob("refs/bisect/*").unwrap().next().is_none());
+
+        // Automated run: the command's exit status decides each mark
+        manager.bisect_start(repo_path, options).unwrap();
+        let output = Mutex::new(Vec::new());
+        let steps = Mutex::new(Vec::new());
+        let run = GitBisectRunOptions {
+            command: "cat state.txt; grep -q skip state.txt && exit 125; ! grep -q bug state.txt".to_string(),
+            operation_id: None,
+        };
+        let state = manager
+            .bisect_run(repo_path, run, |line| output.lock().unwrap().push(line), |step| steps.lock().unwrap().push(step))
+            .unwrap();
+        assert_eq!(state.first_bad_commit.unwrap().hash, commits[6].to_string());
+
+        let steps = steps.into_inner().unwrap();
+        assert!(!steps.is_empty() && steps.len() <= 5);
+        for step in &steps {
+            let expected = match commits.iter().position(|oid| oid.to_string() == step.commit).unwrap() {
+                3 => GitBisectMark::Skip,
+                6.. => GitBisectMark::Bad,
+                _ => GitBisectMark::Good,
+            };
+            assert_eq!(step.mark, expected);
+        }
+        let output = output.into_inner().unwrap();
+        assert!(output.iter().any(|line| line.stream == GitHookStream::Stdout && line.line == "bug"));
+
+        // With the only commit before the first bad one skipped, both remain suspects
+        manager.bisect_reset(repo_path).unwrap();
+        let options = GitBisectStartOptions {
+            bad: Some(commits[4].to_string()),
+            good: Some(vec![commits[2].to_string()]),
+        };
+        manager.bisect_start(repo_path, options).unwrap();
+        let skip = GitBisectMarkOptions {
+            mark: GitBisectMark::Skip,
+            commit: Some(commits[3].to_string()),
+        };
+        let state = manager.bisect_mark(repo_path, skip).unwrap();
+        assert!(state.current.is_none() && state.first_bad_commit.is_none());
+        assert_eq!(state.possible_first_bad.len(), 2);
+        manager.bisect_reset(repo_path).unwrap();
+    }
+}
@@ -0,0 +1,381 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+    repository::GitManager,
+    utils::*,
+};
+use chrono::Utc;
+use git2::{Blame, BlameOptions, Commit, DiffOptions, Oid, Patch, Repository};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::path::Path;
+
+const IGNORE_REVS_FILE: &str = ".git-blame-ignore-revs";
+/// How many ignored commits in a row a line is traced through before it stays attributed
+/// to the last one.
+const MAX_IGNORE_DEPTH: usize = 16;
+
+/// Where a line of the blamed file was last changed.
+#[derive(Debug, Clone)]
+struct BlamedLine {
+    commit: Oid,
+    path: String,
+    /// 1-based line number in `path` at `commit`
+    orig_line: usize,
+}
+
+impl GitManager {
+    /// Blame a file at a revision, or in the working tree when no revision is given.
+    pub fn blame(&self, repo_path: &str, file_path: &str, options: GitBlameOptions) -> Result<GitBlame> {
+        let repo = self.get_repo(repo_path)?;
+        let ignored = ignored_revisions(&repo, &options)?;
+
+        let (newest, contents) = match &options.revision {
+            Some(rev) => {
+                let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+                let entry = commit
+                    .tree()?
+                    .get_path(Path::new(file_path))
+                    .map_err(|_| Error::FileNotFound(file_path.to_string()))?;
+                let blob = repo.find_blob(entry.id())?;
+                (Some(commit.id()), blob.content().to_vec())
+            }
+            None => {
+                let workdir = repo.workdir()
+                    .ok_or_else(|| anyhow::anyhow!("Repository has no working directory"))?;
+                let contents = std::fs::read(workdir.join(file_path))
+                    .map_err(|_| Error::FileNotFound(file_path.to_string()))?;
+                (None, contents)
+            }
+        };
+
+        let mut blamed = match newest {
+            Some(oid) => blame_lines(&repo, file_path, Some(oid), &options, None)?,
+            None => blame_lines(&repo, file_path, None, &options, Some(&contents))?,
+        };
+        resolve_ignored(&repo, &mut blamed, &options, &ignored, &mut HashMap::new(), 0)?;
+
+        let content_lines: Vec<String> = String::from_utf8_lossy(&contents)
+            .lines()
+            .map(String::from)
+            .collect();
+
+        let mut metadata: HashMap<Oid, (String, GitAuthor)> = HashMap::new();
+        let mut hunks: Vec<GitBlameHunk> = Vec::new();
+        let mut lines = Vec::with_capacity(blamed.len());
+        let mut previous: Option<&BlamedLine> = None;
+
+        for (idx, line) in blamed.iter().enumerate() {
+            if let Entry::Vacant(entry) = metadata.entry(line.commit) {
+                entry.insert(commit_metadata(&repo, line.commit)?);
+            }
+            let (summary, author) = &metadata[&line.commit];
+
+            let continues_hunk = previous.is_some_and(|prev| {
+                prev.commit == line.commit && prev.path == line.path && prev.orig_line + 1 == line.orig_line
+            });
+            match hunks.last_mut() {
+                Some(hunk) if continues_hunk => hunk.line_count += 1,
+                _ => hunks.push(GitBlameHunk {
+                    commit: line.commit.to_string(),
+                    summary: summary.clone(),
+                    author: author.clone(),
+                    timestamp: author.timestamp,
+                    start_line: idx + 1,
+                    line_count: 1,
+                    orig_path: line.path.clone(),
+                    orig_start_line: line.orig_line,
+                }),
+            }
+            previous = Some(line);
+
+            lines.push(GitBlameLine {
+                commit: line.commit.to_string(),
+                author: author.name.clone(),
+                timestamp: author.timestamp,
+                line_number: (idx + 1) as u32,
+                content: content_lines.get(idx).cloned().unwrap_or_default(),
+            });
+        }
+
+        Ok(GitBlame {
+            path: file_path.to_string(),
+            revision: newest.map(|oid| oid.to_string()),
+            hunks,
+            lines,
+        })
+    }
+}
+
+/// Blame `path` as of `newest` (HEAD when unset), optionally against modified `buffer` contents.
+/// Lines that are not committed are attributed to the zero id.
+fn blame_lines(
+    repo: &Repository,
+    path: &str,
+    newest: Option<Oid>,
+    options: &GitBlameOptions,
+    buffer: Option<&[u8]>,
+) -> Result<Vec<BlamedLine>> {
+    let mut blame_opts = BlameOptions::new();
+    blame_opts.ignore_whitespace(options.ignore_whitespace.unwrap_or(false));
+    if options.detect_copies.unwrap_or(true) {
+        blame_opts
+            .track_copies_same_commit_moves(true)
+            .track_copies_same_commit_copies(true);
+    }
+    if let Some(oid) = newest {
+        blame_opts.newest_commit(oid);
+    }
+
+    let blame = match repo.blame_file(Path::new(path), Some(&mut blame_opts)) {
+        Ok(blame) => blame,
+        // A file that was never committed (or an unborn HEAD) is entirely uncommitted
+        Err(e) => {
+            let Some(buffer) = buffer else {
+                return Err(e.into());
+            };
+            return Ok((1..=count_lines(buffer))
+                .map(|orig_line| BlamedLine {
+                    commit: Oid::zero(),
+                    path: path.to_string(),
+                    orig_line,
+                })
+                .collect());
+        }
+    };
+
+    match buffer {
+        Some(buffer) => Ok(collect_blamed_lines(&blame.blame_buffer(buffer)?, path)),
+        None => Ok(collect_blamed_lines(&blame, path)),
+    }
+}
+
+fn collect_blamed_lines(blame: &Blame, path: &str) -> Vec<BlamedLine> {
+    let mut lines = Vec::new();
+    for hunk in blame.iter() {
+        let hunk_path = hunk
+            .path()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        for offset in 0..hunk.lines_in_hunk() {
+            lines.push(BlamedLine {
+                commit: hunk.final_commit_id(),
+                path: hunk_path.clone(),
+                orig_line: hunk.orig_start_line() + offset,
+            });
+        }
+    }
+    lines
+}
+
+/// Re-attribute lines blamed on ignored commits to the commit that last touched the
+/// corresponding line in the ignored commit's parent, like `git blame --ignore-rev`.
+fn resolve_ignored(
+    repo: &Repository,
+    lines: &mut [BlamedLine],
+    options: &GitBlameOptions,
+    ignored: &HashSet<Oid>,
+    cache: &mut HashMap<(Oid, String), Vec<BlamedLine>>,
+    depth: usize,
+) -> Result<()> {
+    if ignored.is_empty() || depth >= MAX_IGNORE_DEPTH {
+        return Ok(());
+    }
+
+    let targets: HashSet<(Oid, String)> = lines
+        .iter()
+        .filter(|line| ignored.contains(&line.commit))
+        .map(|line| (line.commit, line.path.clone()))
+        .collect();
+
+    for (oid, path) in targets {
+        let commit = repo.find_commit(oid)?;
+        let Ok(parent) = commit.parent(0) else {
+            continue;
+        };
+        let Some(line_map) = map_to_parent(repo, &commit, &parent, &path, options)? else {
+            continue;
+        };
+
+        let key = (parent.id(), path.clone());
+        if !cache.contains_key(&key) {
+            let mut parent_lines = blame_lines(repo, &path, Some(parent.id()), options, None)?;
+            resolve_ignored(repo, &mut parent_lines, options, ignored, cache, depth + 1)?;
+            cache.insert(key.clone(), parent_lines);
+        }
+        let parent_lines = &cache[&key];
+
+        for line in lines.iter_mut().filter(|line| line.commit == oid && line.path == path) {
+            let parent_line = line_map
+                .get(line.orig_line - 1)
+                .copied()
+                .flatten()
+                .and_then(|old_line| parent_lines.get(old_line - 1));
+            if let Some(parent_line) = parent_line {
+                *line = parent_line.clone();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// For every line of `path` in `commit`, the matching 1-based line in `parent`. Unchanged
+/// lines map directly; changed lines map to the line at the same offset of the replaced
+/// block, and added lines beyond it have no match. `None` when the parent lacks the file.
+fn map_to_parent(
+    repo: &Repository,
+    commit: &Commit,
+    parent: &Commit,
+    path: &str,
+    options: &GitBlameOptions,
+) -> Result<Option<Vec<Option<usize>>>> {
+    let Ok(old_entry) = parent.tree()?.get_path(Path::new(path)) else {
+        return Ok(None);
+    };
+    let new_entry = commit.tree()?.get_path(Path::new(path))?;
+    let old_blob = repo.find_blob(old_entry.id())?;
+    let new_blob = repo.find_blob(new_entry.id())?;
+
+    let mut diff_opts = DiffOptions::new();
+    diff_opts
+        .context_lines(0)
+        .ignore_whitespace(options.ignore_whitespace.unwrap_or(false));
+    let patch = Patch::from_blobs(&old_blob, None, &new_blob, None, Some(&mut diff_opts))?;
+
+    let new_len = count_lines(new_blob.content());
+    let mut map = vec![None; new_len];
+    let (mut old_line, mut new_line) = (1usize, 1usize);
+
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, _) = patch.hunk(hunk_idx)?;
+        let (old_lines, new_lines) = (hunk.old_lines() as usize, hunk.new_lines() as usize);
+        // Without context an empty side's start is the line before the change
+        let old_start = hunk.old_start() as usize + usize::from(old_lines == 0);
+        let new_start = hunk.new_start() as usize + usize::from(new_lines == 0);
+
+        while new_line < new_start && new_line <= new_len {
+            map[new_line - 1] = Some(old_line);
+            new_line += 1;
+            old_line += 1;
+        }
+        for offset in 0..new_lines.min(new_len + 1 - new_line) {
+            if offset < old_lines {
+                map[new_line - 1] = Some(old_start + offset);
+            }
+            new_line += 1;
+        }
+        old_line = old_start + old_lines;
+    }
+    while new_line <= new_len {
+        map[new_line - 1] = Some(old_line);
+        new_line += 1;
+        old_line += 1;
+    }
+
+    Ok(Some(map))
+}
+
+/// Revisions from `blame.ignoreRevsFile` (or `.git-blame-ignore-revs`) plus the explicit ones.
+fn ignored_revisions(repo: &Repository, options: &GitBlameOptions) -> Result<HashSet<Oid>> {
+    let mut revs = options.ignore_revs.clone().unwrap_or_default();
+
+    if options.use_ignore_revs_file.unwrap_or(true) {
+        if let Some(workdir) = repo.workdir() {
+            let file = repo
+                .config()?
+                .get_string("blame.ignoreRevsFile")
+                .unwrap_or_else(|_| IGNORE_REVS_FILE.to_string());
+            if let Ok(content) = std::fs::read_to_string(workdir.join(file)) {
+                revs.extend(
+                    content
+                        .lines()
+                        .map(|line| line.split('#').next().unwrap_or("").trim())
+                        .filter(|rev| !rev.is_empty())
+                        .map(String::from),
+                );
+            }
+        }
+    }
+
+    // Unknown revisions are skipped, as git does
+    Ok(revs
+        .iter()
+        .filter_map(|rev| repo.revparse_single(rev).and_then(|obj| obj.peel_to_commit()).ok())
+        .map(|commit| commit.id())
+        .collect())
+}
+
+fn commit_metadata(repo: &Repository, oid: Oid) -> Result<(String, GitAuthor)> {
+    if oid.is_zero() {
+        let author = GitAuthor {
+            name: "Not Committed Yet".to_string(),
+            email: String::new(),
+            timestamp: Utc::now(),
+        };
+        return Ok(("Not Committed Yet".to_string(), author));
+    }
+
+    let commit = repo.find_commit(oid)?;
+    let summary = commit.summary().unwrap_or("").to_string();
+    let author = convert_author(&commit.author())?;
+    Ok((summary, author))
+}
+
+fn count_lines(content: &[u8]) -> usize {
+    let newlines = content.iter().filter(|&&b| b == b'\n').count();
+    if content.last().is_some_and(|&b| b != b'\n') {
+        newlines + 1
+    } else {
+        newlines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_all, commit_files};
+
+    #[test]
+    fn test_blame_follows_renames_and_ignores_revs() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        let first = commit_files(&repo, &[("old.txt", "one\ntwo\nthree\n")], "add file");
+        std::fs::rename(dir.path().join("old.txt"), dir.path().join("new.txt")).unwrap();
+        let renamed = commit_all(&repo, "rename");
+        let second = commit_files(&repo, &[("new.txt", "one\ntwo\nthree\nfour\n")], "add four");
+        let reformat = commit_files(&repo, &[("new.txt", "ONE\ntwo\nthree\nfour\n")], "reformat");
+        std::fs::write(dir.path().join("new.txt"), "ONE\ntwo\nthree\nfour\nfive\n").unwrap();
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+
+        let blame = manager.blame(repo_path, "new.txt", GitBlameOptions::default()).unwrap();
+        let commits: Vec<_> = blame.lines.iter().map(|line| line.commit.clone()).collect();
+        assert_eq!(
+            commits,
+            vec![
+                reformat.to_string(),
+                first.to_string(),
+                first.to_string(),
+                second.to_string(),
+                Oid::zero().to_string(),
+            ]
+        );
+        assert_eq!(blame.hunks.len(), 4);
+        assert_eq!(blame.hunks[1].orig_path, "old.txt");
+        assert_eq!(blame.hunks[1].line_count, 2);
+        assert_eq!(blame.hunks[1].summary, "add file");
+
+        std::fs::write(dir.path().join(IGNORE_REVS_FILE), format!("# formatting\n{}\n", reformat)).unwrap();
+        let options = GitBlameOptions {
+            revision: Some("HEAD".to_string()),
+            ..Default::default()
+        };
+        let blame = manager.blame(repo_path, "new.txt", options).unwrap();
+        assert_eq!(blame.lines[0].commit, first.to_string());
+        assert_eq!(blame.lines[0].content, "ONE");
+        assert_eq!(blame.hunks.len(), 2);
+        assert!(blame.lines.iter().all(|line| line.commit != renamed.to_string()));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    error::{Error, Result},
+    repository::GitManager,
+};
+use git2::{Branch, BranchType, Oid, Repository};
+
+impl GitManager {
+    /// Delete a local branch. Without `force`, branches with commits that are neither in their
+    /// upstream nor in HEAD are refused, like `git branch -d`.
+    pub fn delete_branch(&self, repo_path: &str, branch_name: &str, force: bool) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let mut branch = find_local_branch(&repo, branch_name)?;
+        if branch.is_head() {
+            return Err(Error::InvalidState(format!("cannot delete the checked-out branch '{}'", branch_name)));
+        }
+
+        if !force {
+            if let Some(tip) = branch.get().target() {
+                let base = match branch.upstream() {
+                    Ok(upstream) => upstream.get().target(),
+                    Err(_) => repo.head().ok().and_then(|head| head.target()),
+                };
+                if !base.is_some_and(|base| is_merged_into(&repo, tip, base)) {
+                    return Err(Error::BranchNotMerged(branch_name.to_string()));
+                }
+            }
+        }
+
+        branch.delete()?;
+        Ok(())
+    }
+
+    /// Rename a local branch, carrying its upstream and HEAD along.
+    pub fn rename_branch(&self, repo_path: &str, branch_name: &str, new_name: &str, force: bool) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let mut branch = find_local_branch(&repo, branch_name)?;
+        branch.rename(new_name, force)?;
+        Ok(())
+    }
+
+    /// Set the upstream of `branch_name` (default: the current branch) to a remote-tracking or
+    /// local branch, or unset it when `upstream` is `None`.
+    pub fn set_upstream(&self, repo_path: &str, branch_name: Option<&str>, upstream: Option<&str>) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let mut branch = match branch_name {
+            Some(name) => find_local_branch(&repo, name)?,
+            None => {
+                let head = repo.head()?;
+                if !head.is_branch() {
+                    return Err(Error::InvalidState("HEAD is detached".to_string()));
+                }
+                Branch::wrap(head)
+            }
+        };
+
+        if let Some(upstream) = upstream {
+            let exists = repo.find_branch(upstream, BranchType::Remote).is_ok()
+                || repo.find_branch(upstream, BranchType::Local).is_ok();
+            if !exists {
+                return Err(Error::BranchNotFound(upstream.to_string()));
+            }
+        }
+        branch.set_upstream(upstream)?;
+        Ok(())
+    }
+}
+
+/// Whether every commit of `tip` is contained in `target`.
+pub(crate) fn is_merged_into(repo: &Repository, tip: Oid, target: Oid) -> bool {
+    tip == target || repo.graph_descendant_of(target, tip).unwrap_or(false)
+}
+
+pub(crate) fn find_local_branch<'r>(repo: &'r Repository, branch_name: &str) -> Result<Branch<'r>> {
+    repo.find_branch(branch_name, BranchType::Local)
+        .map_err(|_| Error::BranchNotFound(branch_name.to_string()))
+}
+
+/// The local name for a remote-tracking branch: `origin/feature/x` becomes `feature/x`.
+pub(crate) fn tracking_branch_name(repo: &Repository, remote_branch: &Branch) -> Result<String> {
+    let refname = remote_branch.get().name().unwrap_or("");
+    let remote = repo.branch_remote_name(refname)?;
+    let remote = remote.as_str().unwrap_or("");
+    let shorthand = remote_branch.name()?.unwrap_or("");
+    Ok(shorthand
+        .strip_prefix(&format!("{}/", remote))
+        .unwrap_or(shorthand)
+        .to_string())
+}
+
+/// The only remote-tracking branch called `branch_name` across all remotes, which
+/// `git checkout <name>` turns into a new tracking branch.
+pub(crate) fn unique_remote_branch<'r>(repo: &'r Repository, branch_name: &str) -> Result<Option<Branch<'r>>> {
+    let mut found = Vec::new();
+    for remote in repo.remotes()?.iter().flatten() {
+        if let Ok(branch) = repo.find_branch(&format!("{}/{}", remote, branch_name), BranchType::Remote) {
+            found.push(branch);
+        }
+    }
+    Ok(match found.len() {
+        1 => found.pop(),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+    use crate::test_support::commit_file;
+
+    #[test]
+    fn test_branch_lifecycle_and_tracking() {
+        let dir = tempfile::tempdir().unwrap();
+        let origin_dir = dir.path().join("origin");
+        let origin = Repository::init(&origin_dir).unwrap();
+        let base = commit_file(&origin, "a.txt", "base", "base");
+        origin.branch("feature", &origin.find_commit(base).unwrap(), false).unwrap();
+
+        let work_dir = dir.path().join("work");
+        let url = format!("file://{}", origin_dir.display());
+        let work = Repository::clone(&url, &work_dir).unwrap();
+        let default_branch = work.head().unwrap().shorthand().unwrap().to_string();
+        let manager = GitManager::new();
+        let repo_path = work_dir.to_str().unwrap();
+
+        // A remote branch is checked out as a new tracking branch, explicitly or by name alone
+        let options = GitCheckoutOptions {
+            track: Some("origin/feature".to_string()),
+            ..Default::default()
+        };
+        manager.checkout(repo_path, options).unwrap();
+        let branches = manager.get_branches(repo_path).unwrap();
+        let feature = branches.iter().find(|b| b.name == "feature").unwrap();
+        assert!(feature.is_current);
+        assert_eq!(feature.upstream.as_deref(), Some("origin/feature"));
+
+        manager.delete_branch(repo_path, &default_branch, false).unwrap();
+        let options = GitCheckoutOptions {
+            branch: Some(default_branch.clone()),
+            ..Default::default()
+        };
+        manager.checkout(repo_path, options).unwrap();
+        let current = manager.get_branches(repo_path).unwrap().into_iter().find(|b| b.is_current).unwrap();
+        assert_eq!(current.name, default_branch);
+        assert_eq!(current.upstream, Some(format!("origin/{}", default_branch)));
+
+        // Unmerged work is only deleted with force
+        manager.create_branch(repo_path, "topic", None).unwrap();
+        let options = GitCheckoutOptions {
+            branch: Some("topic".to_string()),
+            ..Default::default()
+        };
+        manager.checkout(repo_path, options).unwrap();
+        commit_file(&work, "b.txt", "topic work", "topic work");
+        let options = GitCheckoutOptions {
+            branch: Some(default_branch.clone()),
+            ..Default::default()
+        };
+        manager.checkout(repo_path, options).unwrap();
+        manager.create_branch(repo_path, "stale", None).unwrap();
+
+        let options = GitBranchListOptions {
+            merged_into: Some(default_branch.clone()),
+            ..Default::default()
+        };
+        let merged: Vec<_> = manager
+            .get_branches_with_options(repo_path, options)
+            .unwrap()
+            .into_iter()
+            .map(|b| b.name)
+            .collect();
+        assert!(merged.contains(&"stale".to_string()) && !merged.contains(&"topic".to_string()));
+
+        assert!(matches!(manager.delete_branch(repo_path, "topic", false), Err(Error::BranchNotMerged(_))));
+        manager.rename_branch(repo_path, "topic", "topic-renamed", false).unwrap();
+        manager.set_upstream(repo_path, Some("topic-renamed"), Some("origin/feature")).unwrap();
+        manager.set_upstream(repo_path, Some("topic-renamed"), None).unwrap();
+        manager.delete_branch(repo_path, "topic-renamed", true).unwrap();
+        manager.delete_branch(repo_path, "stale", false).unwrap();
+        assert!(matches!(manager.delete_branch(repo_path, &default_branch, true), Err(Error::InvalidState(_))));
+    }
+}
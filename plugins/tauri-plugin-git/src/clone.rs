@@ -0,0 +1,255 @@
+use crate::{
+    error::{Error, Result},
+    lfs::smudge_if_installed,
+    models::*,
+    remote::remote_callbacks,
+    repository::GitManager,
+    sparse::{apply_sparse, enable_sparse, in_cone},
+    submodule::update_recursive,
+};
+use git2::{
+    build::{CheckoutBuilder, RepoBuilder},
+    Config, Direction, FetchOptions, Remote, Repository, RepositoryInitOptions,
+};
+use std::path::Path;
+
+impl GitManager {
+    /// Clone `url` into `path`. Progress is reported through `on_progress`, and the clone can be
+    /// cancelled with `options.operation_id`.
+    pub fn clone_repository<F: Fn(GitTransferProgress)>(
+        &self,
+        url: &str,
+        path: &str,
+        options: GitCloneOptions,
+        on_progress: F,
+    ) -> Result<()> {
+        let operation = self.begin_operation(options.operation_id.as_deref());
+        let config = Config::open_default()?;
+        let credentials = options.credentials.as_ref();
+        let kind = GitRemoteOperation::Clone;
+
+        let bare = options.bare.unwrap_or(false);
+        let sparse = options.sparse_paths.as_ref().filter(|_| !bare);
+        let branch = match &options.branch {
+            Some(branch) => Some(branch.clone()),
+            // A single-branch clone needs the default branch name up front for its refspec
+            None if options.single_branch.unwrap_or(false) => {
+                Some(default_branch(url, remote_callbacks(&config, credentials, &operation, kind, &on_progress)).map_err(|e| operation.map_err(e))?)
+            }
+            None => None,
+        };
+
+        let mut fetch_opts = FetchOptions::new();
+        fetch_opts.remote_callbacks(remote_callbacks(&config, credentials, &operation, kind, &on_progress));
+        // libgit2's local transport cannot make shallow clones, so local sources get full history
+        if let Some(depth) = options.depth.filter(|_| !is_local_url(url)) {
+            fetch_opts.depth(depth as i32);
+        }
+
+        let mut builder = RepoBuilder::new();
+        builder.bare(bare).fetch_options(fetch_opts);
+        if let Some(branch) = &branch {
+            builder.branch(branch);
+        }
+        if let (Some(branch), true) = (&branch, options.single_branch.unwrap_or(false)) {
+            let refspec = format!("+refs/heads/{0}:refs/remotes/origin/{0}", branch);
+            builder.remote_create(move |repo, name, url| repo.remote_with_fetch(name, url, &refspec));
+        }
+        if sparse.is_some() {
+            // The sparse checkout decides which files to write once the clone is done
+            let mut checkout = CheckoutBuilder::new();
+            checkout.dry_run();
+            builder.with_checkout(checkout);
+        }
+
+        let repo = builder
+            .clone(url, Path::new(path))
+            .map_err(|e| operation.map_err(e))?;
+
+        let cone = match sparse {
+            Some(paths) => Some(checkout_sparse(&repo, paths)?),
+            None => None,
+        };
+        if !bare {
+            smudge_if_installed(&repo)?;
+        }
+
+        if options.recurse_submodules.unwrap_or(false) && !bare {
+            // Submodules outside a sparse cone stay unpopulated
+            let paths = cone.map(|dirs| {
+                repo.submodules()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|submodule| submodule.path().to_string_lossy().replace('\\', "/"))
+                    .filter(|sub_path| in_cone(&dirs, sub_path))
+                    .collect::<Vec<_>>()
+            });
+            let update_opts = GitSubmoduleUpdateOptions {
+                paths: None,
+                init: Some(true),
+                recursive: Some(true),
+                credentials: options.credentials.clone(),
+                operation_id: None,
+            };
+            update_recursive(&repo, paths.as_deref(), &update_opts, &operation, &on_progress)?;
+        }
+
+        Ok(())
+    }
+
+    /// Create an empty repository at `path`, like `git init`.
+    pub fn init_repository(&self, path: &str, options: GitInitOptions) -> Result<()> {
+        let mut init_opts = RepositoryInitOptions::new();
+        init_opts.bare(options.bare.unwrap_or(false)).mkpath(true);
+        if let Some(branch) = &options.initial_branch {
+            init_opts.initial_head(branch);
+        }
+        if let Some(template_dir) = &options.template_dir {
+            if !Path::new(template_dir).is_dir() {
+                return Err(Error::FileNotFound(template_dir.clone()));
+            }
+            init_opts.external_template(true).template_path(Path::new(template_dir));
+        }
+        Repository::init_opts(path, &init_opts)?;
+        Ok(())
+    }
+}
+
+fn is_local_url(url: &str) -> bool {
+    url.starts_with("file://") || (!url.contains("://") && Path::new(url).exists())
+}
+
+/// Name of the branch the remote's HEAD points at.
+fn default_branch(url: &str, callbacks: git2::RemoteCallbacks<'_>) -> std::result::Result<String, git2::Error> {
+    let mut remote = Remote::create_detached(url)?;
+    let connection = remote.connect_auth(Direction::Fetch, Some(callbacks), None)?;
+    let head = connection.default_branch()?;
+    let head = head.as_str().unwrap_or("");
+    head.strip_prefix("refs/heads/")
+        .map(String::from)
+        .ok_or_else(|| git2::Error::from_str("remote HEAD is not a branch"))
+}
+
+/// Populate the index of a clone that was made without a checkout, then write only the files
+/// inside the sparse cone. Returns the cone's directories as normalized by `enable_sparse`.
+fn checkout_sparse(repo: &Repository, paths: &[String]) -> Result<Vec<String>> {
+    let Ok(head) = repo.head() else {
+        // An empty remote has nothing to check out
+        return enable_sparse(repo, paths);
+    };
+    let tree = head.peel_to_tree()?;
+    let mut index = repo.index()?;
+    index.read_tree(&tree)?;
+    index.write()?;
+
+    let paths = enable_sparse(repo, paths)?;
+    apply_sparse(repo, Some(&paths))?;
+
+    let mut index = repo.index()?;
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+    for entry in index.iter() {
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        if in_cone(&paths, &path) {
+            checkout.path(path);
+        }
+    }
+    repo.checkout_index(Some(&mut index), Some(&mut checkout))?;
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::commit_all;
+
+    #[test]
+    fn test_clone_single_branch_sparse() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = GitManager::new();
+
+        let origin_dir = dir.path().join("origin");
+        let options = GitInitOptions {
+            initial_branch: Some("trunk".to_string()),
+            ..Default::default()
+        };
+        manager.init_repository(origin_dir.to_str().unwrap(), options).unwrap();
+        let origin = Repository::open(&origin_dir).unwrap();
+        for file in ["README.md", "apps/web/index.ts", "apps/api/main.rs", "libs/core/lib.rs"] {
+            let path = origin_dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "v1\n").unwrap();
+        }
+        commit_all(&origin, "first");
+        std::fs::write(origin_dir.join("README.md"), "v2\n").unwrap();
+        commit_all(&origin, "second");
+        let head = origin.head().unwrap().peel_to_commit().unwrap();
+        origin.branch("other", &head, false).unwrap();
+
+        let clone_dir = dir.path().join("clone");
+        let clone_path = clone_dir.to_str().unwrap();
+        let options = GitCloneOptions {
+            single_branch: Some(true),
+            sparse_paths: Some(vec!["apps/web".to_string()]),
+            ..Default::default()
+        };
+        let url = format!("file://{}", origin_dir.display());
+        manager.clone_repository(&url, clone_path, options, |_| {}).unwrap();
+
+        let clone = Repository::open(&clone_dir).unwrap();
+        assert_eq!(clone.head().unwrap().shorthand(), Some("trunk"));
+        assert!(clone.find_branch("origin/trunk", git2::BranchType::Remote).is_ok());
+        assert!(clone.find_branch("origin/other", git2::BranchType::Remote).is_err());
+
+        // Only top-level files and the cone are written, and the rest is not reported deleted
+        assert!(clone_dir.join("README.md").exists());
+        assert!(clone_dir.join("apps/web/index.ts").exists());
+        assert!(!clone_dir.join("apps/api").exists());
+        assert!(!clone_dir.join("libs").exists());
+        let status = manager.status(clone_path).unwrap();
+        assert!(status.staged.is_empty() && status.unstaged.is_empty() && status.untracked.is_empty());
+
+        let sparse = manager.set_sparse_checkout(clone_path, vec!["libs/core/".to_string()]).unwrap();
+        assert_eq!(sparse.paths, vec!["libs/core".to_string()]);
+        assert_eq!(manager.get_sparse_checkout(clone_path).unwrap().paths, sparse.paths);
+        assert!(clone_dir.join("libs/core/lib.rs").exists());
+        assert!(!clone_dir.join("apps").exists());
+
+        manager.disable_sparse_checkout(clone_path).unwrap();
+        assert!(clone_dir.join("apps/api/main.rs").exists());
+        assert!(manager.status(clone_path).unwrap().unstaged.is_empty());
+    }
+
+    #[test]
+    fn test_sparse_clone_populates_submodules_inside_the_cone() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib_dir = dir.path().join("lib");
+        let lib = Repository::init(&lib_dir).unwrap();
+        std::fs::write(lib_dir.join("lib.txt"), "lib\n").unwrap();
+        commit_all(&lib, "lib");
+
+        let origin_dir = dir.path().join("origin");
+        let origin = Repository::init(&origin_dir).unwrap();
+        let url = format!("file://{}", lib_dir.display());
+        for path in ["apps/web/vendor/lib", "libs/lib"] {
+            let mut submodule = origin.submodule(&url, Path::new(path), true).unwrap();
+            submodule.clone(None).unwrap();
+            submodule.add_finalize().unwrap();
+        }
+        commit_all(&origin, "add submodules");
+
+        let clone_dir = dir.path().join("clone");
+        let options = GitCloneOptions {
+            sparse_paths: Some(vec!["/apps/web/".to_string()]),
+            recurse_submodules: Some(true),
+            ..Default::default()
+        };
+        let origin_url = format!("file://{}", origin_dir.display());
+        GitManager::new()
+            .clone_repository(&origin_url, clone_dir.to_str().unwrap(), options, |_| {})
+            .unwrap();
+
+        assert!(clone_dir.join("apps/web/vendor/lib/lib.txt").exists());
+        assert!(!clone_dir.join("libs/lib/lib.txt").exists());
+    }
+}
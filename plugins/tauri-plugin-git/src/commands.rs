@@ -9,8 +9,9 @@ use crate::{
 pub async fn git_status<R: Runtime>(
     app: AppHandle<R>,
     repo_path: String,
+    options: Option<GitStatusOptions>,
 ) -> Result<GitStatus> {
-    app.git().status(&repo_path)
+    app.git().status_with_options(&repo_path, options.unwrap_or_default())
 }
 
 #[command]
@@ -22,6 +23,15 @@ pub async fn git_log<R: Runtime>(
     app.git().get_log(&repo_path, options)
 }
 
+#[command]
+pub async fn git_graph<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: Option<GitGraphOptions>,
+) -> Result<GitGraphPage> {
+    app.git().get_graph(&repo_path, options.unwrap_or_default())
+}
+
 #[command]
 pub async fn git_commit<R: Runtime>(
     app: AppHandle<R>,
@@ -65,12 +75,40 @@ pub async fn git_unstage_all<R: Runtime>(
     app.git().unstage_all(&repo_path)
 }
 
+#[command]
+pub async fn git_stage_hunk<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    selection: GitHunkSelection,
+) -> Result<()> {
+    app.git().stage_hunk(&repo_path, selection)
+}
+
+#[command]
+pub async fn git_unstage_hunk<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    selection: GitHunkSelection,
+) -> Result<()> {
+    app.git().unstage_hunk(&repo_path, selection)
+}
+
+#[command]
+pub async fn git_discard_hunk<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    selection: GitHunkSelection,
+) -> Result<()> {
+    app.git().discard_hunk(&repo_path, selection)
+}
+
 #[command]
 pub async fn git_branches<R: Runtime>(
     app: AppHandle<R>,
     repo_path: String,
+    options: Option<GitBranchListOptions>,
 ) -> Result<Vec<GitBranch>> {
-    app.git().get_branches(&repo_path)
+    app.git().get_branches(&repo_path, options.unwrap_or_default())
 }
 
 #[command]
@@ -83,6 +121,37 @@ pub async fn git_create_branch<R: Runtime>(
     app.git().create_branch(&repo_path, &branch_name, from_ref.as_deref())
 }
 
+#[command]
+pub async fn git_delete_branch<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    branch_name: String,
+    force: Option<bool>,
+) -> Result<()> {
+    app.git().delete_branch(&repo_path, &branch_name, force.unwrap_or(false))
+}
+
+#[command]
+pub async fn git_rename_branch<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    branch_name: String,
+    new_name: String,
+    force: Option<bool>,
+) -> Result<()> {
+    app.git().rename_branch(&repo_path, &branch_name, &new_name, force.unwrap_or(false))
+}
+
+#[command]
+pub async fn git_set_upstream<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    branch_name: Option<String>,
+    upstream: Option<String>,
+) -> Result<()> {
+    app.git().set_upstream(&repo_path, branch_name.as_deref(), upstream.as_deref())
+}
+
 #[command]
 pub async fn git_checkout<R: Runtime>(
     app: AppHandle<R>,
@@ -100,239 +169,716 @@ pub async fn git_remotes<R: Runtime>(
     app.git().get_remotes(&repo_path)
 }
 
-// Placeholder commands - to be implemented
 #[command]
 pub async fn git_diff<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _cached: bool,
+    app: AppHandle<R>,
+    repo_path: String,
+    cached: bool,
+    options: Option<GitDiffOptions>,
 ) -> Result<Vec<GitDiff>> {
-    Ok(vec![])
+    app.git().diff(&repo_path, cached, options.unwrap_or_default())
 }
 
 #[command]
 pub async fn git_diff_file<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _file_path: String,
-    _cached: bool,
+    app: AppHandle<R>,
+    repo_path: String,
+    file_path: String,
+    cached: bool,
 ) -> Result<GitDiff> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().diff_file(&repo_path, &file_path, cached)
 }
 
 #[command]
-pub async fn git_delete_branch<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _branch_name: String,
-    _force: bool,
-) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+pub async fn git_diff_commits<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    from_ref: String,
+    to_ref: String,
+    options: Option<GitDiffOptions>,
+) -> Result<Vec<GitDiff>> {
+    app.git().diff_commits(&repo_path, &from_ref, &to_ref, options.unwrap_or_default())
 }
 
 #[command]
-pub async fn git_merge<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _options: GitMergeOptions,
+pub async fn git_show_commit<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    commit: String,
+    options: Option<GitShowCommitOptions>,
+) -> Result<GitCommitDetails> {
+    app.git().show_commit(&repo_path, &commit, options.unwrap_or_default())
+}
+
+#[command]
+pub async fn git_add_remote<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    name: String,
+    url: String,
+) -> Result<GitRemote> {
+    app.git().add_remote(&repo_path, &name, &url)
+}
+
+#[command]
+pub async fn git_remove_remote<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    name: String,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().remove_remote(&repo_path, &name)
+}
+
+#[command]
+pub async fn git_rename_remote<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    name: String,
+    new_name: String,
+) -> Result<Vec<String>> {
+    app.git().rename_remote(&repo_path, &name, &new_name)
+}
+
+#[command]
+pub async fn git_set_remote_url<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    name: String,
+    options: GitRemoteUrlOptions,
+) -> Result<GitRemote> {
+    app.git().set_remote_url(&repo_path, &name, options)
+}
+
+#[command]
+pub async fn git_set_remote_refspecs<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    name: String,
+    refspecs: GitRemoteRefspecs,
+) -> Result<GitRemote> {
+    app.git().set_remote_refspecs(&repo_path, &name, refspecs)
+}
+
+#[command]
+pub async fn git_prune_remote<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    name: String,
+    options: GitRemotePruneOptions,
+) -> Result<Vec<String>> {
+    app.git().prune_remote(&repo_path, &name, options)
 }
 
 #[command]
 pub async fn git_fetch<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _options: GitFetchOptions,
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitFetchOptions,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().fetch(&repo_path, options)
 }
 
 #[command]
 pub async fn git_pull<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _options: GitPullOptions,
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitPullOptions,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().pull(&repo_path, options)
 }
 
 #[command]
 pub async fn git_push<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _options: GitPushOptions,
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitPushOptions,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().push(&repo_path, options)
 }
 
 #[command]
-pub async fn git_add_remote<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _name: String,
-    _url: String,
+pub async fn git_cancel_operation<R: Runtime>(
+    app: AppHandle<R>,
+    operation_id: String,
+) -> Result<bool> {
+    Ok(app.git().cancel_operation(&operation_id))
+}
+
+#[command]
+pub async fn git_merge<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitMergeOptions,
+) -> Result<GitMergeResult> {
+    app.git().merge(&repo_path, options)
+}
+
+#[command]
+pub async fn git_merge_continue<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    message: Option<String>,
+) -> Result<String> {
+    app.git().merge_continue(&repo_path, message)
+}
+
+#[command]
+pub async fn git_merge_abort<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().merge_abort(&repo_path)
 }
 
 #[command]
-pub async fn git_remove_remote<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _name: String,
+pub async fn git_rebase<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitRebaseOptions,
+) -> Result<GitRebaseResult> {
+    app.git().rebase(&repo_path, options)
+}
+
+#[command]
+pub async fn git_rebase_continue<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<GitRebaseResult> {
+    app.git().rebase_continue(&repo_path)
+}
+
+#[command]
+pub async fn git_rebase_skip<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<GitRebaseResult> {
+    app.git().rebase_skip(&repo_path)
+}
+
+#[command]
+pub async fn git_rebase_abort<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().rebase_abort(&repo_path)
 }
 
 #[command]
-pub async fn git_stash<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _options: GitStashOptions,
+pub async fn git_rebase_interactive<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitInteractiveRebaseOptions,
+) -> Result<GitSequencerState> {
+    app.git().interactive_rebase(&repo_path, options)
+}
+
+#[command]
+pub async fn git_reset<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitResetOptions,
+) -> Result<()> {
+    app.git().reset(&repo_path, options)
+}
+
+#[command]
+pub async fn git_reflog<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: Option<GitReflogOptions>,
+) -> Result<Vec<GitReflogEntry>> {
+    app.git().reflog(&repo_path, options.unwrap_or_default())
+}
+
+#[command]
+pub async fn git_undo_preview<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: Option<GitUndoOptions>,
+) -> Result<GitUndoPlan> {
+    app.git().undo_preview(&repo_path, options.unwrap_or_default())
+}
+
+#[command]
+pub async fn git_undo<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: Option<GitUndoOptions>,
+) -> Result<GitUndoPlan> {
+    app.git().undo(&repo_path, options.unwrap_or_default())
+}
+
+#[command]
+pub async fn git_bisect_start<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: Option<GitBisectStartOptions>,
+) -> Result<GitBisectState> {
+    app.git().bisect_start(&repo_path, options.unwrap_or_default())
+}
+
+#[command]
+pub async fn git_bisect_mark<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitBisectMarkOptions,
+) -> Result<GitBisectState> {
+    app.git().bisect_mark(&repo_path, options)
+}
+
+#[command]
+pub async fn git_bisect_state<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<Option<GitBisectState>> {
+    app.git().bisect_state(&repo_path)
+}
+
+#[command]
+pub async fn git_bisect_run<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitBisectRunOptions,
+) -> Result<GitBisectState> {
+    app.git().bisect_run(&repo_path, options)
+}
+
+#[command]
+pub async fn git_bisect_reset<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<()> {
+    app.git().bisect_reset(&repo_path)
+}
+
+#[command]
+pub async fn git_format_patch<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitFormatPatchOptions,
+) -> Result<GitPatchSeries> {
+    app.git().format_patch(&repo_path, options)
+}
+
+#[command]
+pub async fn git_apply_patch<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitApplyPatchOptions,
+) -> Result<GitApplyResult> {
+    app.git().apply_patch(&repo_path, options)
+}
+
+#[command]
+pub async fn git_am<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitAmOptions,
+) -> Result<Vec<GitAmPatch>> {
+    app.git().am(&repo_path, options)
+}
+
+#[command]
+pub async fn git_revert<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitRevertOptions,
+) -> Result<GitSequencerState> {
+    app.git().revert(&repo_path, options)
+}
+
+#[command]
+pub async fn git_cherry_pick<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitCherryPickOptions,
+) -> Result<GitSequencerState> {
+    app.git().cherry_pick(&repo_path, options)
+}
+
+#[command]
+pub async fn git_sequencer_continue<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<GitSequencerState> {
+    app.git().sequencer_continue(&repo_path)
+}
+
+#[command]
+pub async fn git_sequencer_skip<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<GitSequencerState> {
+    app.git().sequencer_skip(&repo_path)
+}
+
+#[command]
+pub async fn git_sequencer_abort<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().sequencer_abort(&repo_path)
+}
+
+#[command]
+pub async fn git_sequencer_state<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<Option<GitSequencerState>> {
+    app.git().sequencer_state(&repo_path)
+}
+
+#[command]
+pub async fn git_conflicts<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<Vec<GitConflict>> {
+    app.git().get_conflicts(&repo_path)
+}
+
+#[command]
+pub async fn git_resolve_conflict<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    file_path: String,
+    resolution: GitConflictResolution,
+) -> Result<()> {
+    app.git().resolve_conflict(&repo_path, &file_path, resolution)
+}
+
+#[command]
+pub async fn git_operation_state<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<GitOperationState> {
+    app.git().get_operation_state(&repo_path)
+}
+
+#[command]
+pub async fn git_stash<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitStashOptions,
+) -> Result<String> {
+    app.git().stash_save(&repo_path, options)
 }
 
 #[command]
 pub async fn git_stash_list<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
+    app: AppHandle<R>,
+    repo_path: String,
 ) -> Result<Vec<GitStashEntry>> {
-    Ok(vec![])
+    app.git().stash_list(&repo_path)
 }
 
 #[command]
 pub async fn git_stash_apply<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _stash_id: String,
+    app: AppHandle<R>,
+    repo_path: String,
+    stash_id: String,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().stash_apply(&repo_path, &stash_id)
 }
 
 #[command]
 pub async fn git_stash_pop<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _stash_id: String,
+    app: AppHandle<R>,
+    repo_path: String,
+    stash_id: String,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().stash_pop(&repo_path, &stash_id)
 }
 
 #[command]
 pub async fn git_stash_drop<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _stash_id: String,
+    app: AppHandle<R>,
+    repo_path: String,
+    stash_id: String,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().stash_drop(&repo_path, &stash_id)
 }
 
 #[command]
-pub async fn git_reset<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _options: GitResetOptions,
-) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+pub async fn git_stash_show<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    stash_id: String,
+    options: Option<GitDiffOptions>,
+) -> Result<Vec<GitDiff>> {
+    app.git().stash_show(&repo_path, &stash_id, options.unwrap_or_default())
 }
 
 #[command]
-pub async fn git_revert<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _commit: String,
-) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+pub async fn git_blame<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    file_path: String,
+    options: Option<GitBlameOptions>,
+) -> Result<GitBlame> {
+    app.git().blame(&repo_path, &file_path, options.unwrap_or_default())
 }
 
 #[command]
-pub async fn git_cherry_pick<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _commit: String,
-) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+pub async fn git_search_history<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitSearchOptions,
+) -> Result<Vec<GitSearchMatch>> {
+    app.git().search_history(&repo_path, options)
 }
 
 #[command]
 pub async fn git_tags<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
+    app: AppHandle<R>,
+    repo_path: String,
 ) -> Result<Vec<GitTag>> {
-    Ok(vec![])
+    app.git().get_tags(&repo_path)
 }
 
 #[command]
 pub async fn git_create_tag<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _tag_name: String,
-    _message: Option<String>,
-) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app: AppHandle<R>,
+    repo_path: String,
+    tag_name: String,
+    message: Option<String>,
+    options: Option<GitCreateTagOptions>,
+) -> Result<String> {
+    app.git().create_tag(&repo_path, &tag_name, message, options.unwrap_or_default())
 }
 
 #[command]
 pub async fn git_delete_tag<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _tag_name: String,
+    app: AppHandle<R>,
+    repo_path: String,
+    tag_name: String,
+) -> Result<()> {
+    app.git().delete_tag(&repo_path, &tag_name)
+}
+
+#[command]
+pub async fn git_push_tags<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitTagPushOptions,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().push_tags(&repo_path, options)
+}
+
+#[command]
+pub async fn git_worktrees<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<Vec<GitWorktree>> {
+    app.git().list_worktrees(&repo_path)
+}
+
+#[command]
+pub async fn git_worktree_add<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: GitWorktreeAddOptions,
+) -> Result<GitWorktree> {
+    app.git().add_worktree(&repo_path, options)
+}
+
+#[command]
+pub async fn git_worktree_lock<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    worktree: String,
+    reason: Option<String>,
+) -> Result<()> {
+    app.git().lock_worktree(&repo_path, &worktree, reason.as_deref())
+}
+
+#[command]
+pub async fn git_worktree_unlock<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    worktree: String,
+) -> Result<()> {
+    app.git().unlock_worktree(&repo_path, &worktree)
+}
+
+#[command]
+pub async fn git_worktree_prune<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<Vec<String>> {
+    app.git().prune_worktrees(&repo_path)
+}
+
+#[command]
+pub async fn git_worktree_remove<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    worktree: String,
+    force: Option<bool>,
+) -> Result<()> {
+    app.git().remove_worktree(&repo_path, &worktree, force.unwrap_or(false))
+}
+
+#[command]
+pub async fn git_submodules<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<Vec<GitSubmodule>> {
+    app.git().list_submodules(&repo_path)
+}
+
+#[command]
+pub async fn git_submodule_init<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    paths: Option<Vec<String>>,
+) -> Result<()> {
+    app.git().init_submodules(&repo_path, paths)
+}
+
+#[command]
+pub async fn git_submodule_update<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: Option<GitSubmoduleUpdateOptions>,
+) -> Result<()> {
+    app.git().update_submodules(&repo_path, options.unwrap_or_default())
+}
+
+#[command]
+pub async fn git_submodule_sync<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    paths: Option<Vec<String>>,
+    recursive: Option<bool>,
+) -> Result<()> {
+    app.git().sync_submodules(&repo_path, paths, recursive.unwrap_or(false))
+}
+
+#[command]
+pub async fn git_watch<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    options: Option<GitWatchOptions>,
+) -> Result<String> {
+    app.git().watch_repository(&repo_path, options.unwrap_or_default())
+}
+
+#[command]
+pub async fn git_unwatch<R: Runtime>(
+    app: AppHandle<R>,
+    watch_id: String,
+) -> Result<bool> {
+    Ok(app.git().unwatch_repository(&watch_id))
 }
 
 #[command]
 pub async fn git_clone<R: Runtime>(
-    _app: AppHandle<R>,
-    _url: String,
-    _path: String,
+    app: AppHandle<R>,
+    url: String,
+    path: String,
+    options: Option<GitCloneOptions>,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().clone_repository(&url, &path, options.unwrap_or_default())
 }
 
 #[command]
 pub async fn git_init<R: Runtime>(
-    _app: AppHandle<R>,
-    _path: String,
+    app: AppHandle<R>,
+    path: String,
+    options: Option<GitInitOptions>,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().init_repository(&path, options.unwrap_or_default())
+}
+
+#[command]
+pub async fn git_sparse_checkout<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<GitSparseCheckout> {
+    app.git().get_sparse_checkout(&repo_path)
+}
+
+#[command]
+pub async fn git_sparse_checkout_set<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    paths: Vec<String>,
+) -> Result<GitSparseCheckout> {
+    app.git().set_sparse_checkout(&repo_path, paths)
+}
+
+#[command]
+pub async fn git_sparse_checkout_disable<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<()> {
+    app.git().disable_sparse_checkout(&repo_path)
 }
 
 #[command]
 pub async fn git_config_get<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _key: String,
-) -> Result<String> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app: AppHandle<R>,
+    repo_path: String,
+    key: String,
+    scope: Option<GitConfigScope>,
+) -> Result<Option<String>> {
+    app.git().config_get(&repo_path, &key, scope)
+}
+
+#[command]
+pub async fn git_config_get_all<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    key: String,
+    scope: Option<GitConfigScope>,
+) -> Result<Vec<GitConfigEntry>> {
+    app.git().config_get_all(&repo_path, &key, scope)
+}
+
+#[command]
+pub async fn git_config_list<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    scope: Option<GitConfigScope>,
+) -> Result<Vec<GitConfigEntry>> {
+    app.git().config_list(&repo_path, scope)
 }
 
 #[command]
 pub async fn git_config_set<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _key: String,
-    _value: String,
+    app: AppHandle<R>,
+    repo_path: String,
+    key: String,
+    value: GitConfigValue,
+    options: Option<GitConfigSetOptions>,
 ) -> Result<()> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+    app.git().config_set(&repo_path, &key, value, options.unwrap_or_default())
 }
 
 #[command]
-pub async fn git_blame<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _file_path: String,
-) -> Result<GitBlame> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
+pub async fn git_config_unset<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    key: String,
+    options: Option<GitConfigUnsetOptions>,
+) -> Result<()> {
+    app.git().config_unset(&repo_path, &key, options.unwrap_or_default())
 }
 
 #[command]
-pub async fn git_show_commit<R: Runtime>(
-    _app: AppHandle<R>,
-    _repo_path: String,
-    _commit: String,
-) -> Result<GitCommit> {
-    Err(crate::error::Error::Generic(anyhow::anyhow!("Not implemented")))
-}
\ No newline at end of file
+pub async fn git_config<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+) -> Result<GitConfig> {
+    app.git().get_config(&repo_path)
+}
+
+#[command]
+pub async fn git_config_update<R: Runtime>(
+    app: AppHandle<R>,
+    repo_path: String,
+    config: GitConfig,
+    scope: Option<GitConfigScope>,
+) -> Result<GitConfig> {
+    app.git().update_config(&repo_path, config, scope)
+}
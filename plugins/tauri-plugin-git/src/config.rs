@@ -0,0 +1,320 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+    repository::GitManager,
+};
+use git2::{Config, ConfigLevel, ErrorCode, Repository};
+use std::path::Path;
+
+/// Never matches, so `set_multivar` appends instead of replacing (git's `--add` uses the same).
+const MATCH_NOTHING: &str = "a^";
+
+impl GitManager {
+    /// The value of `key`, the last one for multi-valued keys. `None` when it is not set.
+    pub fn config_get(&self, repo_path: &str, key: &str, scope: Option<GitConfigScope>) -> Result<Option<String>> {
+        let repo = self.get_repo(repo_path)?;
+        let config = read_config(&repo, scope)?;
+        not_found_as_none(config.get_string(key))
+    }
+
+    /// Every value of a multi-valued key such as `remote.origin.fetch`, in file order.
+    pub fn config_get_all(&self, repo_path: &str, key: &str, scope: Option<GitConfigScope>) -> Result<Vec<GitConfigEntry>> {
+        let repo = self.get_repo(repo_path)?;
+        let config = read_config(&repo, scope)?;
+        let entries = match config.multivar(key, None) {
+            Ok(entries) => collect_entries(entries, scope),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        };
+        entries
+    }
+
+    /// All entries of one scope, or of every scope in precedence order.
+    pub fn config_list(&self, repo_path: &str, scope: Option<GitConfigScope>) -> Result<Vec<GitConfigEntry>> {
+        let repo = self.get_repo(repo_path)?;
+        let config = read_config(&repo, scope)?;
+        let entries = config.entries(None)?;
+        collect_entries(entries, scope)
+    }
+
+    pub fn config_set(&self, repo_path: &str, key: &str, value: GitConfigValue, options: GitConfigSetOptions) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let mut config = write_config(&repo, options.scope)?;
+
+        let pattern = match (options.append.unwrap_or(false), &options.value_pattern) {
+            (true, _) => Some(MATCH_NOTHING),
+            (false, Some(pattern)) => Some(pattern.as_str()),
+            (false, None) => None,
+        };
+        match (pattern, value) {
+            (Some(pattern), value) => config.set_multivar(key, pattern, &value_string(&value))?,
+            (None, GitConfigValue::Bool(value)) => config.set_bool(key, value)?,
+            (None, GitConfigValue::Int(value)) => config.set_i64(key, value)?,
+            (None, GitConfigValue::String(value)) => config.set_str(key, &value)?,
+        }
+        Ok(())
+    }
+
+    pub fn config_unset(&self, repo_path: &str, key: &str, options: GitConfigUnsetOptions) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let mut config = write_config(&repo, options.scope)?;
+        match (&options.value_pattern, options.all.unwrap_or(false)) {
+            (Some(pattern), _) => config.remove_multivar(key, pattern)?,
+            (None, true) => config.remove_multivar(key, ".*")?,
+            (None, false) => config.remove(key)?,
+        }
+        Ok(())
+    }
+
+    /// The settings shown on the settings page, as seen by git commands in this repository.
+    pub fn get_config(&self, repo_path: &str) -> Result<GitConfig> {
+        let repo = self.get_repo(repo_path)?;
+        let config = repo.config()?.snapshot()?;
+        let string = |key: &str| not_found_as_none(config.get_string(key));
+        let boolean = |key: &str| not_found_as_none(config.get_bool(key));
+
+        Ok(GitConfig {
+            user: GitUserConfig {
+                name: string("user.name")?,
+                email: string("user.email")?,
+                signing_key: string("user.signingkey")?,
+            },
+            commit: GitCommitConfig {
+                gpg_sign: boolean("commit.gpgsign")?,
+                template: string("commit.template")?,
+            },
+            pull: GitPullConfig {
+                // `merges` and `interactive` rebase as well
+                rebase: string("pull.rebase")?.map(|value| Config::parse_bool(value).unwrap_or(true)),
+                ff: string("pull.ff")?,
+            },
+            push: GitPushConfig {
+                default: string("push.default")?,
+            },
+            core: GitCoreConfig {
+                editor: string("core.editor")?,
+                autocrlf: string("core.autocrlf")?,
+                ignorecase: boolean("core.ignorecase")?,
+            },
+        })
+    }
+
+    /// Write the settings that are set in `update` and differ from the current ones to `scope`
+    /// (default: local). Returns the resulting settings.
+    pub fn update_config(&self, repo_path: &str, update: GitConfig, scope: Option<GitConfigScope>) -> Result<GitConfig> {
+        let current = self.get_config(repo_path)?;
+        let repo = self.get_repo(repo_path)?;
+        let mut config = write_config(&repo, scope)?;
+
+        let strings = [
+            ("user.name", current.user.name, update.user.name),
+            ("user.email", current.user.email, update.user.email),
+            ("user.signingkey", current.user.signing_key, update.user.signing_key),
+            ("commit.template", current.commit.template, update.commit.template),
+            ("pull.ff", current.pull.ff, update.pull.ff),
+            ("push.default", current.push.default, update.push.default),
+            ("core.editor", current.core.editor, update.core.editor),
+            ("core.autocrlf", current.core.autocrlf, update.core.autocrlf),
+        ];
+        for (key, current, wanted) in strings {
+            if let Some(wanted) = wanted.filter(|wanted| current.as_ref() != Some(wanted)) {
+                config.set_str(key, &wanted)?;
+            }
+        }
+
+        let bools = [
+            ("commit.gpgsign", current.commit.gpg_sign, update.commit.gpg_sign),
+            ("pull.rebase", current.pull.rebase, update.pull.rebase),
+            ("core.ignorecase", current.core.ignorecase, update.core.ignorecase),
+        ];
+        for (key, current, wanted) in bools {
+            if let Some(wanted) = wanted.filter(|wanted| current != Some(*wanted)) {
+                config.set_bool(key, wanted)?;
+            }
+        }
+
+        self.get_config(repo_path)
+    }
+}
+
+/// The merged configuration, or a single file of it. Missing files read as empty.
+fn read_config(repo: &Repository, scope: Option<GitConfigScope>) -> Result<Config> {
+    let Some(scope) = scope else {
+        return Ok(repo.config()?);
+    };
+    match scope_config(repo, scope) {
+        Ok(Some(config)) => Ok(config),
+        Ok(None) => Ok(Config::new()?),
+        Err(e) => Err(e),
+    }
+}
+
+/// The file writes go to; the local config when no scope is given.
+fn write_config(repo: &Repository, scope: Option<GitConfigScope>) -> Result<Config> {
+    let scope = scope.unwrap_or(GitConfigScope::Local);
+    if let Some(config) = scope_config(repo, scope)? {
+        return Ok(config);
+    }
+
+    match scope {
+        // Like git, create ~/.gitconfig on first write
+        GitConfigScope::Global => {
+            let home = std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .ok_or_else(|| Error::InvalidConfig("cannot locate the home directory".to_string()))?;
+            Ok(Config::open(&Path::new(&home).join(".gitconfig"))?)
+        }
+        GitConfigScope::Worktree => Ok(Config::open(&repo.path().join("config.worktree"))?),
+        _ => Err(Error::InvalidConfig(format!("no {} config file", scope_name(scope)))),
+    }
+}
+
+/// The existing file for `scope`, or `None` when there is none yet.
+fn scope_config(repo: &Repository, scope: GitConfigScope) -> Result<Option<Config>> {
+    let merged = repo.config()?;
+    let level = |level: ConfigLevel| not_found_as_none(merged.open_level(level));
+
+    match scope {
+        GitConfigScope::System => level(ConfigLevel::System),
+        GitConfigScope::Global => match level(ConfigLevel::Global)? {
+            Some(config) => Ok(Some(config)),
+            None => level(ConfigLevel::XDG),
+        },
+        GitConfigScope::Local => level(ConfigLevel::Local),
+        GitConfigScope::Worktree => {
+            // Without the extension git ignores config.worktree entirely
+            if !merged.get_bool("extensions.worktreeConfig").unwrap_or(false) {
+                return Err(Error::InvalidConfig(
+                    "worktree config needs extensions.worktreeConfig to be enabled".to_string(),
+                ));
+            }
+            let path = repo.path().join("config.worktree");
+            match path.exists() {
+                true => Ok(Some(Config::open(&path)?)),
+                false => Ok(None),
+            }
+        }
+    }
+}
+
+fn collect_entries(mut entries: git2::ConfigEntries<'_>, scope: Option<GitConfigScope>) -> Result<Vec<GitConfigEntry>> {
+    let mut collected = Vec::new();
+    while let Some(entry) = entries.next() {
+        let entry = entry?;
+        collected.push(GitConfigEntry {
+            name: entry.name().unwrap_or("").to_string(),
+            value: entry.value().map(String::from),
+            // Files opened on their own report their level as local
+            scope: scope.or_else(|| level_scope(entry.level())),
+        });
+    }
+    Ok(collected)
+}
+
+fn level_scope(level: ConfigLevel) -> Option<GitConfigScope> {
+    match level {
+        ConfigLevel::ProgramData | ConfigLevel::System => Some(GitConfigScope::System),
+        ConfigLevel::XDG | ConfigLevel::Global => Some(GitConfigScope::Global),
+        ConfigLevel::Local => Some(GitConfigScope::Local),
+        ConfigLevel::Worktree => Some(GitConfigScope::Worktree),
+        ConfigLevel::App | ConfigLevel::Highest => None,
+    }
+}
+
+fn scope_name(scope: GitConfigScope) -> &'static str {
+    match scope {
+        GitConfigScope::System => "system",
+        GitConfigScope::Global => "global",
+        GitConfigScope::Local => "local",
+        GitConfigScope::Worktree => "worktree",
+    }
+}
+
+fn value_string(value: &GitConfigValue) -> String {
+    match value {
+        GitConfigValue::Bool(value) => value.to_string(),
+        GitConfigValue::Int(value) => value.to_string(),
+        GitConfigValue::String(value) => value.clone(),
+    }
+}
+
+fn not_found_as_none<T>(result: std::result::Result<T, git2::Error>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_and_multi_valued_config() {
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init(dir.path()).unwrap();
+        let repo_path = dir.path().to_str().unwrap();
+        let manager = GitManager::new();
+        let local = || GitConfigSetOptions::default();
+
+        manager
+            .config_set(repo_path, "pull.rebase", GitConfigValue::String("merges".to_string()), local())
+            .unwrap();
+        manager.config_set(repo_path, "core.ignorecase", GitConfigValue::Bool(true), local()).unwrap();
+        assert_eq!(
+            manager.config_get(repo_path, "core.ignorecase", Some(GitConfigScope::Local)).unwrap().as_deref(),
+            Some("true")
+        );
+        assert_eq!(manager.config_get(repo_path, "test.missing", None).unwrap(), None);
+
+        // Multi-valued keys append and can be removed by pattern
+        for refspec in ["+refs/heads/*:refs/remotes/origin/*", "+refs/tags/*:refs/tags/*"] {
+            let options = GitConfigSetOptions {
+                append: Some(true),
+                ..Default::default()
+            };
+            manager
+                .config_set(repo_path, "remote.origin.fetch", GitConfigValue::String(refspec.to_string()), options)
+                .unwrap();
+        }
+        let values = manager.config_get_all(repo_path, "remote.origin.fetch", None).unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[1].scope, Some(GitConfigScope::Local));
+        let options = GitConfigUnsetOptions {
+            value_pattern: Some("tags".to_string()),
+            ..Default::default()
+        };
+        manager.config_unset(repo_path, "remote.origin.fetch", options).unwrap();
+        assert_eq!(manager.config_get_all(repo_path, "remote.origin.fetch", None).unwrap().len(), 1);
+
+        // Worktree config is refused until the extension is on
+        let options = GitConfigSetOptions {
+            scope: Some(GitConfigScope::Worktree),
+            ..Default::default()
+        };
+        let value = GitConfigValue::String("Worktree User".to_string());
+        assert!(matches!(
+            manager.config_set(repo_path, "user.name", value.clone(), options.clone()),
+            Err(Error::InvalidConfig(_))
+        ));
+        manager
+            .config_set(repo_path, "extensions.worktreeConfig", GitConfigValue::Bool(true), local())
+            .unwrap();
+        manager.config_set(repo_path, "user.name", value, options).unwrap();
+        let worktree = manager.config_list(repo_path, Some(GitConfigScope::Worktree)).unwrap();
+        assert_eq!(worktree.len(), 1);
+        assert_eq!(worktree[0].scope, Some(GitConfigScope::Worktree));
+
+        // The typed view only writes what changed
+        let mut config = manager.get_config(repo_path).unwrap();
+        assert_eq!(config.user.name.as_deref(), Some("Worktree User"));
+        assert_eq!(config.pull.rebase, Some(true));
+        config.push.default = Some("simple".to_string());
+        let updated = manager.update_config(repo_path, config, None).unwrap();
+        assert_eq!(updated.push.default.as_deref(), Some("simple"));
+        let local_config = manager.config_list(repo_path, Some(GitConfigScope::Local)).unwrap();
+        assert!(local_config.iter().any(|entry| entry.name == "pull.rebase" && entry.value.as_deref() == Some("merges")));
+        assert!(!local_config.iter().any(|entry| entry.name == "user.name"));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+    repository::GitManager,
+    utils::*,
+};
+use git2::{FileFavor, Index, IndexConflict, IndexEntry, MergeOptions, Oid, Repository, Tree};
+use std::path::Path;
+
+/// Name of the lone entry in the scratch trees used for union merges.
+const UNION_ENTRY_NAME: &str = "file";
+
+impl GitManager {
+    pub fn get_conflicts(&self, repo_path: &str) -> Result<Vec<GitConflict>> {
+        let repo = self.get_repo(repo_path)?;
+        let index = repo.index()?;
+        collect_conflicts(&repo, &index)
+    }
+
+    /// Resolve a conflicted path with our version, their version or a union of both,
+    /// writing the result to the working tree and staging it.
+    pub fn resolve_conflict(&self, repo_path: &str, file_path: &str, resolution: GitConflictResolution) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let workdir = repo.workdir()
+            .ok_or_else(|| anyhow::anyhow!("Repository has no working directory"))?
+            .to_path_buf();
+        let mut index = repo.index()?;
+
+        let mut conflict = None;
+        for entry in index.conflicts()? {
+            let entry = entry?;
+            if conflict_path(&entry).as_deref() == Some(file_path) {
+                conflict = Some(entry);
+                break;
+            }
+        }
+        let conflict = conflict.ok_or_else(|| Error::FileNotFound(file_path.to_string()))?;
+
+        let resolved = match resolution {
+            GitConflictResolution::Ours => conflict.our.as_ref().map(|e| e.id),
+            GitConflictResolution::Theirs => conflict.their.as_ref().map(|e| e.id),
+            GitConflictResolution::Both => union_merge(&repo, &conflict)?,
+        };
+
+        let full_path = workdir.join(file_path);
+        match resolved {
+            Some(oid) => {
+                let blob = repo.find_blob(oid)?;
+                if let Some(parent) = full_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&full_path, blob.content())?;
+                index.add_path(Path::new(file_path))?;
+            }
+            None => {
+                if full_path.exists() {
+                    std::fs::remove_file(&full_path)?;
+                }
+                index.remove_path(Path::new(file_path))?;
+            }
+        }
+
+        index.write()?;
+        Ok(())
+    }
+
+    pub fn get_operation_state(&self, repo_path: &str) -> Result<GitOperationState> {
+        let mut repo = self.get_repo(repo_path)?;
+
+        let mut merge_heads = Vec::new();
+        if repo.state() == git2::RepositoryState::Merge {
+            repo.mergehead_foreach(|oid| {
+                merge_heads.push(oid.to_string());
+                true
+            })?;
+        }
+
+        let rebase = match repo.open_rebase(None) {
+            Ok(mut rebase) => Some(GitRebaseProgress {
+                current: rebase.operation_current(),
+                total: rebase.len(),
+                orig_head: rebase.orig_head_id().map(|oid| oid.to_string()),
+                orig_head_name: rebase.orig_head_name().map(String::from),
+            }),
+            Err(_) => None,
+        };
+
+        let index = repo.index()?;
+        let conflicted = collect_conflicts(&repo, &index)?
+            .into_iter()
+            .map(|conflict| conflict.path)
+            .collect();
+
+        Ok(GitOperationState {
+            state: convert_repository_state(repo.state()),
+            merge_heads,
+            rebase,
+            conflicted,
+        })
+    }
+}
+
+pub(crate) fn collect_conflicts(repo: &Repository, index: &Index) -> Result<Vec<GitConflict>> {
+    let mut conflicts = Vec::new();
+    if !index.has_conflicts() {
+        return Ok(conflicts);
+    }
+
+    for entry in index.conflicts()? {
+        let entry = entry?;
+        let path = conflict_path(&entry).unwrap_or_default();
+        let ancestor = conflict_side(repo, entry.ancestor.as_ref())?;
+        let ours = conflict_side(repo, entry.our.as_ref())?;
+        let theirs = conflict_side(repo, entry.their.as_ref())?;
+        let is_binary = [&ancestor, &ours, &theirs]
+            .iter()
+            .any(|side| matches!(side, Some(GitConflictSide { content: None, .. })));
+
+        conflicts.push(GitConflict {
+            path,
+            ancestor,
+            ours,
+            theirs,
+            is_binary,
+        });
+    }
+
+    Ok(conflicts)
+}
+
+fn conflict_path(conflict: &IndexConflict) -> Option<String> {
+    conflict
+        .our
+        .as_ref()
+        .or(conflict.their.as_ref())
+        .or(conflict.ancestor.as_ref())
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+}
+
+fn conflict_side(repo: &Repository, entry: Option<&IndexEntry>) -> Result<Option<GitConflictSide>> {
+    let Some(entry) = entry else {
+        return Ok(None);
+    };
+
+    let blob = repo.find_blob(entry.id)?;
+    let content = if blob.is_binary() {
+        None
+    } else {
+        Some(String::from_utf8_lossy(blob.content()).to_string())
+    };
+
+    Ok(Some(GitConflictSide {
+        path: String::from_utf8_lossy(&entry.path).to_string(),
+        oid: entry.id.to_string(),
+        content,
+    }))
+}
+
+/// Merge both sides keeping the lines of each (`merge=union`). When one side deleted the
+/// file the surviving side wins.
+fn union_merge(repo: &Repository, conflict: &IndexConflict) -> Result<Option<Oid>> {
+    let (ours, theirs) = match (&conflict.our, &conflict.their) {
+        (Some(ours), Some(theirs)) => (ours, theirs),
+        (Some(side), None) | (None, Some(side)) => return Ok(Some(side.id)),
+        (None, None) => return Ok(None),
+    };
+
+    let ancestor_tree = single_entry_tree(repo, conflict.ancestor.as_ref())?;
+    let our_tree = single_entry_tree(repo, Some(ours))?;
+    let their_tree = single_entry_tree(repo, Some(theirs))?;
+
+    let mut merge_opts = MergeOptions::new();
+    merge_opts.file_favor(FileFavor::Union);
+    let merged = repo.merge_trees(&ancestor_tree, &our_tree, &their_tree, Some(&merge_opts))?;
+    if merged.has_conflicts() {
+        return Err(Error::MergeConflict);
+    }
+
+    let entry = merged
+        .get_path(Path::new(UNION_ENTRY_NAME), 0)
+        .ok_or(Error::MergeConflict)?;
+    Ok(Some(entry.id))
+}
+
+fn single_entry_tree<'r>(repo: &'r Repository, entry: Option<&IndexEntry>) -> Result<Tree<'r>> {
+    let mut builder = repo.treebuilder(None)?;
+    if let Some(entry) = entry {
+        builder.insert(UNION_ENTRY_NAME, entry.id, entry.mode as i32)?;
+    }
+    Ok(repo.find_tree(builder.write()?)?)
+}
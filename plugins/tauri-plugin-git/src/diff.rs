@@ -0,0 +1,370 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+    lfs::is_lfs_path,
+    repository::GitManager,
+    utils::*,
+};
+use git2::{Diff, DiffFindOptions, DiffLineType as Git2DiffLineType, DiffOptions, Patch, Repository};
+
+impl GitManager {
+    /// Diff the working tree against the index, or the index against HEAD when `cached` is set.
+    pub fn diff(&self, repo_path: &str, cached: bool, options: GitDiffOptions) -> Result<Vec<GitDiff>> {
+        let repo = self.get_repo(repo_path)?;
+        let mut diff = diff_workdir_or_index(&repo, cached, &options)?;
+        collect_diffs(&repo, &mut diff, &options)
+    }
+
+    pub fn diff_file(&self, repo_path: &str, file_path: &str, cached: bool) -> Result<GitDiff> {
+        let options = GitDiffOptions {
+            paths: Some(vec![file_path.to_string()]),
+            include_untracked: Some(true),
+            ..Default::default()
+        };
+
+        self.diff(repo_path, cached, options)?
+            .into_iter()
+            .find(|diff| diff.new_path == file_path || diff.old_path == file_path)
+            .ok_or_else(|| Error::FileNotFound(file_path.to_string()))
+    }
+
+    /// Diff two arbitrary revisions (`from` is the old side, `to` the new side).
+    pub fn diff_commits(&self, repo_path: &str, from_ref: &str, to_ref: &str, options: GitDiffOptions) -> Result<Vec<GitDiff>> {
+        let repo = self.get_repo(repo_path)?;
+        let old_tree = repo.revparse_single(from_ref)?.peel_to_tree()?;
+        let new_tree = repo.revparse_single(to_ref)?.peel_to_tree()?;
+
+        let mut diff_opts = build_diff_options(&options);
+        let mut diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut diff_opts))?;
+        collect_diffs(&repo, &mut diff, &options)
+    }
+
+    /// Everything about one commit, compared against its parent (or the empty tree for a root
+    /// commit), like `git show --stat`.
+    pub fn show_commit(&self, repo_path: &str, commit_ref: &str, options: GitShowCommitOptions) -> Result<GitCommitDetails> {
+        let repo = self.get_repo(repo_path)?;
+        let commit = repo
+            .revparse_single(commit_ref)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(|_| Error::CommitNotFound(commit_ref.to_string()))?;
+
+        let parent_tree = if commit.parent_count() == 0 {
+            None
+        } else {
+            let index = options.parent.unwrap_or(0);
+            let parent = commit
+                .parent(index)
+                .map_err(|_| Error::CommitNotFound(format!("{}^{}", commit_ref, index + 1)))?;
+            Some(parent.tree()?)
+        };
+
+        let diff_options = options.diff_options.unwrap_or_default();
+        let mut diff_opts = build_diff_options(&diff_options);
+        let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), Some(&mut diff_opts))?;
+        find_similar(&mut diff, &diff_options)?;
+
+        let include_diff = options.include_diff.unwrap_or(false);
+        let mut files = Vec::with_capacity(diff.deltas().len());
+        let mut file_stats = Vec::with_capacity(diff.deltas().len());
+        let mut diffs = Vec::new();
+        for (idx, delta) in diff.deltas().enumerate() {
+            let change = create_file_change_from_delta(&delta);
+            let is_lfs = is_lfs_path(&repo, &change.path);
+            let mut stats = GitFileStats {
+                path: change.path.clone(),
+                additions: 0,
+                deletions: 0,
+                is_binary: is_lfs || delta.flags().is_binary(),
+            };
+            if let Some(patch) = Patch::from_diff(&diff, idx)? {
+                if !is_lfs {
+                    let (_, additions, deletions) = patch.line_stats()?;
+                    stats.additions = additions;
+                    stats.deletions = deletions;
+                    stats.is_binary = patch.delta().flags().is_binary();
+                }
+                if include_diff {
+                    diffs.push(convert_patch(&patch, is_lfs)?);
+                }
+            }
+            files.push(change);
+            file_stats.push(stats);
+        }
+
+        let stats = GitDiffStats {
+            additions: file_stats.iter().map(|file| file.additions).sum(),
+            deletions: file_stats.iter().map(|file| file.deletions).sum(),
+            files_changed: file_stats.len(),
+        };
+        let message = commit.message().unwrap_or("");
+        let trailers = git2::message_trailers_strs(message)?
+            .iter()
+            .map(|(key, value)| GitTrailer {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+
+        let mut details = GitCommitDetails {
+            commit: convert_commit(&commit)?,
+            summary: commit.summary().unwrap_or("").to_string(),
+            body: commit.body().map(|body| body.trim_end().to_string()),
+            trailers,
+            file_stats,
+            stats,
+            diff: include_diff.then_some(diffs),
+        };
+        details.commit.files = Some(files);
+        Ok(details)
+    }
+}
+
+pub(crate) fn build_diff_options(options: &GitDiffOptions) -> DiffOptions {
+    let mut diff_opts = DiffOptions::new();
+    diff_opts
+        .context_lines(options.context_lines.unwrap_or(3))
+        .ignore_whitespace(options.ignore_whitespace.unwrap_or(false));
+
+    if let Some(paths) = &options.paths {
+        for path in paths {
+            diff_opts.pathspec(path);
+        }
+        diff_opts.disable_pathspec_match(true);
+    }
+
+    diff_opts
+}
+
+pub(crate) fn diff_workdir_or_index<'a>(repo: &'a Repository, cached: bool, options: &GitDiffOptions) -> Result<Diff<'a>> {
+    let mut diff_opts = build_diff_options(options);
+
+    let diff = if cached {
+        // An unborn HEAD diffs the index against the empty tree
+        let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+        repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut diff_opts))?
+    } else {
+        if options.include_untracked.unwrap_or(false) {
+            diff_opts
+                .include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true);
+        }
+        repo.diff_index_to_workdir(None, Some(&mut diff_opts))?
+    };
+
+    Ok(diff)
+}
+
+/// Run rename/copy detection and convert every delta of `diff` into the plugin's diff model.
+pub(crate) fn collect_diffs(repo: &Repository, diff: &mut Diff, options: &GitDiffOptions) -> Result<Vec<GitDiff>> {
+    find_similar(diff, options)?;
+
+    let mut diffs = Vec::with_capacity(diff.deltas().len());
+    for idx in 0..diff.deltas().len() {
+        if let Some(patch) = Patch::from_diff(diff, idx)? {
+            let path = patch.delta().new_file().path().map(|p| p.to_string_lossy().to_string());
+            let is_lfs = path.is_some_and(|path| is_lfs_path(repo, &path));
+            diffs.push(convert_patch(&patch, is_lfs)?);
+        }
+    }
+
+    Ok(diffs)
+}
+
+pub(crate) fn find_similar(diff: &mut Diff, options: &GitDiffOptions) -> Result<()> {
+    if options.detect_renames.unwrap_or(true) || options.detect_copies.unwrap_or(false) {
+        let mut find_opts = DiffFindOptions::new();
+        find_opts
+            .renames(options.detect_renames.unwrap_or(true))
+            .copies(options.detect_copies.unwrap_or(false))
+            .for_untracked(options.include_untracked.unwrap_or(false));
+        diff.find_similar(Some(&mut find_opts))?;
+    }
+    Ok(())
+}
+
+/// Convert a patch into the diff model. LFS files only differ in their pointer, so like binary
+/// files they get no hunks or line counts.
+pub(crate) fn convert_patch(patch: &Patch, is_lfs: bool) -> Result<GitDiff> {
+    let delta = patch.delta();
+    let old_path = delta.old_file().path()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let new_path = delta.new_file().path()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let is_binary = is_lfs || delta.flags().is_binary();
+
+    let mut hunks = Vec::new();
+    if !is_binary {
+        for hunk_idx in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_idx)?;
+            let mut lines = Vec::with_capacity(line_count);
+
+            for line_idx in 0..line_count {
+                let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+                if is_eofnl_marker(line.origin_value()) {
+                    continue;
+                }
+
+                lines.push(GitDiffLine {
+                    line_type: convert_diff_line_type(line.origin_value()),
+                    content: String::from_utf8_lossy(line.content())
+                        .trim_end_matches('\n')
+                        .to_string(),
+                    old_line_number: line.old_lineno(),
+                    new_line_number: line.new_lineno(),
+                });
+            }
+
+            hunks.push(GitDiffHunk {
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+                lines,
+            });
+        }
+    }
+
+    let (additions, deletions) = match is_lfs {
+        true => (0, 0),
+        false => {
+            let (_, additions, deletions) = patch.line_stats()?;
+            (additions, deletions)
+        }
+    };
+
+    Ok(GitDiff {
+        old_path,
+        new_path,
+        status: convert_delta_to_file_status(delta.status()),
+        is_binary,
+        is_lfs,
+        hunks,
+        stats: GitDiffStats {
+            additions,
+            deletions,
+            files_changed: 1,
+        },
+    })
+}
+
+/// The "\ No newline at end of file" markers are emitted by libgit2 as separate lines;
+/// they are not part of the file content so the diff model leaves them out.
+pub(crate) fn is_eofnl_marker(line_type: Git2DiffLineType) -> bool {
+    matches!(
+        line_type,
+        Git2DiffLineType::ContextEOFNL | Git2DiffLineType::AddEOFNL | Git2DiffLineType::DeleteEOFNL
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::commit_all;
+    use git2::IndexAddOption;
+
+    #[test]
+    fn test_diff_renames_binary_and_missing_eof_newline() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+
+        std::fs::write(dir.path().join("old.txt"), "a\nb\nc\nd\ne\nf\n").unwrap();
+        std::fs::write(dir.path().join("tail.txt"), "one\ntwo").unwrap();
+        std::fs::write(dir.path().join("image.bin"), [0u8, 1, 2, 3]).unwrap();
+        let base = commit_all(&repo, "Initial commit");
+
+        std::fs::rename(dir.path().join("old.txt"), dir.path().join("new.txt")).unwrap();
+        std::fs::write(dir.path().join("tail.txt"), "one\ntwo\nthree\n").unwrap();
+        std::fs::write(dir.path().join("image.bin"), [0u8, 9, 8, 7]).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], IndexAddOption::DEFAULT, None).unwrap();
+        index.update_all(["*"], None).unwrap();
+        index.write().unwrap();
+
+        // The rename only shows up once both sides are in the index
+        let cached = manager.diff(repo_path, true, GitDiffOptions::default()).unwrap();
+        let renamed = cached.iter().find(|diff| diff.new_path == "new.txt").unwrap();
+        assert!(matches!(renamed.status, FileStatus::Renamed));
+        assert_eq!(renamed.old_path, "old.txt");
+        assert!(renamed.hunks.is_empty());
+        let options = GitDiffOptions {
+            detect_renames: Some(false),
+            ..Default::default()
+        };
+        let without_renames = manager.diff(repo_path, true, options).unwrap();
+        assert!(without_renames.iter().any(|diff| diff.old_path == "old.txt" && matches!(diff.status, FileStatus::Deleted)));
+
+        let binary = cached.iter().find(|diff| diff.new_path == "image.bin").unwrap();
+        assert!(binary.is_binary && binary.hunks.is_empty());
+
+        // The "\ No newline at end of file" marker is not a line of its own
+        let tail = cached.iter().find(|diff| diff.new_path == "tail.txt").unwrap();
+        let lines = &tail.hunks[0].lines;
+        let contents: Vec<_> = lines.iter().map(|line| line.content.as_str()).collect();
+        assert_eq!(contents, ["one", "two", "two", "three"]);
+        assert!(matches!(lines[1].line_type, DiffLineType::Delete));
+        assert!(matches!(lines[2].line_type, DiffLineType::Add));
+        assert_eq!((tail.stats.additions, tail.stats.deletions), (2, 1));
+
+        let head = commit_all(&repo, "Rework files");
+        let committed = manager.diff_commits(repo_path, &base.to_string(), &head.to_string(), GitDiffOptions::default()).unwrap();
+        assert_eq!(committed.len(), 3);
+        assert!(committed.iter().any(|diff| diff.old_path == "old.txt" && diff.new_path == "new.txt"));
+        assert!(manager.diff(repo_path, false, GitDiffOptions::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_show_commit_with_stats_and_trailers() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+
+        std::fs::write(dir.path().join("keep.txt"), "one\ntwo\nthree\n").unwrap();
+        std::fs::write(dir.path().join("old.txt"), "a\nb\nc\nd\ne\nf\n").unwrap();
+        let root = commit_all(&repo, "Initial commit");
+
+        let details = manager.show_commit(repo_path, &root.to_string(), GitShowCommitOptions::default()).unwrap();
+        assert_eq!(details.stats.files_changed, 2);
+        assert_eq!(details.stats.additions, 9);
+        assert!(details.body.is_none() && details.diff.is_none());
+
+        std::fs::write(dir.path().join("keep.txt"), "one\n2\nthree\n").unwrap();
+        std::fs::rename(dir.path().join("old.txt"), dir.path().join("new.txt")).unwrap();
+        std::fs::write(dir.path().join("image.bin"), [0u8, 159, 146, 150]).unwrap();
+        let message = "Rework files\n\nLonger explanation.\n\nFixes: #12\nCo-authored-by: Ann <ann@example.com>\nSigned-off-by: Test <test@example.com>\n";
+        commit_all(&repo, message);
+
+        let options = GitShowCommitOptions {
+            include_diff: Some(true),
+            ..Default::default()
+        };
+        let details = manager.show_commit(repo_path, "HEAD", options).unwrap();
+        assert_eq!(details.commit.message, message);
+        assert_eq!(details.summary, "Rework files");
+        assert!(details.body.as_deref().unwrap().starts_with("Longer explanation."));
+        let keys: Vec<_> = details.trailers.iter().map(|trailer| trailer.key.as_str()).collect();
+        assert_eq!(keys, vec!["Fixes", "Co-authored-by", "Signed-off-by"]);
+        assert_eq!(details.trailers[0].value, "#12");
+
+        let files = details.commit.files.as_ref().unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(details.file_stats.len(), 3);
+        let renamed = files.iter().find(|file| file.path == "new.txt").unwrap();
+        assert_eq!(renamed.old_path.as_deref(), Some("old.txt"));
+        let keep = details.file_stats.iter().find(|file| file.path == "keep.txt").unwrap();
+        assert_eq!((keep.additions, keep.deletions), (1, 1));
+        assert!(details.file_stats.iter().find(|file| file.path == "image.bin").unwrap().is_binary);
+        assert_eq!(details.diff.as_ref().unwrap().len(), 3);
+
+        assert!(matches!(
+            manager.show_commit(repo_path, "no-such-ref", GitShowCommitOptions::default()),
+            Err(Error::CommitNotFound(_))
+        ));
+    }
+}
@@ -11,24 +11,75 @@ pub enum Error {
     #[error("Branch not found: {0}")]
     BranchNotFound(String),
     
+    #[error("Branch not fully merged: {0}")]
+    BranchNotMerged(String),
+    
     #[error("Remote not found: {0}")]
     RemoteNotFound(String),
     
     #[error("Commit not found: {0}")]
     CommitNotFound(String),
     
+    #[error("Worktree not found: {0}")]
+    WorktreeNotFound(String),
+    
     #[error("File not found: {0}")]
     FileNotFound(String),
     
+    #[error("Invalid patch selection: {0}")]
+    InvalidSelection(String),
+    
     #[error("Merge conflict")]
     MergeConflict,
     
+    #[error("Invalid repository state: {0}")]
+    InvalidState(String),
+    
+    #[error("Invalid rebase plan: {0}")]
+    InvalidRebasePlan(String),
+    
+    #[error("Cannot fast-forward: {0}")]
+    NotFastForward(String),
+    
+    #[error("Push rejected: {0}")]
+    PushRejected(String),
+    
+    #[error("Operation cancelled")]
+    Cancelled,
+    
     #[error("Uncommitted changes")]
     UncommittedChanges,
     
+    #[error("Invalid author: {0}")]
+    InvalidAuthor(String),
+    
+    #[error("Signing failed: {0}")]
+    Signing(String),
+    
+    #[error("Invalid date: {0}")]
+    InvalidDate(String),
+    
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
     
+    #[error("Hook failed: {0}")]
+    Hook(String),
+    
+    #[error("Git LFS error: {0}")]
+    Lfs(String),
+    
+    #[error("Bisect failed: {0}")]
+    Bisect(String),
+    
+    #[error("Invalid search: {0}")]
+    InvalidSearch(String),
+    
+    #[error("Invalid patch: {0}")]
+    InvalidPatch(String),
+    
+    #[error("File watcher error: {0}")]
+    Watcher(#[from] notify::Error),
+    
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     
@@ -0,0 +1,496 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+    repository::GitManager,
+    utils::*,
+};
+use chrono::{DateTime, NaiveDate};
+use git2::{Commit, Oid, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+const DEFAULT_PAGE_SIZE: usize = 200;
+
+/// Where a graph page stopped: the commit each lane is waiting for and the starting points
+/// that have not been reached yet. Together they are exactly the unvisited part of the walk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GraphCursor {
+    lanes: Vec<Option<String>>,
+    pending: Vec<String>,
+}
+
+/// Commit filters shared by the log and graph endpoints.
+pub(crate) struct CommitFilter {
+    author: Option<String>,
+    grep: Option<String>,
+    since: Option<i64>,
+    until: Option<i64>,
+    path: Option<String>,
+}
+
+impl CommitFilter {
+    pub(crate) fn new(
+        author: Option<&str>,
+        grep: Option<&str>,
+        since: Option<&str>,
+        until: Option<&str>,
+        path: Option<&str>,
+    ) -> Result<Self> {
+        Ok(Self {
+            author: author.map(str::to_lowercase),
+            grep: grep.map(str::to_lowercase),
+            since: since.map(|date| parse_date(date, false)).transpose()?,
+            until: until.map(|date| parse_date(date, true)).transpose()?,
+            path: path.map(|p| p.trim_end_matches('/').to_string()).filter(|p| !p.is_empty()),
+        })
+    }
+
+    pub(crate) fn matches(&self, commit: &Commit) -> Result<bool> {
+        let time = commit.time().seconds();
+        if self.since.is_some_and(|since| time < since) || self.until.is_some_and(|until| time > until) {
+            return Ok(false);
+        }
+
+        if let Some(author) = &self.author {
+            let signature = commit.author();
+            let name = signature.name().unwrap_or("").to_lowercase();
+            let email = signature.email().unwrap_or("").to_lowercase();
+            if !name.contains(author) && !email.contains(author) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(grep) = &self.grep {
+            if !commit.message().unwrap_or("").to_lowercase().contains(grep) {
+                return Ok(false);
+            }
+        }
+
+        match &self.path {
+            Some(path) => touches_path(commit, path),
+            None => Ok(true),
+        }
+    }
+}
+
+impl GitManager {
+    /// One page of the commit graph in topological order, with lane assignments for drawing
+    /// and the refs pointing at each commit.
+    pub fn get_graph(&self, repo_path: &str, options: GitGraphOptions) -> Result<GitGraphPage> {
+        let repo = self.get_repo(repo_path)?;
+        let filter = CommitFilter::new(
+            options.author.as_deref(),
+            options.grep.as_deref(),
+            options.since.as_deref(),
+            options.until.as_deref(),
+            options.path.as_deref(),
+        )?;
+        let limit = options.limit.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+        let (mut lanes, mut pending) = match &options.cursor {
+            Some(cursor) => {
+                let cursor: GraphCursor = serde_json::from_str(cursor)
+                    .map_err(|_| Error::InvalidState("invalid graph cursor".to_string()))?;
+                let lanes = cursor
+                    .lanes
+                    .iter()
+                    .map(|lane| lane.as_deref().map(Oid::from_str).transpose())
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                let pending = cursor
+                    .pending
+                    .iter()
+                    .map(|oid| Oid::from_str(oid))
+                    .collect::<std::result::Result<HashSet<_>, _>>()?;
+                (lanes, pending)
+            }
+            None => (Vec::new(), start_points(&repo, &options)?),
+        };
+
+        for oid in lanes.iter().flatten().chain(pending.iter()) {
+            revwalk.push(*oid)?;
+        }
+
+        let decorations = ref_decorations(&repo)?;
+        let mut nodes = Vec::new();
+        let mut walk = revwalk.peekable();
+
+        while nodes.len() < limit {
+            let Some(oid) = walk.next() else {
+                break;
+            };
+            let oid = oid?;
+            pending.remove(&oid);
+            let commit = repo.find_commit(oid)?;
+            let parents: Vec<Oid> = commit.parent_ids().collect();
+
+            if !filter.matches(&commit)? {
+                // Hidden commits hand their lanes over to their parents
+                skip_commit(&mut lanes, oid, &parents);
+                continue;
+            }
+
+            let before = lanes.clone();
+            let (column, incoming, outgoing) = place_commit(&mut lanes, oid, &parents);
+            let pass_through = (0..before.len().min(lanes.len()))
+                .filter(|&lane| {
+                    before[lane].is_some()
+                        && before[lane] == lanes[lane]
+                        && !incoming.contains(&lane)
+                        && !outgoing.contains(&lane)
+                })
+                .collect();
+
+            nodes.push(GitGraphNode {
+                commit: convert_commit(&commit)?,
+                column,
+                incoming,
+                outgoing,
+                pass_through,
+                refs: decorations.get(&oid).cloned().unwrap_or_default(),
+            });
+        }
+
+        let has_more = walk.peek().is_some();
+        let next_cursor = if has_more {
+            let cursor = GraphCursor {
+                lanes: lanes.iter().map(|lane| lane.map(|oid| oid.to_string())).collect(),
+                pending: pending.iter().map(|oid| oid.to_string()).collect(),
+            };
+            Some(serde_json::to_string(&cursor).map_err(|e| Error::Generic(e.into()))?)
+        } else {
+            None
+        };
+
+        Ok(GitGraphPage { nodes, next_cursor })
+    }
+}
+
+/// The commits a fresh walk starts from; unborn HEAD yields none.
+fn start_points(repo: &Repository, options: &GitGraphOptions) -> Result<HashSet<Oid>> {
+    let mut starts = HashSet::new();
+
+    if options.all.unwrap_or(false) {
+        for reference in repo.references()? {
+            if let Ok(commit) = reference?.peel_to_commit() {
+                starts.insert(commit.id());
+            }
+        }
+    }
+
+    match &options.revisions {
+        Some(revisions) => {
+            for rev in revisions {
+                let commit = repo
+                    .revparse_single(rev)
+                    .and_then(|obj| obj.peel_to_commit())
+                    .map_err(|_| Error::CommitNotFound(rev.clone()))?;
+                starts.insert(commit.id());
+            }
+        }
+        None => {
+            if let Ok(head) = repo.head().and_then(|head| head.peel_to_commit()) {
+                starts.insert(head.id());
+            }
+        }
+    }
+
+    Ok(starts)
+}
+
+/// Put `oid` in the lane that expects it (or a free one), end any other lanes converging on
+/// it and route its parents into lanes. Returns the node column and its incoming and
+/// outgoing lanes.
+fn place_commit(lanes: &mut Vec<Option<Oid>>, oid: Oid, parents: &[Oid]) -> (usize, Vec<usize>, Vec<usize>) {
+    let incoming: Vec<usize> = (0..lanes.len()).filter(|&lane| lanes[lane] == Some(oid)).collect();
+    let column = incoming.first().copied().unwrap_or_else(|| free_lane(lanes));
+    for &lane in &incoming {
+        lanes[lane] = None;
+    }
+
+    let mut outgoing = Vec::with_capacity(parents.len());
+    for (idx, parent) in parents.iter().enumerate() {
+        let lane = match lanes.iter().position(|lane| *lane == Some(*parent)) {
+            // Another lane already leads to this parent; join it
+            Some(lane) => lane,
+            None if idx == 0 => column,
+            None => free_lane(lanes),
+        };
+        lanes[lane] = Some(*parent);
+        if !outgoing.contains(&lane) {
+            outgoing.push(lane);
+        }
+    }
+
+    trim_lanes(lanes);
+    (column, incoming, outgoing)
+}
+
+fn skip_commit(lanes: &mut Vec<Option<Oid>>, oid: Oid, parents: &[Oid]) {
+    let waiting: Vec<usize> = (0..lanes.len()).filter(|&lane| lanes[lane] == Some(oid)).collect();
+    if waiting.is_empty() {
+        // A hidden starting point: its parents still have to be reached, from this page or
+        // through the cursor
+        if let Some(first) = parents.first().filter(|first| !lanes.contains(&Some(**first))) {
+            let lane = free_lane(lanes);
+            lanes[lane] = Some(*first);
+        }
+    }
+
+    // Every waiting lane continues to the first parent so they still converge where it is shown
+    for &lane in &waiting {
+        lanes[lane] = parents.first().copied();
+    }
+    for parent in parents.iter().skip(1) {
+        if !lanes.contains(&Some(*parent)) {
+            let lane = free_lane(lanes);
+            lanes[lane] = Some(*parent);
+        }
+    }
+    trim_lanes(lanes);
+}
+
+fn free_lane(lanes: &mut Vec<Option<Oid>>) -> usize {
+    match lanes.iter().position(Option::is_none) {
+        Some(lane) => lane,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+fn trim_lanes(lanes: &mut Vec<Option<Oid>>) {
+    while lanes.last() == Some(&None) {
+        lanes.pop();
+    }
+}
+
+fn ref_decorations(repo: &Repository) -> Result<HashMap<Oid, Vec<GitRefDecoration>>> {
+    let mut decorations: HashMap<Oid, Vec<GitRefDecoration>> = HashMap::new();
+
+    if let Ok(head) = repo.head() {
+        if let Ok(commit) = head.peel_to_commit() {
+            decorations.entry(commit.id()).or_default().push(GitRefDecoration {
+                name: "HEAD".to_string(),
+                full_name: "HEAD".to_string(),
+                kind: GitRefKind::Head,
+            });
+        }
+    }
+
+    for reference in repo.references()? {
+        let reference = reference?;
+        let Some(full_name) = reference.name() else {
+            continue;
+        };
+        let kind = if reference.is_branch() {
+            GitRefKind::Branch
+        } else if reference.is_remote() {
+            // `origin/HEAD` only repeats the remote's default branch
+            if full_name.ends_with("/HEAD") {
+                continue;
+            }
+            GitRefKind::Remote
+        } else if reference.is_tag() {
+            GitRefKind::Tag
+        } else {
+            continue;
+        };
+
+        if let Ok(commit) = reference.peel_to_commit() {
+            decorations.entry(commit.id()).or_default().push(GitRefDecoration {
+                name: reference.shorthand().unwrap_or(full_name).to_string(),
+                full_name: full_name.to_string(),
+                kind,
+            });
+        }
+    }
+
+    Ok(decorations)
+}
+
+/// Whether `commit` changed `path`. Merges count only when they differ from every parent,
+/// which is how git simplifies path-limited history.
+fn touches_path(commit: &Commit, path: &str) -> Result<bool> {
+    let entry_id = |commit: &Commit| -> Result<Option<Oid>> {
+        Ok(commit.tree()?.get_path(Path::new(path)).ok().map(|entry| entry.id()))
+    };
+
+    let own = entry_id(commit)?;
+    if commit.parent_count() == 0 {
+        return Ok(own.is_some());
+    }
+    for parent in commit.parents() {
+        if entry_id(&parent)? == own {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Parse an RFC 3339 timestamp or a `YYYY-MM-DD` date; a bare `until` date covers the whole day.
+fn parse_date(value: &str, end_of_day: bool) -> Result<i64> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Ok(date.timestamp());
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| Error::InvalidDate(value.to_string()))?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    Ok(time.map(|t| t.and_utc().timestamp()).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Signature, Time};
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    /// Each commit is a second newer than the last, so the walk order does not depend on ties.
+    static CLOCK: AtomicI64 = AtomicI64::new(1_700_000_000);
+
+    fn commit(repo: &Repository, refname: Option<&str>, parents: &[Oid], file: &str, message: &str) -> Oid {
+        let mut index = repo.index().unwrap();
+        match parents.first() {
+            Some(parent) => index.read_tree(&repo.find_commit(*parent).unwrap().tree().unwrap()).unwrap(),
+            None => index.clear().unwrap(),
+        }
+        std::fs::write(repo.workdir().unwrap().join(file), message).unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::new("Test", "test@example.com", &Time::new(CLOCK.fetch_add(1, Ordering::SeqCst), 0)).unwrap();
+        let parents: Vec<_> = parents.iter().map(|oid| repo.find_commit(*oid).unwrap()).collect();
+        let parent_refs: Vec<_> = parents.iter().collect();
+        repo.commit(refname, &sig, &sig, message, &tree, &parent_refs).unwrap()
+    }
+
+    #[test]
+    fn test_graph_lanes_and_paging() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        let root = commit(&repo, Some("HEAD"), &[], "a.txt", "root");
+        let side = commit(&repo, None, &[root], "b.txt", "side");
+        let main = commit(&repo, Some("HEAD"), &[root], "a.txt", "main");
+        let merge = commit(&repo, Some("HEAD"), &[main, side], "a.txt", "merge");
+        repo.reference("refs/tags/v1", side, false, "tag").unwrap();
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+
+        let page = manager.get_graph(repo_path, GitGraphOptions::default()).unwrap();
+        let order: Vec<_> = page.nodes.iter().map(|node| node.commit.hash.clone()).collect();
+        assert_eq!(order.first(), Some(&merge.to_string()));
+        assert_eq!(order.last(), Some(&root.to_string()));
+        assert!(page.next_cursor.is_none());
+
+        let merge_node = &page.nodes[0];
+        assert_eq!(merge_node.column, 0);
+        assert_eq!(merge_node.outgoing, vec![0, 1]);
+        assert!(merge_node.refs.iter().any(|r| r.kind == GitRefKind::Head));
+        let side_node = page.nodes.iter().find(|node| node.commit.hash == side.to_string()).unwrap();
+        assert_eq!(side_node.column, 1);
+        assert!(side_node.refs.iter().any(|r| r.name == "v1" && r.kind == GitRefKind::Tag));
+        // Both branches have joined a single lane by the time they reach the root
+        let root_node = page.nodes.last().unwrap();
+        assert_eq!(root_node.incoming.len(), 1);
+        assert!(root_node.outgoing.is_empty());
+
+        // Paging two commits at a time visits every commit exactly once
+        let mut cursor = None;
+        let mut paged = Vec::new();
+        loop {
+            let options = GitGraphOptions {
+                limit: Some(2),
+                cursor: cursor.take(),
+                ..Default::default()
+            };
+            let page = manager.get_graph(repo_path, options).unwrap();
+            paged.extend(page.nodes.into_iter().map(|node| (node.commit.hash, node.column)));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        let unpaged: Vec<_> = manager
+            .get_graph(repo_path, GitGraphOptions::default())
+            .unwrap()
+            .nodes
+            .into_iter()
+            .map(|node| (node.commit.hash, node.column))
+            .collect();
+        assert_eq!(paged, unpaged);
+
+        // Path-limited history skips commits that leave the file alone
+        let options = GitGraphOptions {
+            path: Some("b.txt".to_string()),
+            ..Default::default()
+        };
+        let page = manager.get_graph(repo_path, options).unwrap();
+        let hashes: Vec<_> = page.nodes.iter().map(|node| node.commit.hash.clone()).collect();
+        assert_eq!(hashes, vec![side.to_string()]);
+
+        // Log pagination counts only commits that pass the filters: "main" and "side" match,
+        // so skipping one leaves one
+        let log_options = GitLogOptions {
+            grep: Some("i".to_string()),
+            skip: Some(1),
+            max_count: Some(10),
+            ..Default::default()
+        };
+        let log = manager.get_log(repo_path, log_options).unwrap();
+        assert_eq!(log.len(), 1);
+        assert!(log[0].message == "main" || log[0].message == "side");
+    }
+    #[test]
+    fn test_graph_paging_from_hidden_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        let base = commit(&repo, Some("HEAD"), &[], "a.txt", "change base");
+        let side1 = commit(&repo, None, &[base], "b.txt", "change side1");
+        let side2 = commit(&repo, None, &[side1], "b.txt", "change side2");
+        let main1 = commit(&repo, Some("HEAD"), &[base], "a.txt", "change main1");
+        let main2 = commit(&repo, Some("HEAD"), &[main1], "a.txt", "change main2");
+        commit(&repo, Some("HEAD"), &[main2, side2], "a.txt", "Merge side");
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+        let filtered = |limit, cursor| GitGraphOptions {
+            grep: Some("change".to_string()),
+            limit,
+            cursor,
+            ..Default::default()
+        };
+
+        let unpaged: Vec<_> = manager
+            .get_graph(repo_path, filtered(None, None))
+            .unwrap()
+            .nodes
+            .into_iter()
+            .map(|node| (node.commit.hash, node.column))
+            .collect();
+        assert_eq!(unpaged.len(), 5);
+
+        // The hidden merge at HEAD passes both of its parents on to the next pages
+        let mut cursor = None;
+        let mut paged = Vec::new();
+        loop {
+            let page = manager.get_graph(repo_path, filtered(Some(1), cursor.take())).unwrap();
+            paged.extend(page.nodes.into_iter().map(|node| (node.commit.hash, node.column)));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(paged, unpaged);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+};
+use git2::Repository;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Sender};
+use std::thread::JoinHandle;
+
+/// Run a client-side hook if it is installed, passing each line it prints to `on_output`.
+/// Fails when the hook exits unsuccessfully.
+pub(crate) fn run_hook(repo: &Repository, hook: &str, args: &[&str], on_output: &dyn Fn(GitHookOutput)) -> Result<()> {
+    let Some(path) = find_hook(repo, hook)? else {
+        return Ok(());
+    };
+
+    // Hooks run from the top of the working tree with the same environment git gives them
+    let mut command = hook_command(&path);
+    command
+        .args(args)
+        .current_dir(repo.workdir().unwrap_or(repo.path()))
+        .env("GIT_DIR", repo.path())
+        .env("GIT_INDEX_FILE", repo.path().join("index"))
+        .env("GIT_EDITOR", ":")
+        .env_remove("GIT_WORK_TREE")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = command.spawn()?;
+
+    let (sender, receiver) = mpsc::channel();
+    let readers = [
+        read_lines(child.stdout.take(), GitHookStream::Stdout, sender.clone()),
+        read_lines(child.stderr.take(), GitHookStream::Stderr, sender),
+    ];
+    for (stream, line) in receiver {
+        on_output(GitHookOutput {
+            hook: hook.to_string(),
+            stream,
+            line,
+        });
+    }
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let status = child.wait()?;
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(code) => Err(Error::Hook(format!("{} exited with status {}", hook, code))),
+        None => Err(Error::Hook(format!("{} was terminated by a signal", hook))),
+    }
+}
+
+/// Let `prepare-commit-msg` and, when verifying, `commit-msg` edit the commit message through
+/// `COMMIT_EDITMSG`, as git does. Returns the message they leave behind, cleaned up the way git
+/// cleans an edited message.
+pub(crate) fn run_message_hooks(
+    repo: &Repository,
+    message: &str,
+    amend: bool,
+    verify: bool,
+    on_output: &dyn Fn(GitHookOutput),
+) -> Result<String> {
+    let path = repo.path().join("COMMIT_EDITMSG");
+    std::fs::write(&path, message)?;
+    let file = path.to_string_lossy();
+
+    let source: &[&str] = if amend { &["commit", "HEAD"] } else { &["message"] };
+    let args: Vec<&str> = std::iter::once(file.as_ref()).chain(source.iter().copied()).collect();
+    run_hook(repo, "prepare-commit-msg", &args, on_output)?;
+    if verify {
+        run_hook(repo, "commit-msg", &[&file], on_output)?;
+    }
+    Ok(git2::message_prettify(std::fs::read_to_string(&path)?, Some(b'#'))?)
+}
+
+/// The hook's path in `core.hooksPath` (relative to the working tree) or the repository's
+/// `hooks` directory, if it exists and is executable.
+fn find_hook(repo: &Repository, hook: &str) -> Result<Option<PathBuf>> {
+    let dir = match repo.config()?.get_path("core.hooksPath") {
+        Ok(dir) if dir.is_relative() => repo.workdir().unwrap_or(repo.path()).join(dir),
+        Ok(dir) => dir,
+        // A linked worktree shares the hooks of the main repository
+        Err(_) => match std::fs::read_to_string(repo.path().join("commondir")) {
+            Ok(common_dir) => repo.path().join(common_dir.trim()).join("hooks"),
+            Err(_) => repo.path().join("hooks"),
+        },
+    };
+    let path = dir.join(hook);
+    Ok(is_executable(&path).then_some(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(unix)]
+fn hook_command(path: &Path) -> Command {
+    Command::new(path)
+}
+
+/// Hooks are usually shell scripts, which Windows cannot execute directly.
+#[cfg(not(unix))]
+fn hook_command(path: &Path) -> Command {
+    let mut command = Command::new("sh");
+    command.arg(path);
+    command
+}
+
+pub(crate) fn read_lines<P: Read + Send + 'static>(
+    pipe: Option<P>,
+    stream: GitHookStream,
+    sender: Sender<(GitHookStream, String)>,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let Some(pipe) = pipe else {
+            return;
+        };
+        for line in BufReader::new(pipe).split(b'\n') {
+            let Ok(line) = line else {
+                break;
+            };
+            let line = String::from_utf8_lossy(&line).trim_end_matches('\r').to_string();
+            if sender.send((stream, line)).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::repository::GitManager;
+    use crate::test_support::{init_repo, stage_files};
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::Mutex;
+
+    fn install_hook(repo: &Repository, name: &str, script: &str) {
+        let path = repo.path().join("hooks").join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, format!("#!/bin/sh\n{}", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    fn commit_options(message: &str, no_verify: bool) -> GitCommitOptions {
+        GitCommitOptions {
+            message: message.to_string(),
+            amend: None,
+            author: None,
+            signoff: None,
+            sign: Some(false),
+            no_verify: Some(no_verify),
+        }
+    }
+
+    #[test]
+    fn test_commit_runs_hooks() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(dir.path());
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+
+        // Hooks see the repository's environment, and commit-msg appends a trailer
+        install_hook(&repo, "pre-commit", "echo linting\necho warning >&2\ntest -f \"$GIT_DIR/HEAD\" && test -f gen.txt\n");
+        install_hook(&repo, "prepare-commit-msg", "echo \"$2 $3\" > \"$GIT_DIR/source\"\nprintf '\\n# drafted\\n' >> \"$1\"\n");
+        install_hook(&repo, "commit-msg", "printf '\\nChecked-by: hook\\n' >> \"$1\"\n");
+        install_hook(&repo, "post-commit", "echo done\nexit 1\n");
+        stage_files(&repo, &[("gen.txt", "generated\n")]);
+
+        let output = Mutex::new(Vec::new());
+        let oid = manager
+            .commit(repo_path, commit_options("feat: add", false), |line| output.lock().unwrap().push(line))
+            .unwrap();
+        let commit = repo.find_commit(git2::Oid::from_str(&oid).unwrap()).unwrap();
+        // The comment left by prepare-commit-msg is stripped
+        assert_eq!(commit.message(), Some("feat: add\n\nChecked-by: hook\n"));
+        assert_eq!(std::fs::read_to_string(repo.path().join("source")).unwrap(), "message \n");
+
+        let output = output.into_inner().unwrap();
+        let lines: Vec<_> = output.iter().map(|o| (o.hook.as_str(), o.stream, o.line.as_str())).collect();
+        assert!(lines.contains(&("pre-commit", GitHookStream::Stdout, "linting")));
+        assert!(lines.contains(&("pre-commit", GitHookStream::Stderr, "warning")));
+        assert!(lines.contains(&("post-commit", GitHookStream::Stdout, "done")));
+
+        // A failing commit-msg aborts the commit unless hooks are skipped
+        install_hook(&repo, "commit-msg", "echo 'subject must be conventional' >&2\nexit 1\n");
+        let head = repo.head().unwrap().target();
+        let result = manager.commit(repo_path, commit_options("bad subject", false), |_| {});
+        assert!(matches!(result, Err(Error::Hook(message)) if message.contains("commit-msg")));
+        assert_eq!(repo.head().unwrap().target(), head);
+
+        let oid = manager.commit(repo_path, commit_options("bad subject", true), |_| {}).unwrap();
+        let commit = repo.find_commit(git2::Oid::from_str(&oid).unwrap()).unwrap();
+        assert_eq!(commit.message(), Some("bad subject\n"));
+
+        let options = GitCommitOptions {
+            amend: Some(true),
+            ..commit_options("amended", true)
+        };
+        manager.commit(repo_path, options, |_| {}).unwrap();
+        assert_eq!(std::fs::read_to_string(repo.path().join("source")).unwrap(), "commit HEAD\n");
+    }
+}
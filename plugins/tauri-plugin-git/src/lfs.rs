@@ -0,0 +1,399 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+};
+use git2::{AttrCheckFlags, Index, IndexEntry, IndexTime, Repository};
+use std::fs::{File, Metadata};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+const POINTER_VERSION: &str = "version https://git-lfs.github.com/spec/v1";
+/// Pointer files are a few lines long; anything bigger is real content.
+const MAX_POINTER_SIZE: u64 = 1024;
+const STAGE_MASK: u16 = 0x3000;
+
+/// The `git-lfs` program, which runs the smudge and clean filters.
+pub(crate) struct LfsFilter {
+    program: PathBuf,
+}
+
+impl LfsFilter {
+    /// `git-lfs` from the PATH, if it is installed.
+    pub(crate) fn find() -> Option<Self> {
+        static INSTALLED: OnceLock<bool> = OnceLock::new();
+        let installed = *INSTALLED.get_or_init(|| {
+            Command::new("git-lfs")
+                .arg("version")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        });
+        installed.then(|| Self::new("git-lfs"))
+    }
+
+    pub(crate) fn new(program: impl Into<PathBuf>) -> Self {
+        Self { program: program.into() }
+    }
+
+    /// Turn the working tree file at `path` into a pointer, storing its content in the LFS cache.
+    fn clean(&self, repo: &Repository, path: &str) -> Result<Vec<u8>> {
+        let file = File::open(workdir(repo)?.join(path))?;
+        let output = self
+            .command(repo, "clean", path)
+            .stdin(file)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()?;
+        if !output.status.success() {
+            return Err(filter_error("clean", path, &output.stderr));
+        }
+        Ok(output.stdout)
+    }
+
+    /// Replace the pointer file at `path` with its content, downloading it when needed.
+    fn smudge(&self, repo: &Repository, path: &str, pointer: &[u8]) -> Result<()> {
+        let target = workdir(repo)?.join(path);
+        let file_name = target.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let temp = target.with_file_name(format!(".{}.lfs-{}", file_name, uuid::Uuid::new_v4()));
+
+        let mut child = self
+            .command(repo, "smudge", path)
+            .stdin(Stdio::piped())
+            .stdout(File::create(&temp)?)
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(pointer)?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let _ = std::fs::remove_file(&temp);
+            return Err(filter_error("smudge", path, &output.stderr));
+        }
+
+        std::fs::set_permissions(&temp, target.metadata()?.permissions())?;
+        std::fs::rename(&temp, &target)?;
+        Ok(())
+    }
+
+    fn command(&self, repo: &Repository, filter: &str, path: &str) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .args([filter, "--", path])
+            .current_dir(repo.workdir().unwrap_or(repo.path()))
+            .env("GIT_DIR", repo.path());
+        command
+    }
+}
+
+fn filter_error(filter: &str, path: &str, stderr: &[u8]) -> Error {
+    Error::Lfs(format!("{} failed for {}: {}", filter, path, String::from_utf8_lossy(stderr).trim()))
+}
+
+fn workdir(repo: &Repository) -> Result<&Path> {
+    repo.workdir()
+        .ok_or_else(|| Error::InvalidState("repository has no working tree".to_string()))
+}
+
+/// Whether `.gitattributes` assigns the LFS filter to `path`.
+pub(crate) fn is_lfs_path(repo: &Repository, path: &str) -> bool {
+    repo.get_attr(Path::new(path), "filter", AttrCheckFlags::FILE_THEN_INDEX)
+        .ok()
+        .flatten()
+        == Some("lfs")
+}
+
+/// Whether any `.gitattributes` mentions the LFS filter, so that repositories without LFS skip
+/// the attribute lookup for every file.
+fn uses_lfs(repo: &Repository, index: &Index) -> bool {
+    let mentions_lfs = |data: &[u8]| String::from_utf8_lossy(data).contains("filter=lfs");
+    if let Some(workdir) = repo.workdir() {
+        if std::fs::read(workdir.join(".gitattributes")).is_ok_and(|data| mentions_lfs(&data)) {
+            return true;
+        }
+    }
+    index.iter().any(|entry| {
+        (entry.path == b".gitattributes" || entry.path.ends_with(b"/.gitattributes"))
+            && repo.find_blob(entry.id).is_ok_and(|blob| mentions_lfs(blob.content()))
+    })
+}
+
+/// The content oid and size recorded in an LFS pointer.
+pub(crate) fn parse_pointer(data: &[u8]) -> Option<(String, u64)> {
+    if data.len() as u64 > MAX_POINTER_SIZE {
+        return None;
+    }
+    let text = std::str::from_utf8(data).ok()?;
+    let mut lines = text.lines();
+    if lines.next()? != POINTER_VERSION {
+        return None;
+    }
+
+    let (mut oid, mut size) = (None, None);
+    for line in lines {
+        if let Some(value) = line.strip_prefix("oid sha256:") {
+            oid = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("size ") {
+            size = value.parse().ok();
+        }
+    }
+    Some((oid?, size?))
+}
+
+/// The bytes of `path` if it is a pointer file.
+fn read_pointer_file(path: &Path) -> Option<Vec<u8>> {
+    if path.metadata().ok()?.len() > MAX_POINTER_SIZE {
+        return None;
+    }
+    std::fs::read(path).ok().filter(|data| parse_pointer(data).is_some())
+}
+
+fn lfs_entries(repo: &Repository, index: &Index) -> Vec<IndexEntry> {
+    if !uses_lfs(repo, index) {
+        return Vec::new();
+    }
+    index
+        .iter()
+        .filter(|entry| entry.flags & STAGE_MASK == 0)
+        .filter(|entry| is_lfs_path(repo, &String::from_utf8_lossy(&entry.path)))
+        .collect()
+}
+
+/// LFS files in the index whose working tree copy exists, with whether it is still the
+/// pointer or the downloaded content.
+pub(crate) fn lfs_files(repo: &Repository) -> Result<Vec<GitLfsFile>> {
+    let Some(workdir) = repo.workdir() else {
+        return Ok(Vec::new());
+    };
+    let index = repo.index()?;
+
+    let mut files = Vec::new();
+    for entry in lfs_entries(repo, &index) {
+        // Content committed before the path was tracked by LFS has no pointer
+        let Some((oid, size)) = repo.find_blob(entry.id).ok().and_then(|blob| parse_pointer(blob.content())) else {
+            continue;
+        };
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        let file = workdir.join(&path);
+        if !file.exists() {
+            continue;
+        }
+        let state = match read_pointer_file(&file) {
+            Some(_) => GitLfsState::Pointer,
+            None => GitLfsState::Smudged,
+        };
+        files.push(GitLfsFile { path, state, oid, size });
+    }
+    Ok(files)
+}
+
+/// Whether the working tree content of an LFS file cleans to the pointer in the index, i.e. it
+/// only looks modified because libgit2 compares it with the pointer. The index is left alone:
+/// staging or checking the file out records its stat data, after which status skips it.
+pub(crate) fn is_unmodified_lfs_file(repo: &Repository, path: &str, filter: &LfsFilter) -> bool {
+    if !is_lfs_path(repo, path) {
+        return false;
+    }
+    let Some(entry) = repo.index().ok().and_then(|index| index.get_path(Path::new(path), 0)) else {
+        return false;
+    };
+    let Ok(blob) = repo.find_blob(entry.id) else {
+        return false;
+    };
+    parse_pointer(blob.content()).is_some() && filter.clean(repo, path).is_ok_and(|pointer| pointer == blob.content())
+}
+
+/// Stage `path` as the pointer produced by the clean filter, as `git add` does for LFS files.
+pub(crate) fn add_lfs_path(repo: &Repository, index: &mut Index, path: &str, filter: &LfsFilter) -> Result<()> {
+    let file = workdir(repo)?.join(path);
+    let metadata = file.metadata().map_err(|_| Error::FileNotFound(path.to_string()))?;
+    let pointer = match read_pointer_file(&file) {
+        Some(pointer) => pointer,
+        None => filter.clean(repo, path)?,
+    };
+
+    let mut entry = IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode: file_mode(&metadata),
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id: repo.blob(&pointer)?,
+        flags: 0,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    };
+    // The stat data of the real file lets status treat it as unchanged
+    set_stat(&mut entry, &metadata);
+    index.add(&entry)?;
+    Ok(())
+}
+
+/// Replace pointer files in the working tree with their content, as the smudge filter does
+/// during a git checkout. Files whose content cannot be fetched keep their pointer, and
+/// `GIT_LFS_SKIP_SMUDGE=1` skips the filter like it does for git.
+pub(crate) fn smudge_worktree(repo: &Repository, filter: &LfsFilter) -> Result<()> {
+    if std::env::var_os("GIT_LFS_SKIP_SMUDGE").is_some_and(|value| value != "0") {
+        return Ok(());
+    }
+    let Some(workdir) = repo.workdir() else {
+        return Ok(());
+    };
+
+    let mut index = repo.index()?;
+    let mut changed = false;
+    for mut entry in lfs_entries(repo, &index) {
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        let file = workdir.join(&path);
+        let Some(pointer) = read_pointer_file(&file) else {
+            continue;
+        };
+        if filter.smudge(repo, &path, &pointer).is_err() {
+            continue;
+        }
+        set_stat(&mut entry, &file.metadata()?);
+        index.add(&entry)?;
+        changed = true;
+    }
+    if changed {
+        index.write()?;
+    }
+    Ok(())
+}
+
+/// Run the smudge filter when `git-lfs` is installed.
+pub(crate) fn smudge_if_installed(repo: &Repository) -> Result<()> {
+    match LfsFilter::find() {
+        Some(filter) => smudge_worktree(repo, &filter),
+        None => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    if metadata.permissions().mode() & 0o111 != 0 {
+        0o100755
+    } else {
+        0o100644
+    }
+}
+
+#[cfg(not(unix))]
+fn file_mode(_metadata: &Metadata) -> u32 {
+    0o100644
+}
+
+#[cfg(unix)]
+fn set_stat(entry: &mut IndexEntry, metadata: &Metadata) {
+    use std::os::unix::fs::MetadataExt;
+    entry.ctime = IndexTime::new(metadata.ctime() as i32, metadata.ctime_nsec() as u32);
+    entry.mtime = IndexTime::new(metadata.mtime() as i32, metadata.mtime_nsec() as u32);
+    entry.dev = metadata.dev() as u32;
+    entry.ino = metadata.ino() as u32;
+    entry.uid = metadata.uid();
+    entry.gid = metadata.gid();
+    entry.file_size = metadata.len() as u32;
+}
+
+#[cfg(not(unix))]
+fn set_stat(entry: &mut IndexEntry, metadata: &Metadata) {
+    if let Ok(modified) = metadata.modified().map(chrono::DateTime::<chrono::Utc>::from) {
+        entry.mtime = IndexTime::new(modified.timestamp() as i32, modified.timestamp_subsec_nanos());
+    }
+    entry.file_size = metadata.len() as u32;
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::repository::GitManager;
+    use crate::test_support::commit_index;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Stands in for git-lfs, keeping content in a directory of the repository keyed by its hash.
+    const FAKE_LFS: &str = r#"#!/bin/sh
+store="$GIT_DIR/fake-lfs"
+mkdir -p "$store"
+case "$1" in
+clean)
+    cat > "$store/incoming"
+    oid=$(sha256sum "$store/incoming" | cut -d' ' -f1)
+    size=$(wc -c < "$store/incoming" | tr -d ' ')
+    mv "$store/incoming" "$store/$oid"
+    printf 'version https://git-lfs.github.com/spec/v1\noid sha256:%s\nsize %s\n' "$oid" "$size"
+    ;;
+smudge)
+    oid=$(sed -n 's/^oid sha256://p')
+    cat "$store/$oid"
+    ;;
+esac
+"#;
+
+    #[test]
+    fn test_lfs_pointers_in_status_diff_and_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("git-lfs");
+        std::fs::write(&program, FAKE_LFS).unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let filter = LfsFilter::new(&program);
+
+        let workdir = dir.path().join("repo");
+        let repo = Repository::init(&workdir).unwrap();
+        let manager = GitManager::new();
+        let repo_path = workdir.to_str().unwrap();
+        std::fs::write(workdir.join(".gitattributes"), "*.psd filter=lfs diff=lfs merge=lfs -text\n").unwrap();
+        std::fs::write(workdir.join("design.psd"), "layer one\n".repeat(200)).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(".gitattributes")).unwrap();
+        add_lfs_path(&repo, &mut index, "design.psd", &filter).unwrap();
+        index.write().unwrap();
+        commit_index(&repo, "add design");
+
+        // The pointer is committed while the working tree keeps the content
+        let entry = index.get_path(Path::new("design.psd"), 0).unwrap();
+        let (oid, size) = parse_pointer(repo.find_blob(entry.id).unwrap().content()).unwrap();
+        assert_eq!(size, 2000);
+        let status = manager.status(repo_path).unwrap();
+        assert!(status.unstaged.is_empty());
+        assert_eq!(status.lfs.len(), 1);
+        assert_eq!((status.lfs[0].state, status.lfs[0].oid.as_str()), (GitLfsState::Smudged, oid.as_str()));
+
+        // A checkout without the filter leaves the pointer, which the smudge filter replaces
+        std::fs::remove_file(workdir.join("design.psd")).unwrap();
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        repo.checkout_head(Some(checkout.force())).unwrap();
+        assert_eq!(manager.status(repo_path).unwrap().lfs[0].state, GitLfsState::Pointer);
+
+        smudge_worktree(&repo, &filter).unwrap();
+        assert_eq!(std::fs::read_to_string(workdir.join("design.psd")).unwrap(), "layer one\n".repeat(200));
+        assert_eq!(manager.status(repo_path).unwrap().lfs[0].state, GitLfsState::Smudged);
+        assert!(is_unmodified_lfs_file(&repo, "design.psd", &filter));
+
+        // Changed content is never diffed as text
+        std::fs::write(workdir.join("design.psd"), "layer two\n".repeat(200)).unwrap();
+        let diffs = manager.diff(repo_path, false, GitDiffOptions::default()).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert!(diffs[0].is_lfs && diffs[0].is_binary && diffs[0].hunks.is_empty());
+        assert!(!is_unmodified_lfs_file(&repo, "design.psd", &filter));
+
+        // Restored content is recognised without writing the index, until staging records its
+        // stat data
+        std::fs::write(workdir.join("design.psd"), "layer one\n".repeat(200)).unwrap();
+        let index_file = std::fs::read(repo.path().join("index")).unwrap();
+        assert!(is_unmodified_lfs_file(&repo, "design.psd", &filter));
+        assert_eq!(std::fs::read(repo.path().join("index")).unwrap(), index_file);
+        index.read(true).unwrap();
+        add_lfs_path(&repo, &mut index, "design.psd", &filter).unwrap();
+        index.write().unwrap();
+        let statuses = repo.statuses(None).unwrap();
+        assert!(statuses.iter().all(|status| status.path() != Some("design.psd")));
+    }
+}
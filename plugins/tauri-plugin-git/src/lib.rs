@@ -1,6 +1,6 @@
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Manager, Runtime,
+    Emitter, Manager, Runtime,
 };
 use std::sync::Arc;
 
@@ -8,14 +8,40 @@ pub mod error;
 pub mod models;
 pub mod utils;
 pub mod repository;
+pub mod graph;
+pub mod diff;
+pub mod staging;
+pub mod remote;
+pub mod merge;
+pub mod rebase;
+pub mod conflicts;
+pub mod sequencer;
+pub mod stash;
+pub mod blame;
+pub mod signing;
+pub mod tags;
+pub mod worktree;
+pub mod submodule;
+pub mod watcher;
+pub mod clone;
+pub mod sparse;
+pub mod config;
+pub mod branch;
+pub mod reflog;
+pub mod hooks;
+pub mod lfs;
+pub mod bisect;
+pub mod search;
+pub mod patches;
 pub mod commands;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 pub use error::{Error, Result};
 pub use models::*;
 use repository::GitManager;
 
 pub struct Git<R: Runtime> {
-    #[allow(dead_code)]
     app: tauri::AppHandle<R>,
     manager: Arc<GitManager>,
 }
@@ -25,12 +51,35 @@ impl<R: Runtime> Git<R> {
         self.manager.status(repo_path)
     }
 
+    pub fn status_with_options(&self, repo_path: &str, options: GitStatusOptions) -> Result<GitStatus> {
+        self.manager.status_with_options(repo_path, options)
+    }
+
+    /// Watch a repository and emit `plugin:git:status-changed` events as it changes.
+    pub fn watch_repository(&self, repo_path: &str, options: GitWatchOptions) -> Result<String> {
+        let app = self.app.clone();
+        self.manager.watch_repository(repo_path, options, move |change| {
+            let _ = app.emit("plugin:git:status-changed", change);
+        })
+    }
+
+    pub fn unwatch_repository(&self, watch_id: &str) -> bool {
+        self.manager.unwatch_repository(watch_id)
+    }
+
     pub fn get_log(&self, repo_path: &str, options: GitLogOptions) -> Result<Vec<GitCommit>> {
         self.manager.get_log(repo_path, options)
     }
 
+    pub fn get_graph(&self, repo_path: &str, options: GitGraphOptions) -> Result<GitGraphPage> {
+        self.manager.get_graph(repo_path, options)
+    }
+
+    /// Commit, emitting each line printed by the commit hooks as a `plugin:git:hook-output` event.
     pub fn commit(&self, repo_path: &str, options: GitCommitOptions) -> Result<String> {
-        self.manager.commit(repo_path, options)
+        self.manager.commit(repo_path, options, |output| {
+            let _ = self.app.emit("plugin:git:hook-output", output);
+        })
     }
 
     pub fn stage_file(&self, repo_path: &str, file_path: &str) -> Result<()> {
@@ -49,8 +98,48 @@ impl<R: Runtime> Git<R> {
         self.manager.unstage_all(repo_path)
     }
 
-    pub fn get_branches(&self, repo_path: &str) -> Result<Vec<GitBranch>> {
-        self.manager.get_branches(repo_path)
+    pub fn stage_hunk(&self, repo_path: &str, selection: GitHunkSelection) -> Result<()> {
+        self.manager.stage_hunk(repo_path, selection)
+    }
+
+    pub fn unstage_hunk(&self, repo_path: &str, selection: GitHunkSelection) -> Result<()> {
+        self.manager.unstage_hunk(repo_path, selection)
+    }
+
+    pub fn discard_hunk(&self, repo_path: &str, selection: GitHunkSelection) -> Result<()> {
+        self.manager.discard_hunk(repo_path, selection)
+    }
+
+    pub fn diff(&self, repo_path: &str, cached: bool, options: GitDiffOptions) -> Result<Vec<GitDiff>> {
+        self.manager.diff(repo_path, cached, options)
+    }
+
+    pub fn diff_file(&self, repo_path: &str, file_path: &str, cached: bool) -> Result<GitDiff> {
+        self.manager.diff_file(repo_path, file_path, cached)
+    }
+
+    pub fn diff_commits(&self, repo_path: &str, from_ref: &str, to_ref: &str, options: GitDiffOptions) -> Result<Vec<GitDiff>> {
+        self.manager.diff_commits(repo_path, from_ref, to_ref, options)
+    }
+
+    pub fn show_commit(&self, repo_path: &str, commit: &str, options: GitShowCommitOptions) -> Result<GitCommitDetails> {
+        self.manager.show_commit(repo_path, commit, options)
+    }
+
+    pub fn get_branches(&self, repo_path: &str, options: GitBranchListOptions) -> Result<Vec<GitBranch>> {
+        self.manager.get_branches_with_options(repo_path, options)
+    }
+
+    pub fn delete_branch(&self, repo_path: &str, branch_name: &str, force: bool) -> Result<()> {
+        self.manager.delete_branch(repo_path, branch_name, force)
+    }
+
+    pub fn rename_branch(&self, repo_path: &str, branch_name: &str, new_name: &str, force: bool) -> Result<()> {
+        self.manager.rename_branch(repo_path, branch_name, new_name, force)
+    }
+
+    pub fn set_upstream(&self, repo_path: &str, branch_name: Option<&str>, upstream: Option<&str>) -> Result<()> {
+        self.manager.set_upstream(repo_path, branch_name, upstream)
     }
 
     pub fn create_branch(&self, repo_path: &str, branch_name: &str, from_ref: Option<&str>) -> Result<()> {
@@ -61,9 +150,326 @@ impl<R: Runtime> Git<R> {
         self.manager.checkout(repo_path, options)
     }
 
+    pub fn merge(&self, repo_path: &str, options: GitMergeOptions) -> Result<GitMergeResult> {
+        self.manager.merge(repo_path, options)
+    }
+
+    pub fn merge_continue(&self, repo_path: &str, message: Option<String>) -> Result<String> {
+        self.manager.merge_continue(repo_path, message)
+    }
+
+    pub fn merge_abort(&self, repo_path: &str) -> Result<()> {
+        self.manager.merge_abort(repo_path)
+    }
+
+    pub fn rebase(&self, repo_path: &str, options: GitRebaseOptions) -> Result<GitRebaseResult> {
+        self.manager.rebase(repo_path, options)
+    }
+
+    pub fn rebase_continue(&self, repo_path: &str) -> Result<GitRebaseResult> {
+        self.manager.rebase_continue(repo_path)
+    }
+
+    pub fn rebase_skip(&self, repo_path: &str) -> Result<GitRebaseResult> {
+        self.manager.rebase_skip(repo_path)
+    }
+
+    pub fn rebase_abort(&self, repo_path: &str) -> Result<()> {
+        self.manager.rebase_abort(repo_path)
+    }
+
+    pub fn interactive_rebase(&self, repo_path: &str, options: GitInteractiveRebaseOptions) -> Result<GitSequencerState> {
+        self.manager.interactive_rebase(repo_path, options)
+    }
+
+    pub fn cherry_pick(&self, repo_path: &str, options: GitCherryPickOptions) -> Result<GitSequencerState> {
+        self.manager.cherry_pick(repo_path, options)
+    }
+
+    pub fn revert(&self, repo_path: &str, options: GitRevertOptions) -> Result<GitSequencerState> {
+        self.manager.revert(repo_path, options)
+    }
+
+    pub fn sequencer_continue(&self, repo_path: &str) -> Result<GitSequencerState> {
+        self.manager.sequencer_continue(repo_path)
+    }
+
+    pub fn sequencer_skip(&self, repo_path: &str) -> Result<GitSequencerState> {
+        self.manager.sequencer_skip(repo_path)
+    }
+
+    pub fn sequencer_abort(&self, repo_path: &str) -> Result<()> {
+        self.manager.sequencer_abort(repo_path)
+    }
+
+    pub fn sequencer_state(&self, repo_path: &str) -> Result<Option<GitSequencerState>> {
+        self.manager.sequencer_state(repo_path)
+    }
+
+    pub fn reset(&self, repo_path: &str, options: GitResetOptions) -> Result<()> {
+        self.manager.reset(repo_path, options)
+    }
+
+    pub fn reflog(&self, repo_path: &str, options: GitReflogOptions) -> Result<Vec<GitReflogEntry>> {
+        self.manager.reflog(repo_path, options)
+    }
+
+    pub fn undo_preview(&self, repo_path: &str, options: GitUndoOptions) -> Result<GitUndoPlan> {
+        self.manager.undo_preview(repo_path, options)
+    }
+
+    pub fn undo(&self, repo_path: &str, options: GitUndoOptions) -> Result<GitUndoPlan> {
+        self.manager.undo(repo_path, options)
+    }
+
+    pub fn bisect_start(&self, repo_path: &str, options: GitBisectStartOptions) -> Result<GitBisectState> {
+        self.manager.bisect_start(repo_path, options)
+    }
+
+    pub fn bisect_mark(&self, repo_path: &str, options: GitBisectMarkOptions) -> Result<GitBisectState> {
+        self.manager.bisect_mark(repo_path, options)
+    }
+
+    pub fn bisect_state(&self, repo_path: &str) -> Result<Option<GitBisectState>> {
+        self.manager.bisect_state(repo_path)
+    }
+
+    /// Run a bisect to the end, emitting the command's output as `plugin:git:bisect-output`
+    /// events and each result as a `plugin:git:bisect-step` event.
+    pub fn bisect_run(&self, repo_path: &str, options: GitBisectRunOptions) -> Result<GitBisectState> {
+        self.manager.bisect_run(
+            repo_path,
+            options,
+            |output| {
+                let _ = self.app.emit("plugin:git:bisect-output", output);
+            },
+            |step| {
+                let _ = self.app.emit("plugin:git:bisect-step", step);
+            },
+        )
+    }
+
+    pub fn bisect_reset(&self, repo_path: &str) -> Result<()> {
+        self.manager.bisect_reset(repo_path)
+    }
+
+    pub fn format_patch(&self, repo_path: &str, options: GitFormatPatchOptions) -> Result<GitPatchSeries> {
+        self.manager.format_patch(repo_path, options)
+    }
+
+    pub fn apply_patch(&self, repo_path: &str, options: GitApplyPatchOptions) -> Result<GitApplyResult> {
+        self.manager.apply_patch(repo_path, options)
+    }
+
+    pub fn am(&self, repo_path: &str, options: GitAmOptions) -> Result<Vec<GitAmPatch>> {
+        self.manager.am(repo_path, options)
+    }
+
+    pub fn get_conflicts(&self, repo_path: &str) -> Result<Vec<GitConflict>> {
+        self.manager.get_conflicts(repo_path)
+    }
+
+    pub fn resolve_conflict(&self, repo_path: &str, file_path: &str, resolution: GitConflictResolution) -> Result<()> {
+        self.manager.resolve_conflict(repo_path, file_path, resolution)
+    }
+
+    pub fn get_operation_state(&self, repo_path: &str) -> Result<GitOperationState> {
+        self.manager.get_operation_state(repo_path)
+    }
+
+    pub fn stash_save(&self, repo_path: &str, options: GitStashOptions) -> Result<String> {
+        self.manager.stash_save(repo_path, options)
+    }
+
+    pub fn stash_list(&self, repo_path: &str) -> Result<Vec<GitStashEntry>> {
+        self.manager.stash_list(repo_path)
+    }
+
+    pub fn stash_apply(&self, repo_path: &str, stash_id: &str) -> Result<()> {
+        self.manager.stash_apply(repo_path, stash_id)
+    }
+
+    pub fn stash_pop(&self, repo_path: &str, stash_id: &str) -> Result<()> {
+        self.manager.stash_pop(repo_path, stash_id)
+    }
+
+    pub fn stash_drop(&self, repo_path: &str, stash_id: &str) -> Result<()> {
+        self.manager.stash_drop(repo_path, stash_id)
+    }
+
+    pub fn stash_show(&self, repo_path: &str, stash_id: &str, options: GitDiffOptions) -> Result<Vec<GitDiff>> {
+        self.manager.stash_show(repo_path, stash_id, options)
+    }
+
+    pub fn blame(&self, repo_path: &str, file_path: &str, options: GitBlameOptions) -> Result<GitBlame> {
+        self.manager.blame(repo_path, file_path, options)
+    }
+
+    /// Search the history for changes to a string, emitting each commit found as a
+    /// `plugin:git:search-result` event.
+    pub fn search_history(&self, repo_path: &str, options: GitSearchOptions) -> Result<Vec<GitSearchMatch>> {
+        self.manager.search_history(repo_path, options, |found| {
+            let _ = self.app.emit("plugin:git:search-result", found);
+        })
+    }
+
+    pub fn get_tags(&self, repo_path: &str) -> Result<Vec<GitTag>> {
+        self.manager.get_tags(repo_path)
+    }
+
+    pub fn create_tag(
+        &self,
+        repo_path: &str,
+        tag_name: &str,
+        message: Option<String>,
+        options: GitCreateTagOptions,
+    ) -> Result<String> {
+        self.manager.create_tag(repo_path, tag_name, message, options)
+    }
+
+    pub fn delete_tag(&self, repo_path: &str, tag_name: &str) -> Result<()> {
+        self.manager.delete_tag(repo_path, tag_name)
+    }
+
+    pub fn list_worktrees(&self, repo_path: &str) -> Result<Vec<GitWorktree>> {
+        self.manager.list_worktrees(repo_path)
+    }
+
+    pub fn add_worktree(&self, repo_path: &str, options: GitWorktreeAddOptions) -> Result<GitWorktree> {
+        self.manager.add_worktree(repo_path, options)
+    }
+
+    pub fn lock_worktree(&self, repo_path: &str, worktree: &str, reason: Option<&str>) -> Result<()> {
+        self.manager.lock_worktree(repo_path, worktree, reason)
+    }
+
+    pub fn unlock_worktree(&self, repo_path: &str, worktree: &str) -> Result<()> {
+        self.manager.unlock_worktree(repo_path, worktree)
+    }
+
+    pub fn prune_worktrees(&self, repo_path: &str) -> Result<Vec<String>> {
+        self.manager.prune_worktrees(repo_path)
+    }
+
+    pub fn remove_worktree(&self, repo_path: &str, worktree: &str, force: bool) -> Result<()> {
+        self.manager.remove_worktree(repo_path, worktree, force)
+    }
+
+    pub fn list_submodules(&self, repo_path: &str) -> Result<Vec<GitSubmodule>> {
+        self.manager.list_submodules(repo_path)
+    }
+
+    pub fn init_submodules(&self, repo_path: &str, paths: Option<Vec<String>>) -> Result<()> {
+        self.manager.init_submodules(repo_path, paths)
+    }
+
+    pub fn update_submodules(&self, repo_path: &str, options: GitSubmoduleUpdateOptions) -> Result<()> {
+        self.manager.update_submodules(repo_path, options, |progress| self.emit_progress(progress))
+    }
+
+    pub fn sync_submodules(&self, repo_path: &str, paths: Option<Vec<String>>, recursive: bool) -> Result<()> {
+        self.manager.sync_submodules(repo_path, paths, recursive)
+    }
+
+    pub fn clone_repository(&self, url: &str, path: &str, options: GitCloneOptions) -> Result<()> {
+        self.manager.clone_repository(url, path, options, |progress| self.emit_progress(progress))
+    }
+
+    pub fn init_repository(&self, path: &str, options: GitInitOptions) -> Result<()> {
+        self.manager.init_repository(path, options)
+    }
+
+    pub fn get_sparse_checkout(&self, repo_path: &str) -> Result<GitSparseCheckout> {
+        self.manager.get_sparse_checkout(repo_path)
+    }
+
+    pub fn set_sparse_checkout(&self, repo_path: &str, paths: Vec<String>) -> Result<GitSparseCheckout> {
+        self.manager.set_sparse_checkout(repo_path, paths)
+    }
+
+    pub fn disable_sparse_checkout(&self, repo_path: &str) -> Result<()> {
+        self.manager.disable_sparse_checkout(repo_path)
+    }
+
+    pub fn config_get(&self, repo_path: &str, key: &str, scope: Option<GitConfigScope>) -> Result<Option<String>> {
+        self.manager.config_get(repo_path, key, scope)
+    }
+
+    pub fn config_get_all(&self, repo_path: &str, key: &str, scope: Option<GitConfigScope>) -> Result<Vec<GitConfigEntry>> {
+        self.manager.config_get_all(repo_path, key, scope)
+    }
+
+    pub fn config_list(&self, repo_path: &str, scope: Option<GitConfigScope>) -> Result<Vec<GitConfigEntry>> {
+        self.manager.config_list(repo_path, scope)
+    }
+
+    pub fn config_set(&self, repo_path: &str, key: &str, value: GitConfigValue, options: GitConfigSetOptions) -> Result<()> {
+        self.manager.config_set(repo_path, key, value, options)
+    }
+
+    pub fn config_unset(&self, repo_path: &str, key: &str, options: GitConfigUnsetOptions) -> Result<()> {
+        self.manager.config_unset(repo_path, key, options)
+    }
+
+    pub fn get_config(&self, repo_path: &str) -> Result<GitConfig> {
+        self.manager.get_config(repo_path)
+    }
+
+    pub fn update_config(&self, repo_path: &str, config: GitConfig, scope: Option<GitConfigScope>) -> Result<GitConfig> {
+        self.manager.update_config(repo_path, config, scope)
+    }
+
     pub fn get_remotes(&self, repo_path: &str) -> Result<Vec<GitRemote>> {
         self.manager.get_remotes(repo_path)
     }
+
+    pub fn add_remote(&self, repo_path: &str, name: &str, url: &str) -> Result<GitRemote> {
+        self.manager.add_remote(repo_path, name, url)
+    }
+
+    pub fn remove_remote(&self, repo_path: &str, name: &str) -> Result<()> {
+        self.manager.remove_remote(repo_path, name)
+    }
+
+    pub fn rename_remote(&self, repo_path: &str, name: &str, new_name: &str) -> Result<Vec<String>> {
+        self.manager.rename_remote(repo_path, name, new_name)
+    }
+
+    pub fn set_remote_url(&self, repo_path: &str, name: &str, options: GitRemoteUrlOptions) -> Result<GitRemote> {
+        self.manager.set_remote_url(repo_path, name, options)
+    }
+
+    pub fn set_remote_refspecs(&self, repo_path: &str, name: &str, refspecs: GitRemoteRefspecs) -> Result<GitRemote> {
+        self.manager.set_remote_refspecs(repo_path, name, refspecs)
+    }
+
+    pub fn prune_remote(&self, repo_path: &str, name: &str, options: GitRemotePruneOptions) -> Result<Vec<String>> {
+        self.manager.prune_remote(repo_path, name, options, |progress| self.emit_progress(progress))
+    }
+
+    pub fn fetch(&self, repo_path: &str, options: GitFetchOptions) -> Result<()> {
+        self.manager.fetch(repo_path, options, |progress| self.emit_progress(progress))
+    }
+
+    pub fn pull(&self, repo_path: &str, options: GitPullOptions) -> Result<()> {
+        self.manager.pull(repo_path, options, |progress| self.emit_progress(progress))
+    }
+
+    pub fn push(&self, repo_path: &str, options: GitPushOptions) -> Result<()> {
+        self.manager.push(repo_path, options, |progress| self.emit_progress(progress))
+    }
+
+    pub fn push_tags(&self, repo_path: &str, options: GitTagPushOptions) -> Result<()> {
+        self.manager.push_tags(repo_path, options, |progress| self.emit_progress(progress))
+    }
+
+    pub fn cancel_operation(&self, operation_id: &str) -> bool {
+        self.manager.cancel_operation(operation_id)
+    }
+
+    fn emit_progress(&self, progress: GitTransferProgress) {
+        let _ = self.app.emit("plugin:git:progress", progress);
+    }
 }
 
 /// Extension trait to access the git API
@@ -81,41 +487,101 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("git")
         .invoke_handler(tauri::generate_handler![
             commands::git_status,
+            commands::git_watch,
+            commands::git_unwatch,
             commands::git_log,
+            commands::git_graph,
             commands::git_commit,
             commands::git_stage,
             commands::git_unstage,
             commands::git_stage_all,
             commands::git_unstage_all,
+            commands::git_stage_hunk,
+            commands::git_unstage_hunk,
+            commands::git_discard_hunk,
             commands::git_diff,
             commands::git_diff_file,
+            commands::git_diff_commits,
             commands::git_branches,
             commands::git_create_branch,
             commands::git_checkout,
             commands::git_delete_branch,
+            commands::git_rename_branch,
+            commands::git_set_upstream,
             commands::git_merge,
+            commands::git_merge_continue,
+            commands::git_merge_abort,
+            commands::git_rebase,
+            commands::git_rebase_continue,
+            commands::git_rebase_skip,
+            commands::git_rebase_abort,
+            commands::git_rebase_interactive,
+            commands::git_sequencer_continue,
+            commands::git_sequencer_skip,
+            commands::git_sequencer_abort,
+            commands::git_sequencer_state,
+            commands::git_conflicts,
+            commands::git_resolve_conflict,
+            commands::git_operation_state,
             commands::git_fetch,
             commands::git_pull,
             commands::git_push,
+            commands::git_cancel_operation,
             commands::git_remotes,
             commands::git_add_remote,
             commands::git_remove_remote,
+            commands::git_rename_remote,
+            commands::git_set_remote_url,
+            commands::git_set_remote_refspecs,
+            commands::git_prune_remote,
             commands::git_stash,
             commands::git_stash_list,
             commands::git_stash_apply,
             commands::git_stash_pop,
             commands::git_stash_drop,
+            commands::git_stash_show,
             commands::git_reset,
+            commands::git_reflog,
+            commands::git_undo_preview,
+            commands::git_undo,
+            commands::git_bisect_start,
+            commands::git_bisect_mark,
+            commands::git_bisect_state,
+            commands::git_bisect_run,
+            commands::git_bisect_reset,
+            commands::git_format_patch,
+            commands::git_apply_patch,
+            commands::git_am,
             commands::git_revert,
             commands::git_cherry_pick,
             commands::git_tags,
             commands::git_create_tag,
             commands::git_delete_tag,
+            commands::git_push_tags,
+            commands::git_worktrees,
+            commands::git_worktree_add,
+            commands::git_worktree_lock,
+            commands::git_worktree_unlock,
+            commands::git_worktree_prune,
+            commands::git_worktree_remove,
+            commands::git_submodules,
+            commands::git_submodule_init,
+            commands::git_submodule_update,
+            commands::git_submodule_sync,
             commands::git_clone,
             commands::git_init,
+            commands::git_sparse_checkout,
+            commands::git_sparse_checkout_set,
+            commands::git_sparse_checkout_disable,
             commands::git_config_get,
             commands::git_config_set,
+            commands::git_config_get_all,
+            commands::git_config_list,
+            commands::git_config_unset,
+            commands::git_config,
+            commands::git_config_update,
             commands::git_blame,
+            commands::git_search_history,
             commands::git_show_commit,
         ])
         .setup(move |app, _api| {
@@ -0,0 +1,202 @@
+use crate::{
+    conflicts::collect_conflicts,
+    error::{Error, Result},
+    models::*,
+    remote::fast_forward,
+    repository::{update_head, GitManager},
+    sequencer::sequencer_in_progress,
+    signing::create_commit,
+    utils::*,
+};
+use git2::{build::CheckoutBuilder, AnnotatedCommit, Oid, Repository, RepositoryState, ResetType};
+
+impl GitManager {
+    /// Merge a branch or revision into HEAD: fast-forward when possible (unless `noFastForward`),
+    /// otherwise a merge commit, or just stage the result for `squash`/`noCommit`.
+    pub fn merge(&self, repo_path: &str, options: GitMergeOptions) -> Result<GitMergeResult> {
+        let repo = self.get_repo(repo_path)?;
+        ensure_clean_state(&repo)?;
+
+        let incoming = resolve_annotated_commit(&repo, &options.branch)?;
+        let (analysis, preference) = repo.merge_analysis(&[&incoming])?;
+
+        if analysis.is_up_to_date() {
+            return Ok(merge_result(GitMergeStatus::UpToDate, None, Vec::new()));
+        }
+
+        let squash = options.squash.unwrap_or(false);
+        let no_fast_forward = options.no_fast_forward.unwrap_or(false) || preference.is_no_fast_forward();
+        let fast_forward_only = options.fast_forward_only.unwrap_or(false) || preference.is_fastforward_only();
+
+        if (analysis.is_fast_forward() || analysis.is_unborn()) && !no_fast_forward && !squash {
+            let refname = format!("refs/heads/{}", get_head_branch(&repo)?);
+            let reflog_msg = format!("merge {}: Fast-forward", options.branch);
+            fast_forward(&repo, &refname, incoming.id(), &reflog_msg)?;
+            return Ok(merge_result(GitMergeStatus::FastForward, Some(incoming.id()), Vec::new()));
+        }
+
+        if fast_forward_only {
+            return Err(Error::NotFastForward(format!("HEAD has diverged from {}", options.branch)));
+        }
+
+        merge_annotated(&repo, &incoming, squash, options.no_commit.unwrap_or(false), options.message.as_deref())
+    }
+
+    /// Commit a merge whose conflicts have been resolved (or that was started with `noCommit`).
+    pub fn merge_continue(&self, repo_path: &str, message: Option<String>) -> Result<String> {
+        let mut repo = self.get_repo(repo_path)?;
+        if repo.state() != RepositoryState::Merge {
+            return Err(Error::InvalidState("no merge in progress".to_string()));
+        }
+
+        let mut merge_heads = Vec::new();
+        repo.mergehead_foreach(|oid| {
+            merge_heads.push(*oid);
+            true
+        })?;
+
+        Ok(commit_merge(&repo, &merge_heads, message.as_deref())?.to_string())
+    }
+
+    pub fn merge_abort(&self, repo_path: &str) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        if repo.state() != RepositoryState::Merge {
+            return Err(Error::InvalidState("no merge in progress".to_string()));
+        }
+
+        let head = repo.head()?.peel_to_commit()?;
+        repo.reset(head.as_object(), ResetType::Hard, Some(CheckoutBuilder::new().force()))?;
+        repo.cleanup_state()?;
+        Ok(())
+    }
+}
+
+pub(crate) fn ensure_clean_state(repo: &Repository) -> Result<()> {
+    if sequencer_in_progress(repo) {
+        return Err(Error::InvalidState("interactive rebase, cherry-pick or revert in progress".to_string()));
+    }
+
+    match repo.state() {
+        RepositoryState::Clean => Ok(()),
+        state => Err(Error::InvalidState(format!(
+            "{:?} in progress",
+            convert_repository_state(state)
+        ))),
+    }
+}
+
+/// Perform a three-way merge of `incoming` into HEAD, leaving conflicts in the index and
+/// working tree when they occur.
+pub(crate) fn merge_annotated(
+    repo: &Repository,
+    incoming: &AnnotatedCommit,
+    squash: bool,
+    no_commit: bool,
+    message: Option<&str>,
+) -> Result<GitMergeResult> {
+    repo.merge(&[incoming], None, None)?;
+
+    // A squash merge has no second parent, so it must not leave MERGE_HEAD behind
+    if squash {
+        repo.cleanup_state()?;
+    }
+
+    let index = repo.index()?;
+    if index.has_conflicts() {
+        let conflicts = collect_conflicts(repo, &index)?;
+        return Ok(merge_result(GitMergeStatus::Conflicts, None, conflicts));
+    }
+
+    if squash || no_commit {
+        return Ok(merge_result(GitMergeStatus::Staged, None, Vec::new()));
+    }
+
+    let oid = commit_merge(repo, &[incoming.id()], message)?;
+    Ok(merge_result(GitMergeStatus::Merged, Some(oid), Vec::new()))
+}
+
+/// Create the merge commit from the index with HEAD and `merge_heads` as parents.
+pub(crate) fn commit_merge(repo: &Repository, merge_heads: &[Oid], message: Option<&str>) -> Result<Oid> {
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        return Err(Error::MergeConflict);
+    }
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = repo.signature()?;
+    let head = repo.head()?.peel_to_commit()?;
+
+    let mut parents = vec![head];
+    for oid in merge_heads {
+        parents.push(repo.find_commit(*oid)?);
+    }
+    let parent_refs: Vec<_> = parents.iter().collect();
+
+    // MERGE_MSG lists the conflicted paths in comment lines, which git strips before committing
+    let message = match message {
+        Some(message) => message.to_string(),
+        None => git2::message_prettify(repo.message().unwrap_or_else(|_| "Merge".to_string()), Some(b'#'))?,
+    };
+    let oid = create_commit(repo, &signature, &signature, &message, &tree, &parent_refs, None)?;
+    update_head(repo, oid, &format!("commit (merge): {}", message.lines().next().unwrap_or("")))?;
+    repo.cleanup_state()?;
+    Ok(oid)
+}
+
+fn merge_result(status: GitMergeStatus, commit: Option<Oid>, conflicts: Vec<GitConflict>) -> GitMergeResult {
+    GitMergeResult {
+        status,
+        commit: commit.map(|oid| oid.to_string()),
+        conflicts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+
+    #[test]
+    fn test_merge_conflict_resolve_and_continue() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(dir.path());
+
+        let base = commit_file(&repo, "file.txt", "base\n", "base");
+        let main = get_head_branch(&repo).unwrap();
+        repo.branch("feature", &repo.find_commit(base).unwrap(), false).unwrap();
+        commit_file(&repo, "file.txt", "ours\n", "ours");
+
+        repo.set_head("refs/heads/feature").unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force())).unwrap();
+        commit_file(&repo, "file.txt", "theirs\n", "theirs");
+        repo.set_head(&format!("refs/heads/{}", main)).unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force())).unwrap();
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+        let options = GitMergeOptions {
+            branch: "feature".to_string(),
+            ..Default::default()
+        };
+
+        let result = manager.merge(repo_path, options.clone()).unwrap();
+        assert_eq!(result.status, GitMergeStatus::Conflicts);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].ours.as_ref().unwrap().content.as_deref(), Some("ours\n"));
+        assert_eq!(result.conflicts[0].theirs.as_ref().unwrap().content.as_deref(), Some("theirs\n"));
+        assert!(matches!(manager.merge(repo_path, options), Err(Error::InvalidState(_))));
+
+        manager.resolve_conflict(repo_path, "file.txt", GitConflictResolution::Both).unwrap();
+        assert!(manager.get_conflicts(repo_path).unwrap().is_empty());
+        let resolved = std::fs::read_to_string(dir.path().join("file.txt")).unwrap();
+        assert!(resolved.contains("ours") && resolved.contains("theirs"));
+
+        let oid = manager.merge_continue(repo_path, None).unwrap();
+        let merge_commit = repo.find_commit(Oid::from_str(&oid).unwrap()).unwrap();
+        assert_eq!(merge_commit.parent_count(), 2);
+        let message = merge_commit.message().unwrap();
+        assert!(message.starts_with("Merge branch 'feature'"));
+        assert!(message.lines().all(|line| !line.starts_with('#')));
+        assert_eq!(repo.state(), RepositoryState::Clean);
+    }
+}
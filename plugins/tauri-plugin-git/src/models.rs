@@ -11,7 +11,7 @@ pub struct GitRepository {
     pub status: GitStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitStatus {
     pub branch: String,
@@ -21,17 +21,115 @@ pub struct GitStatus {
     pub unstaged: Vec<GitFileChange>,
     pub untracked: Vec<String>,
     pub conflicted: Vec<String>,
+    /// Root of the working tree this status describes
+    pub workdir: Option<String>,
+    /// Name of the linked worktree, or `None` for the main working tree
+    pub worktree: Option<String>,
+    /// Submodules with staged or unstaged changes; they are not repeated in the file lists
+    pub submodules: Vec<GitSubmoduleStatus>,
+    /// Git LFS files in the index and whether their content has been downloaded
+    pub lfs: Vec<GitLfsFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitLfsFile {
+    pub path: String,
+    pub state: GitLfsState,
+    /// SHA-256 of the content, from the pointer
+    pub oid: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitLfsState {
+    /// The working tree holds the pointer file; the content was never downloaded
+    Pointer,
+    /// The working tree holds the real content
+    Smudged,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatusOptions {
+    /// Include the full status of each changed submodule, recursively
+    pub recurse_submodules: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWatchOptions {
+    /// Quiet period before a burst of file changes is reported (default: 300)
+    pub debounce_ms: Option<u64>,
+    pub recurse_submodules: Option<bool>,
+}
+
+/// Payload of the `plugin:git:status-changed` event emitted by repository watchers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatusChange {
+    pub watch_id: String,
+    pub repo_path: String,
+    pub status: GitStatus,
+    /// How `status` differs from the previous update
+    pub delta: GitStatusDelta,
+    /// Working tree paths that changed since the previous update
+    pub changed_paths: Vec<String>,
+    pub index_changed: bool,
+    pub head_changed: bool,
+    pub refs_changed: bool,
+}
+
+/// The entries of each `GitStatus` list that were added, removed or changed, matched by path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatusDelta {
+    pub staged: GitListDelta<GitFileChange>,
+    pub unstaged: GitListDelta<GitFileChange>,
+    pub untracked: GitListDelta<String>,
+    pub conflicted: GitListDelta<String>,
+    pub submodules: GitListDelta<GitSubmoduleStatus>,
+    pub lfs: GitListDelta<GitLfsFile>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct GitListDelta<T> {
+    pub added: Vec<T>,
+    pub removed: Vec<T>,
+    /// Entries whose path is still listed but whose details differ, with their new values
+    pub changed: Vec<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSubmoduleStatus {
+    pub name: String,
+    pub path: String,
+    /// Commit recorded in the index
+    pub recorded_commit: Option<String>,
+    /// Commit checked out in the submodule
+    pub checked_out_commit: Option<String>,
+    /// The recorded commit differs from HEAD
+    pub staged: bool,
+    /// The checked-out commit differs from the recorded one
+    pub new_commits: bool,
+    pub modified_content: bool,
+    pub untracked_content: bool,
+    /// Status inside the submodule, when requested with `recurseSubmodules`
+    pub status: Option<Box<GitStatus>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GitFileChange {
     pub path: String,
     pub status: FileStatus,
     pub old_path: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FileStatus {
     Added,
@@ -53,6 +151,8 @@ pub struct GitCommit {
     pub timestamp: DateTime<Utc>,
     pub parents: Vec<String>,
     pub files: Option<Vec<GitFileChange>>,
+    /// Set for signed commits when signatures were requested
+    pub signature: Option<GitSignature>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,16 +182,56 @@ pub struct GitRemote {
     pub url: String,
     pub fetch_url: Option<String>,
     pub push_url: Option<String>,
+    pub fetch_refspecs: Vec<String>,
+    pub push_refspecs: Vec<String>,
+    /// Last fetch that updated a remote-tracking branch or wrote FETCH_HEAD for this remote
+    pub last_fetched: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitTag {
     pub name: String,
+    /// Commit (or other object) the tag points at, peeled through annotated tags
     pub target: String,
+    pub annotated: bool,
     pub tagger: Option<GitAuthor>,
     pub message: Option<String>,
     pub timestamp: Option<DateTime<Utc>>,
+    pub signature: Option<GitSignature>,
+}
+
+/// A GPG, X.509 or SSH signature on a tag or commit and the result of checking it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSignature {
+    pub format: GitSignatureFormat,
+    pub status: GitSignatureStatus,
+    /// User id or SSH principal of the signer, when known
+    pub signer: Option<String>,
+    /// Key id or fingerprint that made the signature
+    pub key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitSignatureFormat {
+    Openpgp,
+    X509,
+    Ssh,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitSignatureStatus {
+    Good,
+    Bad,
+    Expired,
+    Revoked,
+    /// The signature is intact but the key is not trusted or not known
+    UnknownKey,
+    /// No verifier is available or configured
+    Unverified,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,6 +239,10 @@ pub struct GitTag {
 pub struct GitDiff {
     pub old_path: String,
     pub new_path: String,
+    pub status: FileStatus,
+    pub is_binary: bool,
+    /// Tracked by Git LFS; pointer changes are reported without hunks, like binary files
+    pub is_lfs: bool,
     pub hunks: Vec<GitDiffHunk>,
     pub stats: GitDiffStats,
 }
@@ -141,21 +285,97 @@ pub struct GitDiffStats {
     pub files_changed: usize,
 }
 
+/// A commit with everything needed to inspect it: changed files, line stats, trailers and,
+/// on request, the full diff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitDetails {
+    /// The commit, with `files` filled in
+    pub commit: GitCommit,
+    pub summary: String,
+    /// Everything after the summary line, including trailers
+    pub body: Option<String>,
+    pub trailers: Vec<GitTrailer>,
+    /// Line counts for each entry of `commit.files`, in the same order
+    pub file_stats: Vec<GitFileStats>,
+    pub stats: GitDiffStats,
+    pub diff: Option<Vec<GitDiff>>,
+}
+
+/// A `Key: value` trailer at the end of a commit message, such as `Signed-off-by`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitTrailer {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileStats {
+    pub path: String,
+    pub additions: usize,
+    pub deletions: usize,
+    pub is_binary: bool,
+}
+
+/// Selects a hunk of a file's diff, or a contiguous run of lines within it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHunkSelection {
+    pub file_path: String,
+    pub hunk_index: usize,
+    pub line_range: Option<GitLineRange>,
+}
+
+/// Inclusive range of indices into `GitDiffHunk::lines`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitLineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitStashEntry {
+    /// Id of the stash commit
     pub id: String,
+    /// Position in the stash list (`stash@{index}`)
+    pub index: usize,
     pub message: String,
     pub branch: String,
+    /// Commit the stash was made on
+    pub commit: String,
     pub timestamp: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitBlame {
+    pub path: String,
+    /// Commit that was blamed, `None` when blaming the working tree
+    pub revision: Option<String>,
+    pub hunks: Vec<GitBlameHunk>,
     pub lines: Vec<GitBlameLine>,
 }
 
+/// Consecutive lines last changed by the same commit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBlameHunk {
+    /// All zeros for lines that are not committed yet
+    pub commit: String,
+    pub summary: String,
+    pub author: GitAuthor,
+    pub timestamp: DateTime<Utc>,
+    pub start_line: usize,
+    pub line_count: usize,
+    /// Path and first line of the hunk in `commit`, which differ after renames and moves
+    pub orig_path: String,
+    pub orig_start_line: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitBlameLine {
@@ -172,29 +392,111 @@ pub struct GitBlameLine {
 pub struct GitCommitOptions {
     pub message: String,
     pub amend: Option<bool>,
+    /// Author as "Name <email>"; the committer is always the configured user
     pub author: Option<String>,
     pub signoff: Option<bool>,
+    /// Sign the commit; defaults to `commit.gpgSign`
+    pub sign: Option<bool>,
+    /// Skip the `pre-commit` and `commit-msg` hooks
+    pub no_verify: Option<bool>,
 }
 
+/// Payload of the `plugin:git:hook-output` event, one per line a hook prints.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct GitHookOutput {
+    pub hook: String,
+    pub stream: GitHookStream,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitHookStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCloneOptions {
+    /// Branch to check out instead of the remote's default branch
+    pub branch: Option<String>,
+    /// Fetch only this many commits of history
+    pub depth: Option<u32>,
+    /// Only fetch the checked-out branch
+    pub single_branch: Option<bool>,
+    /// Clone and check out submodules, including nested ones
+    pub recurse_submodules: Option<bool>,
+    pub bare: Option<bool>,
+    /// Set up a cone sparse checkout of these directories
+    pub sparse_paths: Option<Vec<String>>,
+    pub credentials: Option<GitCredentials>,
+    pub operation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitInitOptions {
+    /// Name of the initial branch (default: `init.defaultBranch`, else `master`)
+    pub initial_branch: Option<String>,
+    /// Directory whose contents are copied into the new git dir
+    pub template_dir: Option<String>,
+    pub bare: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GitFetchOptions {
     pub remote: Option<String>,
     pub prune: Option<bool>,
     pub tags: Option<bool>,
     pub depth: Option<u32>,
+    pub credentials: Option<GitCredentials>,
+    pub operation_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRemoteUrlOptions {
+    /// New fetch URL
+    pub url: Option<String>,
+    /// Separate URL for pushes
+    pub push_url: Option<String>,
+    /// Drop the push URL so pushes use the fetch URL again
+    pub clear_push_url: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRemoteRefspecs {
+    /// Replacement fetch refspecs, e.g. `+refs/heads/*:refs/remotes/origin/*`
+    pub fetch: Option<Vec<String>>,
+    /// Replacement push refspecs; an empty list restores the default push behavior
+    pub push: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRemotePruneOptions {
+    /// List the stale branches without deleting them
+    pub dry_run: Option<bool>,
+    pub credentials: Option<GitCredentials>,
+    pub operation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitPullOptions {
     pub remote: Option<String>,
     pub branch: Option<String>,
     pub rebase: Option<bool>,
     pub fast_forward_only: Option<bool>,
+    pub credentials: Option<GitCredentials>,
+    pub operation_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitPushOptions {
     pub remote: Option<String>,
@@ -202,23 +504,306 @@ pub struct GitPushOptions {
     pub force: Option<bool>,
     pub set_upstream: Option<bool>,
     pub tags: Option<bool>,
+    pub credentials: Option<GitCredentials>,
+    pub operation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCreateTagOptions {
+    /// Revision to tag (default: HEAD)
+    pub target: Option<String>,
+    /// Replace an existing tag with the same name
+    pub force: Option<bool>,
+    /// Sign the tag; defaults to `tag.gpgSign`. Implies an annotated tag.
+    pub sign: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitTagPushOptions {
+    pub remote: Option<String>,
+    pub tags: Vec<String>,
+    /// Delete the tags on the remote instead of pushing them
+    pub delete: Option<bool>,
+    pub force: Option<bool>,
+    pub credentials: Option<GitCredentials>,
+    pub operation_id: Option<String>,
+}
+
+/// How to authenticate against a remote. When omitted, the SSH agent, default SSH keys
+/// and the configured credential helper are tried in turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum GitCredentials {
+    #[serde(rename_all = "camelCase")]
+    SshAgent {
+        username: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    SshKey {
+        username: Option<String>,
+        private_key_path: String,
+        public_key_path: Option<String>,
+        passphrase: Option<String>,
+    },
+    CredentialHelper,
+    #[serde(rename_all = "camelCase")]
+    Token {
+        username: Option<String>,
+        token: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitRemoteOperation {
+    Fetch,
+    Pull,
+    Push,
+    Submodule,
+    Clone,
 }
 
+/// Payload of the `plugin:git:progress` event emitted during remote operations.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct GitTransferProgress {
+    pub operation_id: String,
+    pub operation: GitRemoteOperation,
+    pub total_objects: usize,
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub local_objects: usize,
+    pub total_deltas: usize,
+    pub indexed_deltas: usize,
+    pub received_bytes: usize,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GitCheckoutOptions {
     pub branch: Option<String>,
     pub create_branch: Option<bool>,
     pub force: Option<bool>,
+    /// Remote branch (e.g. `origin/feature`) to create `branch` from and track. `branch`
+    /// defaults to its name without the remote.
+    pub track: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranchListOptions {
+    /// Only branches whose tip is reachable from this revision
+    pub merged_into: Option<String>,
+    /// Only branches with commits this revision does not contain
+    pub not_merged_into: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitMergeOptions {
     pub branch: String,
     pub fast_forward_only: Option<bool>,
+    pub no_fast_forward: Option<bool>,
     pub no_commit: Option<bool>,
     pub squash: Option<bool>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitMergeStatus {
+    UpToDate,
+    FastForward,
+    Merged,
+    /// The merge result is in the index but was not committed (`noCommit` or `squash`).
+    Staged,
+    Conflicts,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitMergeResult {
+    pub status: GitMergeStatus,
+    pub commit: Option<String>,
+    pub conflicts: Vec<GitConflict>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRebaseOptions {
+    pub upstream: String,
+    pub onto: Option<String>,
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitRebaseStatus {
+    Completed,
+    Conflicts,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRebaseResult {
+    pub status: GitRebaseStatus,
+    pub current_commit: Option<String>,
+    pub applied: usize,
+    pub total: usize,
+    pub conflicts: Vec<GitConflict>,
+}
+
+/// A conflicted path with the base, ours and theirs versions from the index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConflict {
+    pub path: String,
+    pub ancestor: Option<GitConflictSide>,
+    pub ours: Option<GitConflictSide>,
+    pub theirs: Option<GitConflictSide>,
+    pub is_binary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConflictSide {
+    pub path: String,
+    pub oid: String,
+    /// `None` for binary blobs
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitConflictResolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitRepositoryState {
+    Clean,
+    Merge,
+    Revert,
+    RevertSequence,
+    CherryPick,
+    CherryPickSequence,
+    Bisect,
+    Rebase,
+    RebaseInteractive,
+    RebaseMerge,
+    ApplyMailbox,
+    ApplyMailboxOrRebase,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitOperationState {
+    pub state: GitRepositoryState,
+    pub merge_heads: Vec<String>,
+    pub rebase: Option<GitRebaseProgress>,
+    pub conflicted: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRebaseProgress {
+    pub current: Option<usize>,
+    pub total: usize,
+    pub orig_head: Option<String>,
+    pub orig_head_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitRebaseAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+    /// Apply the inverse of the commit, as `git revert` does
+    Revert,
+}
+
+/// One line of an interactive rebase plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRebaseTodoItem {
+    pub action: GitRebaseAction,
+    pub commit: String,
+    /// Replacement message for `reword` and `squash`
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitInteractiveRebaseOptions {
+    /// Revision the plan is replayed onto
+    pub onto: String,
+    /// Steps in the order they are applied (oldest commit first)
+    pub todo: Vec<GitRebaseTodoItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitSequencerStatus {
+    Completed,
+    /// Stopped on conflicts in `current`
+    Conflicts,
+    /// Stopped after applying an `edit` step so the commit can be amended
+    Edit,
+}
+
+/// Command that started a sequencer run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitSequencerOperation {
+    #[default]
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+/// Resumable state of an interactive rebase, cherry-pick or revert, persisted in the git
+/// directory between steps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSequencerState {
+    #[serde(default)]
+    pub operation: GitSequencerOperation,
+    pub status: GitSequencerStatus,
+    pub onto: String,
+    pub orig_head: String,
+    /// Branch being rebased, `None` when HEAD was detached
+    pub head_name: Option<String>,
+    pub done: Vec<GitRebaseTodoItem>,
+    pub todo: Vec<GitRebaseTodoItem>,
+    pub current: Option<GitRebaseTodoItem>,
+    #[serde(default)]
+    pub conflicts: Vec<GitConflict>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCherryPickOptions {
+    /// Revisions or `A..B` ranges, applied in the order given (ranges oldest first)
+    pub commits: Vec<String>,
+    /// Append "(cherry picked from commit ...)" to the messages, like `git cherry-pick -x`
+    pub record_origin: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRevertOptions {
+    /// Revisions or `A..B` ranges, reverted in the order given (ranges newest first)
+    pub commits: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -238,14 +823,386 @@ pub enum ResetMode {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct GitSubmodule {
+    pub name: String,
+    pub path: String,
+    pub url: Option<String>,
+    pub branch: Option<String>,
+    /// Commit recorded in the index (falling back to HEAD)
+    pub recorded_commit: Option<String>,
+    /// Commit checked out in the submodule's working tree
+    pub checked_out_commit: Option<String>,
+    /// Registered in `.git/config` by `git_submodule_init`
+    pub is_initialized: bool,
+    pub is_checked_out: bool,
+    /// Modified or untracked files inside the submodule
+    pub is_dirty: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSubmoduleUpdateOptions {
+    /// Submodule paths to update (default: all)
+    pub paths: Option<Vec<String>>,
+    /// Initialise submodules that are not yet registered
+    pub init: Option<bool>,
+    /// Also update nested submodules
+    pub recursive: Option<bool>,
+    pub credentials: Option<GitCredentials>,
+    pub operation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktree {
+    /// Name under `.git/worktrees`, or `None` for the main working tree
+    pub name: Option<String>,
+    pub path: String,
+    pub is_main: bool,
+    /// Checked-out branch; `None` when HEAD is detached
+    pub branch: Option<String>,
+    pub head: Option<String>,
+    pub is_locked: bool,
+    pub lock_reason: Option<String>,
+    /// The working tree directory is gone and `git_worktree_prune` would remove the entry
+    pub is_prunable: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktreeAddOptions {
+    pub path: String,
+    /// Worktree name (default: the last component of `path`)
+    pub name: Option<String>,
+    /// Branch to check out; when unset a branch named after the worktree is created at HEAD
+    pub branch: Option<String>,
+    /// Create `branch` at `start_point` instead of checking out an existing branch
+    pub create_branch: Option<bool>,
+    pub start_point: Option<String>,
+    pub lock: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSparseCheckout {
+    pub enabled: bool,
+    /// Directories in the cone; top-level files are always checked out
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GitStashOptions {
     pub message: Option<String>,
     pub include_untracked: Option<bool>,
     pub keep_index: Option<bool>,
+    /// Only stash changes to these paths
+    pub paths: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBlameOptions {
+    /// Revision to blame; the working tree (including uncommitted lines) when unset
+    pub revision: Option<String>,
+    pub ignore_whitespace: Option<bool>,
+    /// Follow lines moved or copied from other files (default: true)
+    pub detect_copies: Option<bool>,
+    /// Skip the revisions listed in `blame.ignoreRevsFile` or `.git-blame-ignore-revs` (default: true)
+    pub use_ignore_revs_file: Option<bool>,
+    /// Additional revisions to skip
+    pub ignore_revs: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitDiffOptions {
+    pub paths: Option<Vec<String>>,
+    pub context_lines: Option<u32>,
+    pub ignore_whitespace: Option<bool>,
+    pub include_untracked: Option<bool>,
+    pub detect_renames: Option<bool>,
+    pub detect_copies: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitReflogEntry {
+    pub index: usize,
+    /// Selector for this entry, such as `HEAD@{2}`
+    pub selector: String,
+    /// Unset when the entry created the reference
+    pub old_hash: Option<String>,
+    pub new_hash: String,
+    pub committer: GitAuthor,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitReflogOptions {
+    /// `HEAD`, a branch name or a full reference name (default: HEAD)
+    pub reference: Option<String>,
+    pub max_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitUndoOptions {
+    /// Reference whose reflog is used (default: HEAD when `entry` is given, as listed by
+    /// `reflog`; otherwise the current branch, or HEAD when detached)
+    pub reference: Option<String>,
+    /// Reflog entry to undo; it and every later entry are reverted (default: 0, the last one)
+    pub entry: Option<usize>,
+}
+
+/// What undoing a reflog entry changes. Returned both as a preview and after applying it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitUndoPlan {
+    /// Reference that is moved, such as `refs/heads/main`, or `HEAD` when detached
+    pub reference: String,
+    pub entry: GitReflogEntry,
+    pub current_hash: String,
+    pub target_hash: String,
+    /// Commits that will no longer be reachable from the reference
+    pub commits_removed: Vec<GitCommit>,
+    /// Commits that become reachable again
+    pub commits_restored: Vec<GitCommit>,
+    /// Files that change in the working tree, or in the tree of the reference when it is not checked out
+    pub files: Vec<GitFileChange>,
+    /// Whether the working tree is updated, which requires it to be clean
+    pub updates_worktree: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitBisectMark {
+    Good,
+    Bad,
+    /// The commit cannot be tested
+    Skip,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBisectStartOptions {
+    /// Revision known to be bad (default: HEAD)
+    pub bad: Option<String>,
+    /// Revisions known to be good
+    pub good: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBisectMarkOptions {
+    pub mark: GitBisectMark,
+    /// Revision to mark (default: the commit being tested)
+    pub commit: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBisectRunOptions {
+    /// Shell command run from the top of the working tree at each step. Exit status 0 marks
+    /// the commit good, 125 skips it, and 1-127 mark it bad; anything else stops the run.
+    pub command: String,
+    pub operation_id: Option<String>,
+}
+
+/// Progress of the bisect session in progress.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBisectState {
+    /// Branch or commit that was checked out when the session started
+    pub original_head: String,
+    pub bad: Option<String>,
+    pub good: Vec<String>,
+    pub skipped: Vec<String>,
+    /// Commit checked out for testing; `None` until a good and a bad commit are known, and once
+    /// the session is finished
+    pub current: Option<GitCommit>,
+    /// Commits that may still be the first bad commit
+    pub remaining: usize,
+    /// Rough number of steps left
+    pub remaining_steps: usize,
+    pub first_bad_commit: Option<GitCommit>,
+    /// When only skipped commits are left to test, the commits that may be the first bad one
+    pub possible_first_bad: Vec<GitCommit>,
+}
+
+/// Payload of the `plugin:git:bisect-step` event, sent after each step of `git_bisect_run`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBisectStep {
+    pub commit: String,
+    pub exit_code: i32,
+    pub mark: GitBisectMark,
+}
+
+/// Payload of the `plugin:git:bisect-output` event, one per line the command prints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBisectOutput {
+    pub commit: String,
+    pub stream: GitHookStream,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitSearchMode {
+    /// Commits that change how often the query occurs in a file, like `git log -S`
+    #[default]
+    Occurrences,
+    /// Commits that add or remove a line matching the query, like `git log -G`
+    Lines,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSearchOptions {
+    pub query: String,
+    /// Treat `query` as a regular expression (always the case in `lines` mode)
+    pub regex: Option<bool>,
+    pub mode: Option<GitSearchMode>,
+    pub ignore_case: Option<bool>,
+    /// Only search these files or directories
+    pub paths: Option<Vec<String>>,
+    /// Revision to search back from (default: HEAD)
+    pub revision: Option<String>,
+    /// Search the history of every reference instead
+    pub all: Option<bool>,
+    /// Stop after this many matching commits
+    pub max_count: Option<usize>,
+    pub operation_id: Option<String>,
+}
+
+/// A commit found by `git_search_history`, also sent as a `plugin:git:search-result` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSearchMatch {
+    pub operation_id: String,
+    pub commit: GitCommit,
+    pub files: Vec<GitSearchFileMatch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSearchFileMatch {
+    pub path: String,
+    pub old_path: Option<String>,
+    /// Added and removed lines that contain the query
+    pub lines: Vec<GitDiffLine>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFormatPatchOptions {
+    /// `A..B`, or a single revision for the commits after it up to HEAD, as with `git format-patch`
+    pub range: String,
+    /// Also write each patch to this directory as `0001-<subject>.patch`
+    pub output_dir: Option<String>,
+    /// Text in the subject's brackets (default: "PATCH")
+    pub subject_prefix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFormattedPatch {
+    pub commit: String,
+    pub file_name: String,
+    pub content: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct GitPatchSeries {
+    /// One patch per commit, oldest first; merge commits are left out
+    pub patches: Vec<GitFormattedPatch>,
+    /// Every patch in a single mbox
+    pub mbox: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitApplyLocation {
+    #[default]
+    Workdir,
+    Index,
+    Both,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitApplyPatchOptions {
+    /// Patch text, as written by `git diff` or `git format-patch`
+    pub patch: Option<String>,
+    /// Patch file to read instead of `patch`
+    pub path: Option<String>,
+    pub location: Option<GitApplyLocation>,
+    /// When the patch does not apply, merge it in from the blobs it was made against, like
+    /// `git apply --3way`. The result is written to both the index and the working tree.
+    pub three_way: Option<bool>,
+    /// Only check whether the patch applies
+    pub check: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitApplyResult {
+    pub files: Vec<String>,
+    /// Whether the three-way fallback was needed
+    pub three_way: bool,
+    /// Files left with conflict markers by the three-way fallback
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitAmOptions {
+    /// Mbox text, as written by `git format-patch`
+    pub mbox: Option<String>,
+    /// Mbox file to read instead of `mbox`
+    pub path: Option<String>,
+    /// Merge patches that do not apply from the blobs they were made against
+    pub three_way: Option<bool>,
+    /// Carry on past patches that fail instead of stopping at the first one
+    pub skip_failed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitAmPatchStatus {
+    Applied,
+    Failed,
+    /// Not attempted because an earlier patch failed
+    Pending,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitAmPatch {
+    pub subject: String,
+    pub author: Option<GitAuthor>,
+    pub status: GitAmPatchStatus,
+    /// Commit created for the patch
+    pub commit: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitShowCommitOptions {
+    /// Include the hunks of every changed file
+    pub include_diff: Option<bool>,
+    /// Index of the parent a merge commit is compared against (default: the first parent)
+    pub parent: Option<usize>,
+    pub diff_options: Option<GitDiffOptions>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GitLogOptions {
     pub max_count: Option<usize>,
     pub skip: Option<usize>,
@@ -261,6 +1218,118 @@ pub struct GitLogOptions {
     pub graph: Option<bool>,
     pub follow: Option<bool>,
     pub path: Option<String>,
+    /// Verify commit signatures (runs gpg or ssh-keygen for each signed commit)
+    pub show_signature: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitGraphOptions {
+    /// Revisions to start from (default: HEAD)
+    pub revisions: Option<Vec<String>>,
+    /// Start from every branch, remote-tracking branch and tag
+    pub all: Option<bool>,
+    /// Only show commits touching this file or directory
+    pub path: Option<String>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD`
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub author: Option<String>,
+    pub grep: Option<String>,
+    pub limit: Option<usize>,
+    /// `nextCursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitGraphPage {
+    pub nodes: Vec<GitGraphNode>,
+    /// `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// A commit row of the history graph. Columns are lane indices; a row's lines are the
+/// `incoming` lanes ending at the node, the `outgoing` lanes to its parents and the lanes
+/// that `passThrough` the row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitGraphNode {
+    pub commit: GitCommit,
+    pub column: usize,
+    pub incoming: Vec<usize>,
+    pub outgoing: Vec<usize>,
+    pub pass_through: Vec<usize>,
+    pub refs: Vec<GitRefDecoration>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRefDecoration {
+    pub name: String,
+    pub full_name: String,
+    pub kind: GitRefKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitRefKind {
+    Head,
+    Branch,
+    Remote,
+    Tag,
+}
+
+/// Config file to read or write. Reads without a scope see the merged configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitConfigScope {
+    System,
+    /// `~/.gitconfig`, or `~/.config/git/config` when only that exists
+    Global,
+    Local,
+    /// `config.worktree` of the current worktree; needs `extensions.worktreeConfig`
+    Worktree,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConfigEntry {
+    pub name: String,
+    pub value: Option<String>,
+    /// File the entry comes from; `None` for values set by the application
+    pub scope: Option<GitConfigScope>,
+}
+
+/// A config value as JSON: booleans and integers are written in git's canonical form.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GitConfigValue {
+    Bool(bool),
+    Int(i64),
+    String(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConfigSetOptions {
+    /// File to write (default: local)
+    pub scope: Option<GitConfigScope>,
+    /// Add another value to a multi-valued key instead of replacing it
+    pub append: Option<bool>,
+    /// Only replace the values matching this regular expression
+    pub value_pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConfigUnsetOptions {
+    /// File to write (default: local)
+    pub scope: Option<GitConfigScope>,
+    /// Remove every value of a multi-valued key
+    pub all: Option<bool>,
+    /// Only remove the values matching this regular expression
+    pub value_pattern: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
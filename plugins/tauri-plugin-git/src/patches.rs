@@ -0,0 +1,659 @@
+use crate::{
+    error::{Error, Result},
+    merge::ensure_clean_state,
+    models::*,
+    repository::GitManager,
+    sequencer::{ensure_clean_worktree, resolve_commits},
+    signing::create_commit,
+    utils::*,
+};
+use git2::{
+    build::{CheckoutBuilder, TreeUpdateBuilder},
+    ApplyLocation, ApplyOptions, Commit, Delta, Diff, Email, EmailCreateOptions, Index, ObjectType, Oid, Repository,
+    Signature, Status, Time, Tree,
+};
+use std::path::Path;
+
+impl GitManager {
+    /// Export commits as mbox-formatted patches, oldest first, like `git format-patch`.
+    pub fn format_patch(&self, repo_path: &str, options: GitFormatPatchOptions) -> Result<GitPatchSeries> {
+        let repo = self.get_repo(repo_path)?;
+        let range = match options.range.contains("..") {
+            true => options.range.clone(),
+            false => format!("{}..HEAD", options.range),
+        };
+
+        // Merges have no single diff to send, so format-patch leaves them out
+        let mut commits = Vec::new();
+        for oid in resolve_commits(&repo, &range, true)? {
+            let commit = repo.find_commit(oid)?;
+            if commit.parent_count() <= 1 {
+                commits.push(commit);
+            }
+        }
+
+        let mut patches = Vec::new();
+        for (i, commit) in commits.iter().enumerate() {
+            patches.push(GitFormattedPatch {
+                commit: commit.id().to_string(),
+                file_name: patch_file_name(i + 1, commit.summary().unwrap_or("")),
+                content: format_commit(&repo, commit, i + 1, commits.len(), options.subject_prefix.as_deref())?,
+            });
+        }
+
+        if let Some(dir) = &options.output_dir {
+            std::fs::create_dir_all(dir)?;
+            for patch in &patches {
+                std::fs::write(Path::new(dir).join(&patch.file_name), &patch.content)?;
+            }
+        }
+        let mbox = patches.iter().map(|patch| patch.content.as_str()).collect();
+        Ok(GitPatchSeries { patches, mbox })
+    }
+
+    /// Apply a patch to the working tree, the index or both, like `git apply`.
+    pub fn apply_patch(&self, repo_path: &str, options: GitApplyPatchOptions) -> Result<GitApplyResult> {
+        let repo = self.get_repo(repo_path)?;
+        let text = read_input(options.patch, options.path, "patch")?;
+        let diff = parse_patch(&text)?;
+        let files = patch_paths(&diff);
+        let check = options.check.unwrap_or(false);
+
+        let location = match options.location.unwrap_or_default() {
+            GitApplyLocation::Workdir => ApplyLocation::WorkDir,
+            GitApplyLocation::Index => ApplyLocation::Index,
+            GitApplyLocation::Both => ApplyLocation::Both,
+        };
+        let mut apply_opts = ApplyOptions::new();
+        apply_opts.check(check);
+        match repo.apply(&diff, location, Some(&mut apply_opts)) {
+            Ok(()) => {
+                return Ok(GitApplyResult {
+                    files,
+                    three_way: false,
+                    conflicts: Vec::new(),
+                })
+            }
+            Err(e) if !options.three_way.unwrap_or(false) => return Err(e.into()),
+            Err(_) => {}
+        }
+
+        // The merge starts from the index, so the files must not have unstaged changes
+        for path in &files {
+            let status = repo.status_file(Path::new(path)).unwrap_or(Status::CURRENT);
+            if status.intersects(Status::WT_MODIFIED | Status::WT_DELETED | Status::WT_TYPECHANGE) {
+                return Err(Error::UncommittedChanges);
+            }
+        }
+        let mut index = repo.index()?;
+        let ours = repo.find_tree(index.write_tree()?)?;
+        let mut merged = merge_patch(&repo, &ours, &diff)?;
+        let conflicts = conflicted_paths(&merged)?;
+        if !check {
+            write_merge_result(&repo, &mut index, &mut merged, &files)?;
+        }
+        Ok(GitApplyResult {
+            files,
+            three_way: true,
+            conflicts,
+        })
+    }
+
+    /// Commit each patch of an mbox on top of HEAD, like `git am`. The working tree must be
+    /// clean; it is updated once all patches have been tried.
+    pub fn am(&self, repo_path: &str, options: GitAmOptions) -> Result<Vec<GitAmPatch>> {
+        let repo = self.get_repo(repo_path)?;
+        ensure_clean_state(&repo)?;
+        ensure_clean_worktree(&repo)?;
+        let text = read_input(options.mbox, options.path, "mbox")?;
+        let mails: Vec<Mail> = split_mbox(&text).iter().map(|message| parse_mail(message)).collect();
+        if mails.is_empty() {
+            return Err(Error::InvalidPatch("no patches found".to_string()));
+        }
+
+        let head = repo.head()?;
+        let start = head.peel_to_commit()?;
+        let committer = repo.signature()?;
+        let mut tip = start.clone();
+        let mut failed = false;
+
+        let mut results = Vec::new();
+        for mail in &mails {
+            let mut result = GitAmPatch {
+                subject: mail.subject.clone(),
+                author: mail.author().ok().and_then(|author| convert_author(&author).ok()),
+                status: GitAmPatchStatus::Pending,
+                commit: None,
+                error: None,
+            };
+            if !failed || options.skip_failed.unwrap_or(false) {
+                match apply_mail(&repo, &tip, mail, &committer, options.three_way.unwrap_or(false)) {
+                    Ok(commit) => {
+                        result.status = GitAmPatchStatus::Applied;
+                        result.commit = Some(commit.id().to_string());
+                        tip = commit;
+                    }
+                    Err(e) => {
+                        result.status = GitAmPatchStatus::Failed;
+                        result.error = Some(e.to_string());
+                        failed = true;
+                    }
+                }
+            }
+            results.push(result);
+        }
+
+        if tip.id() != start.id() {
+            repo.checkout_tree(tip.as_object(), Some(CheckoutBuilder::new().safe()))?;
+            let applied = results.iter().filter(|patch| patch.status == GitAmPatchStatus::Applied).count();
+            match head.name().filter(|_| head.is_branch()) {
+                Some(branch) => {
+                    repo.find_reference(branch)?
+                        .set_target(tip.id(), &format!("am: applied {} patches", applied))?;
+                }
+                None => repo.set_head_detached(tip.id())?,
+            }
+        }
+        Ok(results)
+    }
+}
+
+fn format_commit(repo: &Repository, commit: &Commit, number: usize, total: usize, prefix: Option<&str>) -> Result<String> {
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+
+    let mut opts = EmailCreateOptions::new();
+    if let Some(prefix) = prefix {
+        opts.subject_prefix(prefix);
+    }
+    let email = Email::from_diff(
+        &diff,
+        number,
+        total,
+        &commit.id(),
+        commit.summary().unwrap_or(""),
+        commit.body().unwrap_or(""),
+        &commit.author(),
+        &mut opts,
+    )?;
+    Ok(String::from_utf8_lossy(email.as_slice()).into_owned())
+}
+
+/// `0001-Fix-the-thing.patch`, with the subject cut down to safe characters as git does.
+fn patch_file_name(number: usize, subject: &str) -> String {
+    let mut name = String::new();
+    for c in subject.chars() {
+        if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+            name.push(c);
+        } else if !name.is_empty() && !name.ends_with('-') {
+            name.push('-');
+        }
+    }
+    name.truncate(52);
+    let name = name.trim_start_matches('.').trim_end_matches(['-', '.']);
+    format!("{:04}-{}.patch", number, name)
+}
+
+fn read_input(text: Option<String>, path: Option<String>, what: &str) -> Result<String> {
+    match (text, path) {
+        (Some(text), _) => Ok(text),
+        (None, Some(path)) => std::fs::read_to_string(&path).map_err(|_| Error::FileNotFound(path)),
+        (None, None) => Err(Error::InvalidPatch(format!("no {} given", what))),
+    }
+}
+
+fn parse_patch(text: &str) -> Result<Diff<'static>> {
+    let diff = Diff::from_buffer(text.as_bytes()).map_err(|e| Error::InvalidPatch(e.message().to_string()))?;
+    if diff.deltas().len() == 0 {
+        return Err(Error::InvalidPatch("the patch is empty".to_string()));
+    }
+    Ok(diff)
+}
+
+/// Every path a patch touches, including the old side of renames.
+fn patch_paths(diff: &Diff) -> Vec<String> {
+    let mut paths = Vec::new();
+    for delta in diff.deltas() {
+        for file in [delta.old_file(), delta.new_file()] {
+            if let Some(path) = file.path().map(|path| path.to_string_lossy().to_string()) {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+    }
+    paths
+}
+
+/// Merge a patch into `ours` from the blobs it was made against, as `git apply --3way` does.
+fn merge_patch(repo: &Repository, ours: &Tree, diff: &Diff) -> Result<Index> {
+    let base = preimage_tree(repo, ours, diff)?;
+    let mut theirs = repo
+        .apply_to_tree(&base, diff, None)
+        .map_err(|e| Error::InvalidPatch(e.message().to_string()))?;
+    let theirs = repo.find_tree(theirs.write_tree_to(repo)?)?;
+    Ok(repo.merge_trees(&base, ours, &theirs, None)?)
+}
+
+/// `ours` with the files of the patch put back to the versions it was made against.
+fn preimage_tree<'r>(repo: &'r Repository, ours: &Tree, diff: &Diff) -> Result<Tree<'r>> {
+    let mut builder = TreeUpdateBuilder::new();
+    for delta in diff.deltas() {
+        let old = delta.old_file();
+        if delta.status() == Delta::Added {
+            if let Some(path) = delta.new_file().path().filter(|path| ours.get_path(path).is_ok()) {
+                builder.remove(path);
+            }
+            continue;
+        }
+
+        let path = old.path().unwrap_or(Path::new(""));
+        let blob = find_preimage(repo, old.id())
+            .ok_or_else(|| Error::InvalidPatch(format!("the original of {} is not in this repository", path.display())))?;
+        builder.upsert(path, blob, old.mode());
+    }
+    let oid = builder.create_updated(repo, ours)?;
+    Ok(repo.find_tree(oid)?)
+}
+
+/// Find a blob from the possibly abbreviated id on a patch's `index` line.
+fn find_preimage(repo: &Repository, id: Oid) -> Option<Oid> {
+    if id.is_zero() {
+        return None;
+    }
+    let hex = id.to_string();
+    let len = hex.trim_end_matches('0').len().max(7);
+    repo.find_object_by_prefix(&hex[..len], Some(ObjectType::Blob))
+        .ok()
+        .map(|object| object.id())
+}
+
+fn conflicted_paths(index: &Index) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            paths.push(String::from_utf8_lossy(&entry.path).to_string());
+        }
+    }
+    Ok(paths)
+}
+
+/// Copy the merged entries of `files` into the repository's index, with conflicts as stages,
+/// and write them to the working tree with conflict markers.
+fn write_merge_result(repo: &Repository, index: &mut Index, merged: &mut Index, files: &[String]) -> Result<()> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| Error::InvalidState("cannot apply a patch in a bare repository".to_string()))?;
+    // The files are written from the merge result, with markers for conflicts, and the
+    // repository's index is updated on its own below
+    let mut checkout = CheckoutBuilder::new();
+    checkout
+        .force()
+        .allow_conflicts(true)
+        .conflict_style_merge(true)
+        .update_index(false);
+    for path in files {
+        checkout.path(path);
+    }
+    repo.checkout_index(Some(merged), Some(&mut checkout))?;
+
+    let mut conflicts = Vec::new();
+    for conflict in merged.conflicts()? {
+        conflicts.push(conflict?);
+    }
+
+    for path in files {
+        let path = Path::new(path);
+        for stage in 0..=3 {
+            let _ = index.remove(path, stage);
+        }
+        let conflict = conflicts.iter().find(|conflict| {
+            [&conflict.ancestor, &conflict.our, &conflict.their]
+                .iter()
+                .any(|entry| entry.as_ref().is_some_and(|entry| entry.path == path.to_string_lossy().as_bytes()))
+        });
+        match (merged.get_path(path, 0), conflict) {
+            (Some(entry), _) => index.add(&entry)?,
+            // Conflict entries keep their stage in their flags
+            (None, Some(conflict)) => {
+                for entry in [&conflict.ancestor, &conflict.our, &conflict.their].into_iter().flatten() {
+                    index.add(entry)?;
+                }
+            }
+            // Deleted by the patch
+            (None, None) => {
+                let _ = std::fs::remove_file(workdir.join(path));
+            }
+        }
+    }
+    index.write()?;
+    Ok(())
+}
+
+/// One message of an mbox, split into what `git am` needs.
+struct Mail {
+    subject: String,
+    from: Option<String>,
+    date: Option<String>,
+    message: String,
+    patch: String,
+}
+
+impl Mail {
+    fn author(&self) -> Result<Signature<'static>> {
+        let from = self.from.as_deref().ok_or_else(|| Error::InvalidPatch("the patch has no author".to_string()))?;
+        let (name, email) = match from.rsplit_once('<') {
+            Some((name, email)) => (name.trim().trim_matches('"'), email.trim().trim_end_matches('>')),
+            None => (from.trim(), from.trim()),
+        };
+        let time = self
+            .date
+            .as_deref()
+            .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+            .map(|date| Time::new(date.timestamp(), date.offset().local_minus_utc() / 60));
+        Ok(match time {
+            Some(time) => Signature::new(name, email, &time)?,
+            None => Signature::now(name, email)?,
+        })
+    }
+}
+
+/// Split an mbox at its `From ` separator lines. Text without them is a single message.
+fn split_mbox(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let is_separator = |i: usize| {
+        lines[i].starts_with("From ")
+            && lines
+                .get(i + 1)
+                .is_some_and(|next| !next.starts_with(char::is_whitespace) && next.contains(": "))
+    };
+
+    let mut messages = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if is_separator(i) {
+            if current.iter().any(|line| !line.trim().is_empty()) {
+                messages.push(current.join("\n") + "\n");
+            }
+            current.clear();
+            continue;
+        }
+        current.push(line);
+    }
+    if current.iter().any(|line| !line.trim().is_empty()) {
+        messages.push(current.join("\n") + "\n");
+    }
+    messages
+}
+
+fn parse_mail(text: &str) -> Mail {
+    let (head, body) = text.split_once("\n\n").unwrap_or((text, ""));
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in head.lines() {
+        match (line.starts_with(char::is_whitespace), headers.last_mut()) {
+            (true, Some((_, value))) => {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            _ => {
+                if let Some((name, value)) = line.split_once(':') {
+                    headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+                }
+            }
+        }
+    }
+    let header = |name: &str| headers.iter().find(|(key, _)| key == name).map(|(_, value)| decode_header(value));
+
+    // The message ends at the `---` line before the diffstat, or where the diff starts
+    let patch_start = body
+        .match_indices("diff --git ")
+        .map(|(idx, _)| idx)
+        .find(|&idx| idx == 0 || body.as_bytes()[idx - 1] == b'\n')
+        .unwrap_or(body.len());
+    let description = &body[..patch_start];
+    let description = match description.find("\n---\n") {
+        Some(idx) => &description[..idx],
+        None => description.strip_suffix("---\n").unwrap_or(description),
+    };
+
+    let subject = strip_subject_prefix(&header("subject").unwrap_or_default());
+    let description = description.trim();
+    let message = match description.is_empty() {
+        true => format!("{}\n", subject),
+        false => format!("{}\n\n{}\n", subject, description),
+    };
+    Mail {
+        subject,
+        from: header("from"),
+        date: header("date"),
+        message,
+        patch: strip_signature(&body[patch_start..]).to_string(),
+    }
+}
+
+/// Drop `[PATCH 1/3]`-style tags from the start of a subject.
+fn strip_subject_prefix(subject: &str) -> String {
+    let mut subject = subject.trim();
+    while let Some(rest) = subject.strip_prefix('[') {
+        match rest.split_once(']') {
+            Some((_, rest)) => subject = rest.trim_start(),
+            None => break,
+        }
+    }
+    subject.to_string()
+}
+
+/// Remove the `-- ` signature that format-patch puts after the diff.
+fn strip_signature(patch: &str) -> &str {
+    match patch.rfind("\n-- \n").or_else(|| patch.rfind("\n--\n")) {
+        Some(idx) if patch[idx..].lines().count() <= 4 => &patch[..idx + 1],
+        _ => patch,
+    }
+}
+
+/// Decode RFC 2047 quoted-printable words such as `=?UTF-8?q?Ren=C3=A9?=`, which git uses for
+/// names and subjects that are not plain ASCII.
+fn decode_header(value: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("=?") {
+        let word = &rest[start + 2..];
+        let parts: Vec<&str> = word.splitn(3, '?').collect();
+        let end = parts.get(2).and_then(|text| text.find("?="));
+        let (Some(end), true) = (end, parts.len() == 3 && parts[1].eq_ignore_ascii_case("q")) else {
+            break;
+        };
+
+        // Whitespace between two encoded words is not part of the text
+        let before = &rest[..start];
+        if !before.trim().is_empty() || decoded.is_empty() {
+            decoded.push_str(before);
+        }
+        let mut bytes = Vec::new();
+        let text = &parts[2].as_bytes()[..end];
+        let mut i = 0;
+        while i < text.len() {
+            match text[i] {
+                b'_' => bytes.push(b' '),
+                b'=' => {
+                    let hex = text.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+                    match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                        Some(byte) => {
+                            bytes.push(byte);
+                            i += 2;
+                        }
+                        None => bytes.push(b'='),
+                    }
+                }
+                byte => bytes.push(byte),
+            }
+            i += 1;
+        }
+        decoded.push_str(&String::from_utf8_lossy(&bytes));
+        rest = &parts[2][end + 2..];
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Apply a mail's patch on top of `parent` and commit it with the mail's author and message.
+fn apply_mail<'r>(repo: &'r Repository, parent: &Commit<'r>, mail: &Mail, committer: &Signature, three_way: bool) -> Result<Commit<'r>> {
+    let diff = parse_patch(&mail.patch)?;
+    let tree = parent.tree()?;
+    let mut index = match repo.apply_to_tree(&tree, &diff, None) {
+        Ok(index) => index,
+        Err(_) if three_way => merge_patch(repo, &tree, &diff)?,
+        Err(e) => return Err(e.into()),
+    };
+    if index.has_conflicts() {
+        return Err(Error::InvalidPatch(format!("conflicts in {}", conflicted_paths(&index)?.join(", "))));
+    }
+
+    let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+    let author = mail.author()?;
+    let oid = create_commit(repo, &author, committer, &mail.message, &tree, &[parent], None)?;
+    Ok(repo.find_commit(oid)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, commit_index_as, stage_files};
+
+    const ORIGINAL: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\n";
+
+    fn read(repo: &Repository, name: &str) -> String {
+        std::fs::read_to_string(repo.workdir().unwrap().join(name)).unwrap()
+    }
+
+    #[test]
+    fn test_format_patch_apply_and_am() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Committer").unwrap();
+        config.set_str("user.email", "committer@example.com").unwrap();
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+
+        let ada = Signature::new("Ada Lovelace", "ada@example.com", &Time::new(1_700_000_000, 60)).unwrap();
+        let base = commit_file(&repo, "a.txt", ORIGINAL, "base");
+        let changed = ORIGINAL.replace("two", "TWO");
+        stage_files(&repo, &[("a.txt", &changed)]);
+        let first = commit_index_as(&repo, &ada, "Change line two\n\nIt reads better in capitals.\n");
+        stage_files(&repo, &[("b.txt", "new file\n")]);
+        let second = commit_index_as(&repo, &ada, "Add b.txt");
+
+        let out = dir.path().join("out");
+        let options = GitFormatPatchOptions {
+            range: base.to_string(),
+            output_dir: Some(out.to_string_lossy().to_string()),
+            subject_prefix: None,
+        };
+        let series = manager.format_patch(repo_path, options).unwrap();
+        assert_eq!(series.patches.len(), 2);
+        assert_eq!(series.patches[0].file_name, "0001-Change-line-two.patch");
+        assert_eq!(series.patches[1].commit, second.to_string());
+        assert!(series.patches[0].content.contains("Subject: [PATCH 1/2] Change line two"));
+        assert!(out.join("0002-Add-b.txt.patch").exists());
+        assert_eq!(series.mbox.matches(&format!("From {} ", first)).count(), 1);
+
+        // Import the series on a branch that lacks it
+        let branch = repo.branch("import", &repo.find_commit(base).unwrap(), false).unwrap();
+        repo.set_head(branch.get().name().unwrap()).unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force())).unwrap();
+        let options = GitAmOptions {
+            mbox: Some(series.mbox.clone()),
+            ..Default::default()
+        };
+        let patches = manager.am(repo_path, options).unwrap();
+        assert!(patches.iter().all(|patch| patch.status == GitAmPatchStatus::Applied));
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.tree_id(), repo.find_commit(second).unwrap().tree_id());
+        let imported = repo.find_commit(Oid::from_str(patches[0].commit.as_ref().unwrap()).unwrap()).unwrap();
+        assert_eq!(imported.message(), Some("Change line two\n\nIt reads better in capitals.\n"));
+        assert_eq!((imported.author().name(), imported.author().when().seconds()), (Some("Ada Lovelace"), 1_700_000_000));
+        assert_eq!(imported.committer().name(), Some("Committer"));
+        assert_eq!(read(&repo, "b.txt"), "new file\n");
+
+        // A patch applies to a working tree with unrelated changes
+        let main = repo.find_commit(base).unwrap();
+        repo.reset(main.as_object(), git2::ResetType::Hard, None).unwrap();
+        std::fs::write(dir.path().join("a.txt"), ORIGINAL.replace("eight", "EIGHT")).unwrap();
+        let patch = series.patches[0].content.clone();
+        let options = GitApplyPatchOptions {
+            patch: Some(patch.clone()),
+            ..Default::default()
+        };
+        let result = manager.apply_patch(repo_path, options).unwrap();
+        assert_eq!((result.files.as_slice(), result.three_way), (&["a.txt".to_string()][..], false));
+        assert_eq!(read(&repo, "a.txt"), changed.replace("eight", "EIGHT"));
+
+        // When the context has moved on, the three-way fallback merges the change in
+        commit_file(&repo, "a.txt", &ORIGINAL.replace("five", "FIVE"), "five");
+        let direct = GitApplyPatchOptions {
+            patch: Some(patch.clone()),
+            location: Some(GitApplyLocation::Both),
+            ..Default::default()
+        };
+        assert!(manager.apply_patch(repo_path, direct.clone()).is_err());
+        let three_way = GitApplyPatchOptions {
+            three_way: Some(true),
+            ..direct.clone()
+        };
+        let result = manager.apply_patch(repo_path, three_way.clone()).unwrap();
+        assert!(result.three_way && result.conflicts.is_empty());
+        assert_eq!(read(&repo, "a.txt"), changed.replace("five", "FIVE"));
+        assert!(repo.status_file(Path::new("a.txt")).unwrap().contains(Status::INDEX_MODIFIED));
+
+        // A clashing change is left as a conflict
+        repo.checkout_head(Some(CheckoutBuilder::new().force())).unwrap();
+        commit_file(&repo, "a.txt", &ORIGINAL.replace("two", "deux"), "deux");
+        let result = manager.apply_patch(repo_path, three_way).unwrap();
+        assert_eq!(result.conflicts, ["a.txt"]);
+        assert!(read(&repo, "a.txt").contains("<<<<<<<"));
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        assert!(index.has_conflicts());
+
+        // am stops at the first failing patch and reports the rest as pending
+        repo.checkout_head(Some(CheckoutBuilder::new().force())).unwrap();
+        let head = repo.head().unwrap().target();
+        let options = GitAmOptions {
+            mbox: Some(series.mbox.clone()),
+            three_way: Some(true),
+            ..Default::default()
+        };
+        let patches = manager.am(repo_path, options.clone()).unwrap();
+        assert_eq!(patches[0].status, GitAmPatchStatus::Failed);
+        assert!(patches[0].error.as_ref().unwrap().contains("a.txt"));
+        assert_eq!(patches[1].status, GitAmPatchStatus::Pending);
+        assert_eq!(repo.head().unwrap().target(), head);
+
+        let options = GitAmOptions {
+            skip_failed: Some(true),
+            ..options
+        };
+        let patches = manager.am(repo_path, options).unwrap();
+        assert_eq!(patches[1].status, GitAmPatchStatus::Applied);
+        assert_eq!(read(&repo, "b.txt"), "new file\n");
+    }
+
+    #[test]
+    fn test_parse_mail_headers() {
+        let mail = parse_mail(
+            "From: =?UTF-8?q?Ren=C3=A9?= <rene@example.com>\nDate: Tue, 14 Nov 2023 23:13:20 +0100\n\
+             Subject: [PATCH v2 3/7] Fix the\n thing\n\nDetails.\n---\n a.txt | 2 +-\n\ndiff --git a/a.txt b/a.txt\n",
+        );
+        assert_eq!(mail.subject, "Fix the thing");
+        assert_eq!(mail.message, "Fix the thing\n\nDetails.\n");
+        assert!(mail.patch.starts_with("diff --git"));
+        let author = mail.author().unwrap();
+        assert_eq!((author.name(), author.email()), (Some("René"), Some("rene@example.com")));
+        assert_eq!((author.when().seconds(), author.when().offset_minutes()), (1_700_000_000, 60));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    conflicts::collect_conflicts,
+    error::{Error, Result},
+    merge::ensure_clean_state,
+    models::*,
+    repository::GitManager,
+    signing::{create_commit, signing_requested},
+    utils::*,
+};
+use git2::{ErrorCode, Rebase, RebaseOptions, Repository, ResetType};
+
+impl GitManager {
+    /// Replay the commits of `branch` (HEAD by default) that are not in `upstream` on top of
+    /// `onto` (`upstream` by default). Stops with the conflicts when a commit does not apply.
+    pub fn rebase(&self, repo_path: &str, options: GitRebaseOptions) -> Result<GitRebaseResult> {
+        let repo = self.get_repo(repo_path)?;
+        ensure_clean_state(&repo)?;
+
+        let upstream = resolve_annotated_commit(&repo, &options.upstream)?;
+        let onto = match &options.onto {
+            Some(onto) => Some(resolve_annotated_commit(&repo, onto)?),
+            None => None,
+        };
+        let branch = match &options.branch {
+            Some(branch) => Some(resolve_annotated_commit(&repo, branch)?),
+            None => None,
+        };
+
+        let mut rebase = repo.rebase(branch.as_ref(), Some(&upstream), onto.as_ref(), None)?;
+        run_rebase(&repo, &mut rebase)
+    }
+
+    /// Commit the resolved conflicts of the current step and carry on with the rebase.
+    pub fn rebase_continue(&self, repo_path: &str) -> Result<GitRebaseResult> {
+        let repo = self.get_repo(repo_path)?;
+        let mut rebase = open_rebase(&repo)?;
+
+        if rebase.operation_current().is_some() {
+            if repo.index()?.has_conflicts() {
+                return Err(Error::MergeConflict);
+            }
+            commit_step(&repo, &mut rebase)?;
+        }
+
+        run_rebase(&repo, &mut rebase)
+    }
+
+    /// Drop the commit of the current step and carry on with the rest of the rebase.
+    pub fn rebase_skip(&self, repo_path: &str) -> Result<GitRebaseResult> {
+        let repo = self.get_repo(repo_path)?;
+        let mut rebase = open_rebase(&repo)?;
+
+        let head = repo.head()?.peel_to_commit()?;
+        repo.reset(head.as_object(), ResetType::Hard, None)?;
+        run_rebase(&repo, &mut rebase)
+    }
+
+    pub fn rebase_abort(&self, repo_path: &str) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let mut rebase = open_rebase(&repo)?;
+        rebase.abort()?;
+        Ok(())
+    }
+}
+
+fn open_rebase(repo: &Repository) -> Result<Rebase<'_>> {
+    repo.open_rebase(Some(&mut RebaseOptions::new()))
+        .map_err(|_| Error::InvalidState("no rebase in progress".to_string()))
+}
+
+/// Apply the remaining operations of `rebase`, stopping at the first one that conflicts.
+pub(crate) fn run_rebase(repo: &Repository, rebase: &mut Rebase) -> Result<GitRebaseResult> {
+    let total = rebase.len();
+
+    while let Some(step) = rebase.next() {
+        step?;
+        let index = repo.index()?;
+        if index.has_conflicts() {
+            // Leave the rebase in progress so the conflicts can be resolved
+            let current_commit = rebase
+                .operation_current()
+                .and_then(|idx| rebase.nth(idx))
+                .map(|op| op.id().to_string());
+            return Ok(GitRebaseResult {
+                status: GitRebaseStatus::Conflicts,
+                current_commit,
+                applied: rebase.operation_current().unwrap_or(0),
+                total,
+                conflicts: collect_conflicts(repo, &index)?,
+            });
+        }
+        commit_step(repo, rebase)?;
+    }
+
+    rebase.finish(Some(&repo.signature()?))?;
+    Ok(GitRebaseResult {
+        status: GitRebaseStatus::Completed,
+        current_commit: None,
+        applied: total,
+        total,
+        conflicts: Vec::new(),
+    })
+}
+
+fn commit_step(repo: &Repository, rebase: &mut Rebase) -> Result<()> {
+    if signing_requested(&repo.config()?, "commit.gpgsign") {
+        return commit_step_signed(repo, rebase);
+    }
+    match rebase.commit(None, &repo.signature()?, None) {
+        Ok(_) => Ok(()),
+        // The change is already upstream
+        Err(e) if e.code() == ErrorCode::Applied => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// libgit2 cannot sign the commits it rebases, so a step that has to be signed is committed here
+/// the way `Rebase::commit` would: the original author and message on top of the detached HEAD.
+fn commit_step_signed(repo: &Repository, rebase: &mut Rebase) -> Result<()> {
+    let original = match rebase.operation_current().and_then(|idx| rebase.nth(idx)) {
+        Some(operation) => repo.find_commit(operation.id())?,
+        None => return Ok(()),
+    };
+    let mut index = repo.index()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head = repo.head()?.peel_to_commit()?;
+    // The change is already upstream
+    if tree.id() == head.tree_id() {
+        return Ok(());
+    }
+
+    let message = String::from_utf8_lossy(original.message_bytes()).to_string();
+    let oid = create_commit(repo, &original.author(), &repo.signature()?, &message, &tree, &[&head], None)?;
+    repo.set_head_detached(oid)?;
+    Ok(())
+}
@@ -0,0 +1,306 @@
+use crate::{
+    error::{Error, Result},
+    merge::ensure_clean_state,
+    models::*,
+    repository::GitManager,
+    sequencer::ensure_clean_worktree,
+    utils::*,
+};
+use git2::{build::CheckoutBuilder, Oid, Reflog, Repository, Sort};
+
+impl GitManager {
+    /// List the reflog of HEAD or a branch, newest entry first.
+    pub fn reflog(&self, repo_path: &str, options: GitReflogOptions) -> Result<Vec<GitReflogEntry>> {
+        let repo = self.get_repo(repo_path)?;
+        let refname = match options.reference.as_deref() {
+            Some(name) => resolve_refname(&repo, name)?,
+            None => "HEAD".to_string(),
+        };
+        let reflog = repo.reflog(&refname)?;
+
+        let max_count = options.max_count.unwrap_or(100);
+        Ok((0..reflog.len().min(max_count))
+            .filter_map(|index| convert_entry(&repo, &reflog, &refname, index))
+            .collect())
+    }
+
+    /// Describe what undoing a reflog entry would change without touching the repository.
+    pub fn undo_preview(&self, repo_path: &str, options: GitUndoOptions) -> Result<GitUndoPlan> {
+        let repo = self.get_repo(repo_path)?;
+        plan_undo(&repo, &options)
+    }
+
+    /// Move the reference back to where it was before the selected reflog entry, like
+    /// `git reset --hard <branch>@{1}` for the default entry. A checked-out reference also
+    /// updates the working tree, which must be clean.
+    pub fn undo(&self, repo_path: &str, options: GitUndoOptions) -> Result<GitUndoPlan> {
+        let repo = self.get_repo(repo_path)?;
+        let plan = plan_undo(&repo, &options)?;
+        let target = Oid::from_str(&plan.target_hash)?;
+        let message = format!("undo: {}", plan.entry.message);
+
+        if plan.updates_worktree {
+            ensure_clean_state(&repo)?;
+            ensure_clean_worktree(&repo)?;
+            let commit = repo.find_commit(target)?;
+            repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))?;
+        }
+
+        if plan.reference == "HEAD" {
+            repo.set_head_detached(target)?;
+        } else {
+            repo.find_reference(&plan.reference)?.set_target(target, &message)?;
+        }
+        Ok(plan)
+    }
+}
+
+/// Full reference name for `HEAD`, a branch name or any other reference.
+fn resolve_refname(repo: &Repository, name: &str) -> Result<String> {
+    if name == "HEAD" || name.starts_with("refs/") {
+        return Ok(name.to_string());
+    }
+    repo.resolve_reference_from_short_name(name)
+        .ok()
+        .and_then(|reference| reference.name().map(String::from))
+        .ok_or_else(|| Error::BranchNotFound(name.to_string()))
+}
+
+fn convert_entry(repo: &Repository, reflog: &Reflog, refname: &str, index: usize) -> Option<GitReflogEntry> {
+    let entry = reflog.get(index)?;
+    let shorthand = repo
+        .find_reference(refname)
+        .ok()
+        .and_then(|reference| reference.shorthand().map(String::from))
+        .unwrap_or_else(|| refname.to_string());
+    let old_id = entry.id_old();
+    let committer = convert_author(&entry.committer()).ok()?;
+
+    Some(GitReflogEntry {
+        index,
+        selector: format!("{}@{{{}}}", shorthand, index),
+        old_hash: (!old_id.is_zero()).then(|| old_id.to_string()),
+        new_hash: entry.id_new().to_string(),
+        committer,
+        message: entry.message().unwrap_or("").to_string(),
+    })
+}
+
+/// Refuse a HEAD reflog entry that did not move `branch`, such as a checkout or a commit made
+/// before switching to it: undoing it would reset `branch` to another branch's history.
+fn ensure_recorded_on(repo: &Repository, branch: &str, entry: &GitReflogEntry) -> Result<()> {
+    let not_on_branch = || {
+        Error::InvalidState(format!("{} was not recorded on {}; pass its reflog as `reference`", entry.selector, branch))
+    };
+    if entry.message.starts_with("checkout:") {
+        return Err(not_on_branch());
+    }
+    let reflog = repo.reflog(branch)?;
+    let recorded = reflog.iter().any(|branch_entry| {
+        branch_entry.id_new().to_string() == entry.new_hash
+            && Some(branch_entry.id_old().to_string()) == entry.old_hash
+    });
+    if recorded {
+        Ok(())
+    } else {
+        Err(not_on_branch())
+    }
+}
+
+fn plan_undo(repo: &Repository, options: &GitUndoOptions) -> Result<GitUndoPlan> {
+    let head = repo.head().ok();
+    let head_branch = head
+        .as_ref()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.name().map(String::from));
+
+    // The branch's own reflog has one entry per operation, while HEAD also records every step
+    // of a rebase; a HEAD that is on a branch therefore moves that branch. An explicit entry
+    // is an index into HEAD's reflog, the one `reflog` lists by default.
+    let reflog_name = match (options.reference.as_deref(), options.entry) {
+        (Some(name), _) => resolve_refname(repo, name)?,
+        (None, Some(_)) => "HEAD".to_string(),
+        (None, None) => head_branch.clone().unwrap_or_else(|| "HEAD".to_string()),
+    };
+    let reference = match (reflog_name.as_str(), &head_branch) {
+        ("HEAD", Some(branch)) => branch.clone(),
+        _ => reflog_name.clone(),
+    };
+    let current = repo
+        .find_reference(&reference)
+        .ok()
+        .and_then(|reference| reference.target())
+        .ok_or_else(|| Error::BranchNotFound(reference.clone()))?;
+
+    let index = options.entry.unwrap_or(0);
+    let reflog = repo.reflog(&reflog_name)?;
+    let entry = convert_entry(repo, &reflog, &reflog_name, index)
+        .ok_or_else(|| Error::InvalidState(format!("{} has no reflog entry {}", reflog_name, index)))?;
+    let target = match &entry.old_hash {
+        Some(hash) => Oid::from_str(hash)?,
+        None => return Err(Error::InvalidState(format!("{} was created by {}; nothing to undo", reflog_name, entry.selector))),
+    };
+    if reflog_name != reference {
+        ensure_recorded_on(repo, &reference, &entry)?;
+    }
+    let target_commit = repo
+        .find_commit(target)
+        .map_err(|_| Error::CommitNotFound(target.to_string()))?;
+
+    let current_tree = repo.find_commit(current)?.tree()?;
+    let mut diff = repo.diff_tree_to_tree(Some(&current_tree), Some(&target_commit.tree()?), None)?;
+    diff.find_similar(None)?;
+    let files = diff.deltas().map(|delta| create_file_change_from_delta(&delta)).collect();
+
+    Ok(GitUndoPlan {
+        updates_worktree: !repo.is_bare() && head_branch.as_deref().unwrap_or("HEAD") == reference,
+        commits_removed: commits_between(repo, current, target)?,
+        commits_restored: commits_between(repo, target, current)?,
+        current_hash: current.to_string(),
+        target_hash: target.to_string(),
+        reference,
+        entry,
+        files,
+    })
+}
+
+/// Commits reachable from `from` but not from `hidden`, newest first.
+fn commits_between(repo: &Repository, from: Oid, hidden: Oid) -> Result<Vec<GitCommit>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push(from)?;
+    revwalk.hide(hidden)?;
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        commits.push(convert_commit(&repo.find_commit(oid?)?)?);
+    }
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use git2::ResetType;
+
+    #[test]
+    fn test_reflog_and_undo_amend_and_reset() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+
+        let first = commit_file(&repo, "a.txt", "one\n", "first");
+        let second = commit_file(&repo, "b.txt", "two\n", "second");
+        let head = repo.find_commit(second).unwrap();
+        let amended = head.amend(Some("HEAD"), None, None, None, Some("second, reworded"), None).unwrap();
+
+        let entries = manager.reflog(repo_path, GitReflogOptions::default()).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].selector, "HEAD@{0}");
+        assert_eq!(entries[0].new_hash, amended.to_string());
+        assert!(entries[2].old_hash.is_none());
+
+        let plan = manager.undo_preview(repo_path, GitUndoOptions::default()).unwrap();
+        assert!(plan.reference.starts_with("refs/heads/") && plan.updates_worktree);
+        assert_eq!(plan.target_hash, second.to_string());
+        assert_eq!(plan.commits_removed.len(), 1);
+        assert_eq!(plan.commits_restored[0].hash, second.to_string());
+        assert!(plan.files.is_empty());
+        assert_eq!(repo.head().unwrap().target(), Some(amended));
+
+        manager.undo(repo_path, GitUndoOptions::default()).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(second));
+
+        // A hard reset is undone with the file it removed, but only from a clean working tree
+        let target = repo.find_commit(first).unwrap();
+        repo.reset(target.as_object(), ResetType::Hard, None).unwrap();
+        assert!(!dir.path().join("b.txt").exists());
+        let plan = manager.undo_preview(repo_path, GitUndoOptions::default()).unwrap();
+        assert_eq!(plan.files.len(), 1);
+        assert_eq!(plan.files[0].path, "b.txt");
+
+        std::fs::write(dir.path().join("a.txt"), "dirty\n").unwrap();
+        assert!(matches!(manager.undo(repo_path, GitUndoOptions::default()), Err(Error::UncommittedChanges)));
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        manager.undo(repo_path, GitUndoOptions::default()).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(second));
+        assert!(dir.path().join("b.txt").exists());
+
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let options = GitReflogOptions {
+            reference: Some(branch.clone()),
+            max_count: Some(2),
+        };
+        let entries = manager.reflog(repo_path, options).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].selector, format!("{}@{{0}}", branch));
+        assert!(entries[0].message.starts_with("undo: "));
+
+        // An entry picked from the default listing refers to HEAD's reflog
+        let entries = manager.reflog(repo_path, GitReflogOptions::default()).unwrap();
+        let options = GitUndoOptions {
+            entry: Some(1),
+            ..Default::default()
+        };
+        let plan = manager.undo_preview(repo_path, options).unwrap();
+        assert_eq!(plan.entry.selector, entries[1].selector);
+        assert_eq!(plan.reference, format!("refs/heads/{}", branch));
+
+        // Undoing the branch creation is refused
+        let options = GitUndoOptions {
+            reference: Some(branch.clone()),
+            entry: Some(repo.reflog(&format!("refs/heads/{}", branch)).unwrap().len() - 1),
+        };
+        assert!(matches!(manager.undo_preview(repo_path, options), Err(Error::InvalidState(_))));
+    }
+
+    #[test]
+    fn test_undo_refuses_head_entries_from_other_branches() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(dir.path());
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+
+        commit_file(&repo, "a.txt", "one\n", "main one");
+        let main_tip = commit_file(&repo, "a.txt", "two\n", "main two");
+        let main_branch = repo.head().unwrap().name().unwrap().to_string();
+        repo.branch("feature", &repo.find_commit(main_tip).unwrap(), false).unwrap();
+        let checkout = GitCheckoutOptions {
+            branch: Some("feature".to_string()),
+            ..Default::default()
+        };
+        manager.checkout(repo_path, checkout).unwrap();
+        let feature_tip = commit_file(&repo, "b.txt", "b\n", "feature one");
+
+        let entries = manager.reflog(repo_path, GitReflogOptions::default()).unwrap();
+        assert!(entries[1].message.starts_with("checkout:"));
+        assert_eq!(entries[2].new_hash, main_tip.to_string());
+        for entry in [1, 2] {
+            let options = GitUndoOptions {
+                entry: Some(entry),
+                ..Default::default()
+            };
+            assert!(matches!(manager.undo(repo_path, options), Err(Error::InvalidState(_))));
+        }
+        assert_eq!(repo.head().unwrap().target(), Some(feature_tip));
+
+        // The entry still works through the reflog of the branch it was recorded on
+        let options = GitUndoOptions {
+            reference: Some(main_branch.clone()),
+            entry: Some(0),
+        };
+        let plan = manager.undo_preview(repo_path, options).unwrap();
+        assert_eq!(plan.reference, main_branch);
+        assert!(!plan.updates_worktree);
+
+        // HEAD entries made on the current branch can still be undone
+        let options = GitUndoOptions {
+            entry: Some(0),
+            ..Default::default()
+        };
+        manager.undo(repo_path, options).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(main_tip));
+    }
+}
@@ -0,0 +1,775 @@
+use crate::{
+    error::{Error, Result},
+    merge::merge_annotated,
+    models::*,
+    rebase::run_rebase,
+    repository::{GitManager, Operation},
+    utils::*,
+};
+use chrono::{DateTime, Utc};
+use git2::{
+    build::CheckoutBuilder, AutotagOption, BranchType, Config, Cred, CredentialType, Direction,
+    FetchOptions, FetchPrune, Oid, PushOptions, Remote, RemoteCallbacks, Repository,
+};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// libgit2 keeps asking for credentials after a rejected attempt; give up after this many.
+const MAX_AUTH_ATTEMPTS: usize = 5;
+const DEFAULT_SSH_KEYS: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+impl GitManager {
+    pub fn fetch<F: Fn(GitTransferProgress)>(&self, repo_path: &str, options: GitFetchOptions, on_progress: F) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let operation = self.begin_operation(options.operation_id.as_deref());
+        let remote_name = resolve_remote_name(&repo, options.remote.as_deref())?;
+
+        fetch_remote(&repo, &remote_name, &options, &operation, GitRemoteOperation::Fetch, &on_progress)
+    }
+
+    /// Fetch the upstream of the current branch and integrate it by fast-forward, merge or rebase.
+    /// `pull.rebase` and `pull.ff` from git config apply when the options leave them unset.
+    pub fn pull<F: Fn(GitTransferProgress)>(&self, repo_path: &str, options: GitPullOptions, on_progress: F) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let operation = self.begin_operation(options.operation_id.as_deref());
+        let remote_name = resolve_remote_name(&repo, options.remote.as_deref())?;
+
+        let fetch_options = GitFetchOptions {
+            remote: Some(remote_name.clone()),
+            credentials: options.credentials.clone(),
+            ..Default::default()
+        };
+        fetch_remote(&repo, &remote_name, &fetch_options, &operation, GitRemoteOperation::Pull, &on_progress)?;
+
+        let branch_name = get_head_branch(&repo)?;
+        let upstream_ref = match &options.branch {
+            Some(branch) => format!("refs/remotes/{}/{}", remote_name, branch),
+            None => repo
+                .branch_upstream_name(&format!("refs/heads/{}", branch_name))
+                .ok()
+                .and_then(|buf| buf.as_str().map(String::from))
+                .unwrap_or_else(|| format!("refs/remotes/{}/{}", remote_name, branch_name)),
+        };
+        let reference = repo
+            .find_reference(&upstream_ref)
+            .map_err(|_| Error::BranchNotFound(upstream_ref.clone()))?;
+        let incoming = repo.reference_to_annotated_commit(&reference)?;
+
+        let config = repo.config()?;
+        // `merges` and `interactive` rebase as well
+        let rebase = options
+            .rebase
+            .or_else(|| config.get_string("pull.rebase").ok().map(|value| Config::parse_bool(value).unwrap_or(true)))
+            .unwrap_or(false);
+        let pull_ff = config.get_string("pull.ff").ok();
+        let fast_forward_only = options
+            .fast_forward_only
+            .unwrap_or_else(|| pull_ff.as_deref() == Some("only"));
+        // `pull.ff=false` always records a merge commit, but only when merging
+        let no_fast_forward = !rebase
+            && !fast_forward_only
+            && pull_ff.is_some_and(|ff| Config::parse_bool(ff).ok() == Some(false));
+
+        let (analysis, _) = repo.merge_analysis(&[&incoming])?;
+        if analysis.is_up_to_date() {
+            return Ok(());
+        }
+        if analysis.is_unborn() || (analysis.is_fast_forward() && !no_fast_forward) {
+            return fast_forward(&repo, &format!("refs/heads/{}", branch_name), incoming.id(), "pull: Fast-forward");
+        }
+        if fast_forward_only {
+            return Err(Error::NotFastForward(format!("{} has diverged from {}", branch_name, upstream_ref)));
+        }
+
+        let upstream_name = reference.shorthand().unwrap_or(&upstream_ref).to_string();
+        let conflicted = if rebase {
+            let mut rebase = repo.rebase(None, Some(&incoming), None, None)?;
+            run_rebase(&repo, &mut rebase)?.status == GitRebaseStatus::Conflicts
+        } else {
+            let message = format!("Merge remote-tracking branch '{}'", upstream_name);
+            merge_annotated(&repo, &incoming, false, false, Some(&message))?.status == GitMergeStatus::Conflicts
+        };
+
+        // The merge or rebase is left in progress so the conflicts can be resolved
+        if conflicted {
+            return Err(Error::MergeConflict);
+        }
+        Ok(())
+    }
+
+    pub fn push<F: Fn(GitTransferProgress)>(&self, repo_path: &str, options: GitPushOptions, on_progress: F) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let operation = self.begin_operation(options.operation_id.as_deref());
+        let remote_name = resolve_remote_name(&repo, options.remote.as_deref())?;
+        let branch_name = match options.branch {
+            Some(branch) => branch,
+            None => get_head_branch(&repo)?,
+        };
+
+        let force = if options.force.unwrap_or(false) { "+" } else { "" };
+        let mut refspecs = vec![format!("{}refs/heads/{}:refs/heads/{}", force, branch_name, branch_name)];
+        if options.tags.unwrap_or(false) {
+            for tag in repo.tag_names(None)?.iter().flatten() {
+                refspecs.push(format!("refs/tags/{}:refs/tags/{}", tag, tag));
+            }
+        }
+
+        push_refspecs(&repo, &remote_name, &refspecs, options.credentials.as_ref(), &operation, &on_progress)?;
+
+        if options.set_upstream.unwrap_or(false) {
+            let mut branch = repo.find_branch(&branch_name, BranchType::Local)?;
+            branch.set_upstream(Some(&format!("{}/{}", remote_name, branch_name)))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn add_remote(&self, repo_path: &str, name: &str, url: &str) -> Result<GitRemote> {
+        let repo = self.get_repo(repo_path)?;
+        let remote = repo.remote(name, url)?;
+        convert_remote(&repo, &remote)
+    }
+
+    /// Remove a remote along with its remote-tracking branches and branch upstream settings.
+    pub fn remove_remote(&self, repo_path: &str, name: &str) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        find_remote(&repo, name)?;
+        repo.remote_delete(name)?;
+        Ok(())
+    }
+
+    /// Rename a remote and move its remote-tracking branches. Returns the non-default fetch
+    /// refspecs that could not be rewritten and need updating by hand.
+    pub fn rename_remote(&self, repo_path: &str, name: &str, new_name: &str) -> Result<Vec<String>> {
+        let repo = self.get_repo(repo_path)?;
+        find_remote(&repo, name)?;
+        let problems = repo.remote_rename(name, new_name)?;
+        Ok(problems.iter().flatten().map(String::from).collect())
+    }
+
+    /// Change the fetch URL and/or the separate push URL of a remote.
+    pub fn set_remote_url(&self, repo_path: &str, name: &str, options: GitRemoteUrlOptions) -> Result<GitRemote> {
+        let repo = self.get_repo(repo_path)?;
+        find_remote(&repo, name)?;
+        if let Some(url) = &options.url {
+            repo.remote_set_url(name, url)?;
+        }
+        if options.clear_push_url.unwrap_or(false) {
+            repo.remote_set_pushurl(name, None)?;
+        } else if let Some(push_url) = &options.push_url {
+            repo.remote_set_pushurl(name, Some(push_url))?;
+        }
+        let remote = find_remote(&repo, name)?;
+        convert_remote(&repo, &remote)
+    }
+
+    /// Replace the fetch and/or push refspecs of a remote. The previous refspecs are restored
+    /// if any of the new ones is invalid.
+    pub fn set_remote_refspecs(&self, repo_path: &str, name: &str, refspecs: GitRemoteRefspecs) -> Result<GitRemote> {
+        let repo = self.get_repo(repo_path)?;
+        let remote = find_remote(&repo, name)?;
+        let current = convert_remote(&repo, &remote)?;
+
+        if let Some(fetch) = &refspecs.fetch {
+            replace_refspecs(&repo, name, Direction::Fetch, fetch, &current.fetch_refspecs)?;
+        }
+        if let Some(push) = &refspecs.push {
+            replace_refspecs(&repo, name, Direction::Push, push, &current.push_refspecs)?;
+        }
+        let remote = find_remote(&repo, name)?;
+        convert_remote(&repo, &remote)
+    }
+
+    /// Delete remote-tracking branches whose branch no longer exists on the remote, like
+    /// `git remote prune`. Returns the pruned (or, with `dry_run`, prunable) branch names.
+    pub fn prune_remote<F: Fn(GitTransferProgress)>(
+        &self,
+        repo_path: &str,
+        name: &str,
+        options: GitRemotePruneOptions,
+        on_progress: F,
+    ) -> Result<Vec<String>> {
+        let repo = self.get_repo(repo_path)?;
+        let operation = self.begin_operation(options.operation_id.as_deref());
+        let mut remote = find_remote(&repo, name)?;
+        let config = repo.config()?;
+
+        let callbacks = remote_callbacks(&config, options.credentials.as_ref(), &operation, GitRemoteOperation::Fetch, &on_progress);
+        let connection = remote
+            .connect_auth(Direction::Fetch, Some(callbacks), None)
+            .map_err(|e| operation.map_err(e))?;
+        let heads: Vec<String> = connection.list()?.iter().map(|head| head.name().to_string()).collect();
+        drop(connection);
+
+        let refspecs: Vec<_> = remote
+            .refspecs()
+            .filter(|spec| spec.direction() == Direction::Fetch)
+            .collect();
+        let mut expected = HashSet::new();
+        for spec in &refspecs {
+            for head in heads.iter().filter(|head| spec.src_matches(head)) {
+                if let Some(local) = spec.transform(head)?.as_str() {
+                    expected.insert(local.to_string());
+                }
+            }
+        }
+
+        let mut stale = Vec::new();
+        for reference in repo.references()? {
+            let reference = reference?;
+            let Some(refname) = reference.name() else {
+                continue;
+            };
+            // Symbolic refs such as refs/remotes/origin/HEAD follow their target
+            if reference.kind() != Some(git2::ReferenceType::Direct) {
+                continue;
+            }
+            if refspecs.iter().any(|spec| spec.dst_matches(refname)) && !expected.contains(refname) {
+                stale.push((refname.to_string(), reference.shorthand().unwrap_or(refname).to_string()));
+            }
+        }
+
+        if !options.dry_run.unwrap_or(false) {
+            for (refname, _) in &stale {
+                repo.find_reference(refname)?.delete()?;
+            }
+        }
+        Ok(stale.into_iter().map(|(_, shorthand)| shorthand).collect())
+    }
+}
+
+/// Use the explicit remote, else the current branch's upstream remote, else `origin`.
+pub(crate) fn resolve_remote_name(repo: &Repository, remote: Option<&str>) -> Result<String> {
+    let name = match remote {
+        Some(name) => name.to_string(),
+        None => get_head_branch(repo)
+            .ok()
+            .and_then(|branch| repo.branch_upstream_remote(&format!("refs/heads/{}", branch)).ok())
+            .and_then(|buf| buf.as_str().map(String::from))
+            .unwrap_or_else(|| "origin".to_string()),
+    };
+
+    repo.find_remote(&name)
+        .map_err(|_| Error::RemoteNotFound(name.clone()))?;
+    Ok(name)
+}
+
+pub(crate) fn fetch_remote(
+    repo: &Repository,
+    remote_name: &str,
+    options: &GitFetchOptions,
+    operation: &Operation,
+    kind: GitRemoteOperation,
+    on_progress: &dyn Fn(GitTransferProgress),
+) -> Result<()> {
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|_| Error::RemoteNotFound(remote_name.to_string()))?;
+    let config = repo.config()?;
+
+    let mut fetch_opts = FetchOptions::new();
+    fetch_opts.remote_callbacks(remote_callbacks(&config, options.credentials.as_ref(), operation, kind, on_progress));
+    if options.prune.unwrap_or(false) {
+        fetch_opts.prune(FetchPrune::On);
+    }
+    if let Some(tags) = options.tags {
+        fetch_opts.download_tags(if tags { AutotagOption::All } else { AutotagOption::None });
+    }
+    if let Some(depth) = options.depth {
+        fetch_opts.depth(depth as i32);
+    }
+
+    remote
+        .fetch::<&str>(&[], Some(&mut fetch_opts), None)
+        .map_err(|e| operation.map_err(e))
+}
+
+pub(crate) fn push_refspecs(
+    repo: &Repository,
+    remote_name: &str,
+    refspecs: &[String],
+    credentials: Option<&GitCredentials>,
+    operation: &Operation,
+    on_progress: &dyn Fn(GitTransferProgress),
+) -> Result<()> {
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|_| Error::RemoteNotFound(remote_name.to_string()))?;
+    let config = repo.config()?;
+    let rejected = RefCell::new(Vec::new());
+
+    let mut callbacks = remote_callbacks(&config, credentials, operation, GitRemoteOperation::Push, on_progress);
+    callbacks.push_update_reference(|refname, status| {
+        if let Some(message) = status {
+            rejected.borrow_mut().push(format!("{} ({})", refname, message));
+        }
+        Ok(())
+    });
+
+    let mut push_opts = PushOptions::new();
+    push_opts.remote_callbacks(callbacks);
+    remote
+        .push(refspecs, Some(&mut push_opts))
+        .map_err(|e| operation.map_err(e))?;
+    drop(push_opts);
+
+    let rejected = rejected.into_inner();
+    if rejected.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::PushRejected(rejected.join(", ")))
+    }
+}
+
+pub(crate) fn convert_remote(repo: &Repository, remote: &Remote) -> Result<GitRemote> {
+    let name = remote.name().unwrap_or("").to_string();
+    let refspecs = |direction: Direction| -> Vec<String> {
+        remote
+            .refspecs()
+            .filter(|spec| spec.direction() == direction)
+            .filter_map(|spec| spec.str().map(String::from))
+            .collect()
+    };
+
+    Ok(GitRemote {
+        url: remote.url().unwrap_or("").to_string(),
+        fetch_url: remote.url().map(String::from),
+        push_url: remote.pushurl().map(String::from),
+        fetch_refspecs: refspecs(Direction::Fetch),
+        push_refspecs: refspecs(Direction::Push),
+        last_fetched: last_fetched(repo, &name, remote.url())?,
+        name,
+    })
+}
+
+fn find_remote<'r>(repo: &'r Repository, name: &str) -> Result<Remote<'r>> {
+    repo.find_remote(name)
+        .map_err(|_| Error::RemoteNotFound(name.to_string()))
+}
+
+/// The newest reflog entry among the remote's tracking branches, or FETCH_HEAD's modification
+/// time when it was last written by a fetch from this remote.
+fn last_fetched(repo: &Repository, name: &str, url: Option<&str>) -> Result<Option<DateTime<Utc>>> {
+    let mut latest: Option<DateTime<Utc>> = None;
+    for reference in repo.references_glob(&format!("refs/remotes/{}/*", name))? {
+        let reference = reference?;
+        let Some(refname) = reference.name() else {
+            continue;
+        };
+        let Ok(reflog) = repo.reflog(refname) else {
+            continue;
+        };
+        if let Some(entry) = reflog.get(0) {
+            let time = convert_time_to_datetime(&entry.committer().when());
+            latest = latest.max(Some(time));
+        }
+    }
+
+    let fetch_head = repo.path().join("FETCH_HEAD");
+    if let (Some(url), Ok(contents)) = (url, std::fs::read_to_string(&fetch_head)) {
+        if contents.lines().any(|line| line.ends_with(url)) {
+            let modified = std::fs::metadata(&fetch_head)?.modified()?;
+            latest = latest.max(Some(DateTime::<Utc>::from(modified)));
+        }
+    }
+    Ok(latest)
+}
+
+fn replace_refspecs(repo: &Repository, name: &str, direction: Direction, refspecs: &[String], previous: &[String]) -> Result<()> {
+    let key = match direction {
+        Direction::Fetch => format!("remote.{}.fetch", name),
+        Direction::Push => format!("remote.{}.push", name),
+    };
+    let write = |refspecs: &[String]| -> std::result::Result<(), git2::Error> {
+        match repo.config()?.remove_multivar(&key, ".*") {
+            Err(e) if e.code() != git2::ErrorCode::NotFound => return Err(e),
+            _ => {}
+        }
+        for refspec in refspecs {
+            match direction {
+                Direction::Fetch => repo.remote_add_fetch(name, refspec)?,
+                Direction::Push => repo.remote_add_push(name, refspec)?,
+            }
+        }
+        Ok(())
+    };
+
+    if let Err(e) = write(refspecs) {
+        write(previous)?;
+        return Err(Error::InvalidConfig(e.message().to_string()));
+    }
+    Ok(())
+}
+
+/// Callbacks shared by every network operation: authentication, progress reporting and cancellation.
+pub(crate) fn remote_callbacks<'a>(
+    config: &'a Config,
+    credentials: Option<&'a GitCredentials>,
+    operation: &'a Operation,
+    kind: GitRemoteOperation,
+    on_progress: &'a dyn Fn(GitTransferProgress),
+) -> RemoteCallbacks<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut attempts = 0;
+
+    callbacks.credentials(move |url, username_from_url, allowed| {
+        attempts += 1;
+        if operation.is_cancelled() {
+            return Err(git2::Error::from_str("operation cancelled"));
+        }
+        if attempts > MAX_AUTH_ATTEMPTS {
+            return Err(git2::Error::from_str("authentication failed"));
+        }
+        resolve_credentials(config, credentials, url, username_from_url, allowed, attempts)
+    });
+
+    callbacks.transfer_progress(move |progress| {
+        on_progress(GitTransferProgress {
+            total_objects: progress.total_objects(),
+            received_objects: progress.received_objects(),
+            indexed_objects: progress.indexed_objects(),
+            local_objects: progress.local_objects(),
+            total_deltas: progress.total_deltas(),
+            indexed_deltas: progress.indexed_deltas(),
+            received_bytes: progress.received_bytes(),
+            ..empty_progress(operation, kind)
+        });
+        !operation.is_cancelled()
+    });
+
+    callbacks.sideband_progress(move |data| {
+        let message = String::from_utf8_lossy(data).trim().to_string();
+        if !message.is_empty() {
+            on_progress(GitTransferProgress {
+                message: Some(message),
+                ..empty_progress(operation, kind)
+            });
+        }
+        !operation.is_cancelled()
+    });
+
+    callbacks.push_transfer_progress(move |current, total, bytes| {
+        on_progress(GitTransferProgress {
+            total_objects: total,
+            received_objects: current,
+            received_bytes: bytes,
+            ..empty_progress(operation, kind)
+        });
+    });
+
+    callbacks.push_negotiation(move |_| {
+        if operation.is_cancelled() {
+            Err(git2::Error::from_str("operation cancelled"))
+        } else {
+            Ok(())
+        }
+    });
+
+    callbacks
+}
+
+fn empty_progress(operation: &Operation, kind: GitRemoteOperation) -> GitTransferProgress {
+    GitTransferProgress {
+        operation_id: operation.id.clone(),
+        operation: kind,
+        total_objects: 0,
+        received_objects: 0,
+        indexed_objects: 0,
+        local_objects: 0,
+        total_deltas: 0,
+        indexed_deltas: 0,
+        received_bytes: 0,
+        message: None,
+    }
+}
+
+fn resolve_credentials(
+    config: &Config,
+    credentials: Option<&GitCredentials>,
+    url: &str,
+    username_from_url: Option<&str>,
+    allowed: CredentialType,
+    attempt: usize,
+) -> std::result::Result<Cred, git2::Error> {
+    let configured_username = match credentials {
+        Some(GitCredentials::SshAgent { username })
+        | Some(GitCredentials::SshKey { username, .. })
+        | Some(GitCredentials::Token { username, .. }) => username.as_deref(),
+        _ => None,
+    };
+    let default_username = configured_username.or(username_from_url).unwrap_or("git");
+    if allowed.contains(CredentialType::USERNAME) {
+        return Cred::username(default_username);
+    }
+
+    match credentials {
+        Some(GitCredentials::SshAgent { .. }) => Cred::ssh_key_from_agent(default_username),
+        Some(GitCredentials::SshKey { private_key_path, public_key_path, passphrase, .. }) => Cred::ssh_key(
+            default_username,
+            public_key_path.as_deref().map(Path::new),
+            Path::new(private_key_path),
+            passphrase.as_deref(),
+        ),
+        Some(GitCredentials::CredentialHelper) => Cred::credential_helper(config, url, username_from_url),
+        Some(GitCredentials::Token { username, token }) => Cred::userpass_plaintext(
+            username.as_deref().or(username_from_url).unwrap_or("x-access-token"),
+            token,
+        ),
+        None => default_credentials(config, url, username_from_url, allowed, attempt),
+    }
+}
+
+fn default_credentials(
+    config: &Config,
+    url: &str,
+    username_from_url: Option<&str>,
+    allowed: CredentialType,
+    attempt: usize,
+) -> std::result::Result<Cred, git2::Error> {
+    if allowed.contains(CredentialType::SSH_KEY) {
+        let username = username_from_url.unwrap_or("git");
+        // The agent gets the first try, then each of the usual key files in turn
+        if attempt == 1 {
+            return Cred::ssh_key_from_agent(username);
+        }
+        if let Some(key) = default_ssh_keys().get(attempt - 2) {
+            return Cred::ssh_key(username, None, key, None);
+        }
+    }
+
+    if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+        return Cred::credential_helper(config, url, username_from_url);
+    }
+
+    if allowed.contains(CredentialType::DEFAULT) {
+        return Cred::default();
+    }
+
+    Err(git2::Error::from_str("no usable credentials"))
+}
+
+fn default_ssh_keys() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+    let Some(home) = home else {
+        return Vec::new();
+    };
+
+    DEFAULT_SSH_KEYS
+        .iter()
+        .map(|name| Path::new(&home).join(".ssh").join(name))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Move `refname` to `target`, updating the working tree. Creates the ref for an unborn branch.
+pub(crate) fn fast_forward(repo: &Repository, refname: &str, target: Oid, reflog_msg: &str) -> Result<()> {
+    let target_commit = repo.find_commit(target)?;
+    repo.checkout_tree(target_commit.as_object(), Some(CheckoutBuilder::new().safe()))?;
+
+    match repo.find_reference(refname) {
+        Ok(mut reference) => {
+            reference.set_target(target, reflog_msg)?;
+        }
+        Err(_) => {
+            repo.reference(refname, target, true, reflog_msg)?;
+        }
+    }
+    repo.set_head(refname)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo, signature};
+    use std::sync::Mutex;
+
+    fn file_url(path: &Path) -> String {
+        format!("file://{}", path.display())
+    }
+
+    #[test]
+    fn test_push_fetch_and_pull_through_file_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let origin = dir.path().join("origin.git");
+        Repository::init_bare(&origin).unwrap();
+
+        let alice = init_repo(&dir.path().join("alice"));
+        alice.remote("origin", &file_url(&origin)).unwrap();
+        commit_file(&alice, "a.txt", "one\n", "a.txt");
+        let branch = get_head_branch(&alice).unwrap();
+
+        let manager = GitManager::new();
+        let alice_path = alice.workdir().unwrap().to_str().unwrap().to_string();
+        let push_options = GitPushOptions {
+            set_upstream: Some(true),
+            ..Default::default()
+        };
+        manager.push(&alice_path, push_options, |_| {}).unwrap();
+        assert!(alice.find_branch(&branch, BranchType::Local).unwrap().upstream().is_ok());
+
+        let bob = init_repo(&dir.path().join("bob"));
+        bob.remote("origin", &file_url(&origin)).unwrap();
+        bob.set_head(&format!("refs/heads/{}", branch)).unwrap();
+        let bob_path = bob.workdir().unwrap().to_str().unwrap().to_string();
+
+        let events = Mutex::new(Vec::new());
+        let fetch_options = GitFetchOptions {
+            operation_id: Some("fetch-1".to_string()),
+            ..Default::default()
+        };
+        manager
+            .fetch(&bob_path, fetch_options, |progress| events.lock().unwrap().push(progress))
+            .unwrap();
+        let events = events.into_inner().unwrap();
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|p| p.operation_id == "fetch-1"));
+        assert!(bob.find_reference(&format!("refs/remotes/origin/{}", branch)).is_ok());
+
+        // Pull into bob's unborn branch, then again after alice pushes a second commit
+        let pull_options = GitPullOptions {
+            branch: Some(branch.clone()),
+            ..Default::default()
+        };
+        manager.pull(&bob_path, pull_options.clone(), |_| {}).unwrap();
+        let second = commit_file(&alice, "b.txt", "two\n", "b.txt");
+        manager.push(&alice_path, GitPushOptions::default(), |_| {}).unwrap();
+        manager.pull(&bob_path, pull_options.clone(), |_| {}).unwrap();
+
+        assert_eq!(bob.head().unwrap().target().unwrap(), second);
+        assert!(bob.workdir().unwrap().join("b.txt").exists());
+
+        // pull.ff=false records a merge even when a fast-forward is possible
+        bob.config().unwrap().set_str("pull.ff", "false").unwrap();
+        let third = commit_file(&alice, "c.txt", "three\n", "c.txt");
+        manager.push(&alice_path, GitPushOptions::default(), |_| {}).unwrap();
+        manager.pull(&bob_path, pull_options.clone(), |_| {}).unwrap();
+        let merge = bob.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(merge.parent_ids().collect::<Vec<_>>(), [second, third]);
+
+        // pull.rebase=merges rebases rather than merging
+        bob.config().unwrap().set_str("pull.rebase", "merges").unwrap();
+        commit_file(&bob, "d.txt", "four\n", "d.txt");
+        let fifth = commit_file(&alice, "e.txt", "five\n", "e.txt");
+        manager.push(&alice_path, GitPushOptions::default(), |_| {}).unwrap();
+        manager.pull(&bob_path, pull_options, |_| {}).unwrap();
+        let rebased = bob.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(rebased.parent_ids().collect::<Vec<_>>(), [fifth]);
+        assert!(bob.workdir().unwrap().join("d.txt").exists());
+    }
+
+    #[test]
+    fn test_manage_and_prune_remotes() {
+        let dir = tempfile::tempdir().unwrap();
+        let origin = init_repo(&dir.path().join("origin"));
+        commit_file(&origin, "a.txt", "one\n", "a.txt");
+        let head = origin.head().unwrap().peel_to_commit().unwrap();
+        origin.branch("gone", &head, false).unwrap();
+
+        let work = init_repo(&dir.path().join("work"));
+        let work_path = work.workdir().unwrap().to_str().unwrap().to_string();
+        let manager = GitManager::new();
+        let url = file_url(origin.workdir().unwrap());
+
+        let added = manager.add_remote(&work_path, "upstream", &url).unwrap();
+        assert_eq!(added.fetch_refspecs, vec!["+refs/heads/*:refs/remotes/upstream/*".to_string()]);
+        assert!(added.last_fetched.is_none());
+
+        let options = GitFetchOptions {
+            remote: Some("upstream".to_string()),
+            ..Default::default()
+        };
+        manager.fetch(&work_path, options, |_| {}).unwrap();
+        let problems = manager.rename_remote(&work_path, "upstream", "origin").unwrap();
+        assert!(problems.is_empty());
+        assert!(work.find_reference("refs/remotes/origin/gone").is_ok());
+
+        let remotes = manager.get_remotes(&work_path).unwrap();
+        assert_eq!(remotes.len(), 1);
+        assert_eq!(remotes[0].name, "origin");
+        assert!(remotes[0].last_fetched.is_some());
+
+        let options = GitRemoteUrlOptions {
+            push_url: Some("https://example.com/push.git".to_string()),
+            ..Default::default()
+        };
+        let remote = manager.set_remote_url(&work_path, "origin", options).unwrap();
+        assert_eq!(remote.fetch_url.as_deref(), Some(url.as_str()));
+        assert_eq!(remote.push_url.as_deref(), Some("https://example.com/push.git"));
+        let options = GitRemoteUrlOptions {
+            clear_push_url: Some(true),
+            ..Default::default()
+        };
+        assert!(manager.set_remote_url(&work_path, "origin", options).unwrap().push_url.is_none());
+
+        // An invalid refspec leaves the existing ones in place
+        let refspecs = GitRemoteRefspecs {
+            fetch: Some(vec!["refs/heads/*:refs/remotes/origin/*".to_string(), "+refs/tags/*:refs/tags/*".to_string()]),
+            push: Some(vec!["refs/heads/main:refs/heads/main".to_string()]),
+        };
+        let remote = manager.set_remote_refspecs(&work_path, "origin", refspecs).unwrap();
+        assert_eq!(remote.fetch_refspecs.len(), 2);
+        assert_eq!(remote.push_refspecs, vec!["refs/heads/main:refs/heads/main".to_string()]);
+        let refspecs = GitRemoteRefspecs {
+            fetch: Some(vec!["not a refspec".to_string()]),
+            push: None,
+        };
+        assert!(matches!(manager.set_remote_refspecs(&work_path, "origin", refspecs), Err(Error::InvalidConfig(_))));
+        assert_eq!(manager.get_remotes(&work_path).unwrap()[0].fetch_refspecs.len(), 2);
+
+        origin.find_branch("gone", BranchType::Local).unwrap().delete().unwrap();
+        let options = GitRemotePruneOptions {
+            dry_run: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(manager.prune_remote(&work_path, "origin", options, |_| {}).unwrap(), vec!["origin/gone".to_string()]);
+        assert!(work.find_reference("refs/remotes/origin/gone").is_ok());
+        let pruned = manager.prune_remote(&work_path, "origin", GitRemotePruneOptions::default(), |_| {}).unwrap();
+        assert_eq!(pruned, vec!["origin/gone".to_string()]);
+        assert!(work.find_reference("refs/remotes/origin/gone").is_err());
+
+        manager.remove_remote(&work_path, "origin").unwrap();
+        assert!(manager.get_remotes(&work_path).unwrap().is_empty());
+        assert!(matches!(manager.remove_remote(&work_path, "origin"), Err(Error::RemoteNotFound(_))));
+    }
+
+    #[test]
+    fn test_non_fast_forward_push_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let origin = dir.path().join("origin.git");
+        Repository::init_bare(&origin).unwrap();
+
+        let alice = init_repo(&dir.path().join("alice"));
+        alice.remote("origin", &file_url(&origin)).unwrap();
+        commit_file(&alice, "a.txt", "one\n", "a.txt");
+        let alice_path = alice.workdir().unwrap().to_str().unwrap().to_string();
+
+        let manager = GitManager::new();
+        manager.push(&alice_path, GitPushOptions::default(), |_| {}).unwrap();
+
+        // Rewrite history locally so the remote branch is no longer an ancestor
+        let sig = signature();
+        let tree = alice.head().unwrap().peel_to_tree().unwrap();
+        let orphan = alice.commit(None, &sig, &sig, "rewritten", &tree, &[]).unwrap();
+        alice.head().unwrap().set_target(orphan, "rewrite").unwrap();
+
+        assert!(manager.push(&alice_path, GitPushOptions::default(), |_| {}).is_err());
+
+        let force = GitPushOptions {
+            force: Some(true),
+            ..Default::default()
+        };
+        manager.push(&alice_path, force, |_| {}).unwrap();
+    }
+
+    #[test]
+    fn test_cancel_operation() {
+        let manager = GitManager::new();
+        let operation = manager.begin_operation(Some("op"));
+        assert!(manager.cancel_operation("op"));
+        assert!(operation.is_cancelled());
+        drop(operation);
+        assert!(!manager.cancel_operation("op"));
+    }
+}
@@ -1,29 +1,115 @@
-use crate::{error::Result, models::*, utils::*};
+use crate::{
+    branch::{is_merged_into, tracking_branch_name, unique_remote_branch},
+    error::{Error, Result},
+    graph::CommitFilter,
+    hooks::{run_hook, run_message_hooks},
+    lfs::{add_lfs_path, is_lfs_path, is_unmodified_lfs_file, lfs_files, smudge_if_installed, LfsFilter},
+    models::*,
+    remote::convert_remote,
+    sequencer::end_pick_or_revert,
+    signing::{create_commit, verify_commit},
+    sparse::skip_worktree_paths,
+    utils::*,
+    watcher::RepoWatcher,
+};
 use git2::{
-    BranchType, IndexAddOption, Repository, Signature, StatusOptions
+    BranchType, IndexAddOption, Repository, Signature, Sort, StatusOptions
 };
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
-pub struct GitManager;
+pub struct GitManager {
+    operations: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    pub(crate) watchers: Mutex<HashMap<String, RepoWatcher>>,
+}
+
+/// Cancellation handle for a long-running operation; unregisters itself when dropped.
+pub(crate) struct Operation<'a> {
+    manager: &'a GitManager,
+    pub(crate) id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Operation<'_> {
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Turn a libgit2 failure into `Error::Cancelled` when it was caused by a cancellation.
+    pub(crate) fn map_err(&self, err: git2::Error) -> Error {
+        if self.is_cancelled() {
+            Error::Cancelled
+        } else {
+            Error::Git(err)
+        }
+    }
+}
+
+impl Drop for Operation<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut operations) = self.manager.operations.lock() {
+            operations.remove(&self.id);
+        }
+    }
+}
 
 impl GitManager {
     pub fn new() -> Self {
-        Self
+        Self {
+            operations: Mutex::new(HashMap::new()),
+            watchers: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    pub(crate) fn begin_operation(&self, operation_id: Option<&str>) -> Operation<'_> {
+        let id = operation_id
+            .map(String::from)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let cancelled = Arc::new(AtomicBool::new(false));
+        
+        if let Ok(mut operations) = self.operations.lock() {
+            operations.insert(id.clone(), cancelled.clone());
+        }
+        
+        Operation {
+            manager: self,
+            id,
+            cancelled,
+        }
     }
     
-    fn get_repo(&self, path: &str) -> Result<Repository> {
+    /// Request cancellation of a running operation. Returns false if no such operation is running.
+    pub fn cancel_operation(&self, operation_id: &str) -> bool {
+        self.operations
+            .lock()
+            .ok()
+            .and_then(|operations| operations.get(operation_id).cloned())
+            .map(|cancelled| cancelled.store(true, Ordering::SeqCst))
+            .is_some()
+    }
+    
+    pub(crate) fn get_repo(&self, path: &str) -> Result<Repository> {
         find_repository(path)
     }
     
     pub fn status(&self, repo_path: &str) -> Result<GitStatus> {
+        self.status_with_options(repo_path, GitStatusOptions::default())
+    }
+
+    pub fn status_with_options(&self, repo_path: &str, options: GitStatusOptions) -> Result<GitStatus> {
         let repo = self.get_repo(repo_path)?;
         let mut status_opts = StatusOptions::new();
         status_opts
             .include_untracked(true)
             .include_ignored(false)
-            .include_unreadable(false);
+            .include_unreadable(false)
+            .exclude_submodules(true);
         
         let statuses = repo.statuses(Some(&mut status_opts))?;
+        let sparse_paths = skip_worktree_paths(&repo)?;
+        let lfs_filter = LfsFilter::find();
         
         let mut staged = Vec::new();
         let mut unstaged = Vec::new();
@@ -43,7 +129,12 @@ impl GitManager {
                 staged.push(create_file_change(path.clone(), status));
             }
             
-            if status.is_wt_modified() || status.is_wt_deleted() {
+            // Files outside a sparse checkout are missing on purpose, and LFS content is compared
+            // with the index through the clean filter
+            let sparse_deleted = status.is_wt_deleted() && sparse_paths.contains(&path);
+            let lfs_unmodified = status.is_wt_modified()
+                && lfs_filter.as_ref().is_some_and(|filter| is_unmodified_lfs_file(&repo, &path, filter));
+            if (status.is_wt_modified() && !lfs_unmodified) || (status.is_wt_deleted() && !sparse_deleted) {
                 unstaged.push(create_file_change(path.clone(), status));
             }
             
@@ -52,9 +143,25 @@ impl GitManager {
             }
         }
         
-        let branch = get_current_branch_name(&repo)?;
-        let (ahead, behind) = self.get_branch_ahead_behind(&repo, &branch)?;
-        
+        // Detached and unborn heads (common in fresh worktrees) have no upstream to compare with
+        let (branch, ahead, behind) = match repo.head() {
+            Ok(head) if head.is_branch() => {
+                let branch = get_current_branch_name(&repo)?;
+                let (ahead, behind) = self.get_branch_ahead_behind(&repo, &branch)?;
+                (branch, ahead, behind)
+            }
+            Ok(_) => (get_current_branch_name(&repo)?, 0, 0),
+            Err(_) => (get_head_branch(&repo).unwrap_or_else(|_| "HEAD".to_string()), 0, 0),
+        };
+
+        let worktree = if repo.is_worktree() {
+            git2::Worktree::open_from_repository(&repo)
+                .ok()
+                .and_then(|worktree| worktree.name().map(String::from))
+        } else {
+            None
+        };
+
         Ok(GitStatus {
             branch,
             ahead,
@@ -63,6 +170,10 @@ impl GitManager {
             unstaged,
             untracked,
             conflicted,
+            workdir: repo.workdir().map(|path| path.to_string_lossy().to_string()),
+            worktree,
+            submodules: self.submodule_statuses(&repo, &options)?,
+            lfs: lfs_files(&repo)?,
         })
     }
     
@@ -85,7 +196,10 @@ impl GitManager {
     pub fn stage_file(&self, repo_path: &str, file_path: &str) -> Result<()> {
         let repo = self.get_repo(repo_path)?;
         let mut index = repo.index()?;
-        index.add_path(Path::new(file_path))?;
+        match LfsFilter::find().filter(|_| is_lfs_path(&repo, file_path)) {
+            Some(filter) => add_lfs_path(&repo, &mut index, file_path, &filter)?,
+            None => index.add_path(Path::new(file_path))?,
+        }
         index.write()?;
         Ok(())
     }
@@ -109,7 +223,25 @@ impl GitManager {
     pub fn stage_all(&self, repo_path: &str) -> Result<()> {
         let repo = self.get_repo(repo_path)?;
         let mut index = repo.index()?;
-        index.add_all(&["."], IndexAddOption::DEFAULT, None)?;
+        let lfs_filter = LfsFilter::find();
+
+        // LFS files are staged through the clean filter instead
+        let mut lfs_paths = Vec::new();
+        let mut skip_lfs = |path: &Path, _: &[u8]| -> i32 {
+            let path = path.to_string_lossy().replace('\\', "/");
+            if lfs_filter.is_some() && is_lfs_path(&repo, &path) {
+                lfs_paths.push(path);
+                1
+            } else {
+                0
+            }
+        };
+        index.add_all(["."], IndexAddOption::DEFAULT, Some(&mut skip_lfs))?;
+        if let Some(filter) = &lfs_filter {
+            for path in &lfs_paths {
+                add_lfs_path(&repo, &mut index, path, filter)?;
+            }
+        }
         index.write()?;
         Ok(())
     }
@@ -130,53 +262,77 @@ impl GitManager {
         Ok(())
     }
     
-    pub fn commit(&self, repo_path: &str, options: GitCommitOptions) -> Result<String> {
+    /// Create a commit from the index, running the repository's commit hooks unless `no_verify`
+    /// is set. Every line the hooks print is passed to `on_hook_output`.
+    pub fn commit<F: Fn(GitHookOutput)>(&self, repo_path: &str, options: GitCommitOptions, on_hook_output: F) -> Result<String> {
         let repo = self.get_repo(repo_path)?;
+        let verify = !options.no_verify.unwrap_or(false);
+        if verify {
+            run_hook(&repo, "pre-commit", &[], &on_hook_output)?;
+        }
+        let amend = options.amend.unwrap_or(false);
+        let message = run_message_hooks(&repo, &options.message, amend, verify, &on_hook_output)?;
+
+        // pre-commit hooks may restage files, so the index is reread after they run
         let mut index = repo.index()?;
+        index.read(false)?;
         let oid = index.write_tree()?;
         let tree = repo.find_tree(oid)?;
         
-        let signature = if let Some(author) = options.author {
-            // Parse author in format "Name <email>"
-            let parts: Vec<&str> = author.split('<').collect();
-            if parts.len() == 2 {
-                let name = parts[0].trim();
-                let email = parts[1].trim_end_matches('>').trim();
-                Signature::now(name, email)?
-            } else {
-                repo.signature()?
-            }
-        } else {
-            repo.signature()?
+        let committer = repo.signature()?;
+        let head = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+
+        // Amending replaces HEAD, so the new commit takes HEAD's parents and keeps its author
+        let (parents, default_author) = match (&head, amend) {
+            (Some(head), true) => (head.parents().collect(), head.author().to_owned()),
+            (None, true) => return Err(Error::InvalidState("nothing to amend".to_string())),
+            (head, false) => (head.iter().cloned().collect::<Vec<_>>(), committer.clone()),
         };
-        
-        let parent_commit = if options.amend.unwrap_or(false) {
-            let head = repo.head()?.peel_to_commit()?;
-            head.parent(0).ok()
-        } else {
-            repo.head().ok().and_then(|h| h.peel_to_commit().ok())
+        let author = match &options.author {
+            Some(author) => parse_author(author)?,
+            None => default_author,
         };
-        
-        let parents = if let Some(parent) = &parent_commit {
-            vec![parent]
-        } else {
-            vec![]
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+
+        let commit_oid = create_commit(&repo, &author, &committer, &message, &tree, &parent_refs, options.sign)?;
+
+        let summary = message.lines().next().unwrap_or("");
+        let reflog_message = match (amend, parents.is_empty()) {
+            (true, _) => format!("commit (amend): {}", summary),
+            (false, true) => format!("commit (initial): {}", summary),
+            (false, false) => format!("commit: {}", summary),
         };
-        
-        let commit_oid = repo.commit(
-            Some("HEAD"),
-            &signature,
-            &signature,
-            &options.message,
-            &tree,
-            &parents,
-        )?;
-        
+        update_head(&repo, commit_oid, &reflog_message)?;
+
+        // Like git, a failing post-commit hook does not undo the commit
+        let _ = run_hook(&repo, "post-commit", &[], &on_hook_output);
+
         Ok(commit_oid.to_string())
     }
     
     pub fn get_branches(&self, repo_path: &str) -> Result<Vec<GitBranch>> {
+        self.get_branches_with_options(repo_path, GitBranchListOptions::default())
+    }
+
+    pub fn get_branches_with_options(&self, repo_path: &str, options: GitBranchListOptions) -> Result<Vec<GitBranch>> {
         let repo = self.get_repo(repo_path)?;
+        let resolve = |rev: &Option<String>| -> Result<Option<git2::Oid>> {
+            match rev {
+                Some(rev) => Ok(Some(
+                    repo.revparse_single(rev)
+                        .and_then(|obj| obj.peel_to_commit())
+                        .map_err(|_| Error::CommitNotFound(rev.clone()))?
+                        .id(),
+                )),
+                None => Ok(None),
+            }
+        };
+        let merged_into = resolve(&options.merged_into)?;
+        let not_merged_into = resolve(&options.not_merged_into)?;
+        let wanted = |tip: git2::Oid| {
+            merged_into.map_or(true, |target| is_merged_into(&repo, tip, target))
+                && not_merged_into.map_or(true, |target| !is_merged_into(&repo, tip, target))
+        };
         let mut branches = Vec::new();
         
         // Local branches
@@ -185,22 +341,25 @@ impl GitManager {
             let name = branch.name()?.unwrap_or("").to_string();
             let is_current = branch.is_head();
             
-            let last_commit = branch.get().peel_to_commit()?.id().to_string();
-            let (ahead, behind) = if let Ok(upstream) = branch.upstream() {
-                let local_oid = branch.get().target().unwrap();
-                let upstream_oid = upstream.get().target().unwrap();
-                get_ahead_behind(&repo, local_oid, upstream_oid)?
-            } else {
-                (0, 0)
+            // Skip branches whose target is missing rather than failing the whole listing
+            let Ok(tip) = branch.get().peel_to_commit().map(|commit| commit.id()) else {
+                continue;
+            };
+            if !wanted(tip) {
+                continue;
+            }
+            let upstream = branch.upstream().ok();
+            let (ahead, behind) = match upstream.as_ref().and_then(|u| u.get().peel_to_commit().ok()) {
+                Some(upstream_commit) => get_ahead_behind(&repo, tip, upstream_commit.id())?,
+                None => (0, 0),
             };
             
             branches.push(GitBranch {
                 name: name.clone(),
                 is_remote: false,
                 is_current,
-                upstream: branch.upstream().ok()
-                    .and_then(|u| u.name().ok().flatten().map(String::from)),
-                last_commit,
+                upstream: upstream.and_then(|u| u.name().ok().flatten().map(String::from)),
+                last_commit: tip.to_string(),
                 ahead,
                 behind,
             });
@@ -210,14 +369,19 @@ impl GitManager {
         for branch in repo.branches(Some(BranchType::Remote))? {
             let (branch, _) = branch?;
             let name = branch.name()?.unwrap_or("").to_string();
-            let last_commit = branch.get().peel_to_commit()?.id().to_string();
+            let Ok(tip) = branch.get().peel_to_commit().map(|commit| commit.id()) else {
+                continue;
+            };
+            if !wanted(tip) {
+                continue;
+            }
             
             branches.push(GitBranch {
                 name,
                 is_remote: true,
                 is_current: false,
                 upstream: None,
-                last_commit,
+                last_commit: tip.to_string(),
                 ahead: 0,
                 behind: 0,
             });
@@ -239,37 +403,84 @@ impl GitManager {
         Ok(())
     }
     
+    /// Switch to a local branch. A branch that only exists on one remote, or the remote branch
+    /// named by `options.track`, is first created locally with that remote branch as upstream.
     pub fn checkout(&self, repo_path: &str, options: GitCheckoutOptions) -> Result<()> {
         let repo = self.get_repo(repo_path)?;
         
-        if let Some(branch_name) = options.branch {
-            if options.create_branch.unwrap_or(false) {
-                self.create_branch(repo_path, &branch_name, None)?;
+        let track = match &options.track {
+            Some(remote_branch) => Some(
+                repo.find_branch(remote_branch, BranchType::Remote)
+                    .map_err(|_| Error::BranchNotFound(remote_branch.clone()))?,
+            ),
+            None => None,
+        };
+        let branch_name = match (&options.branch, &track) {
+            (Some(name), _) => name.clone(),
+            (None, Some(remote_branch)) => tracking_branch_name(&repo, remote_branch)?,
+            (None, None) => return Ok(()),
+        };
+        
+        let track = match track {
+            Some(track) => Some(track),
+            None if repo.find_branch(&branch_name, BranchType::Local).is_err() && !options.create_branch.unwrap_or(false) => {
+                Some(unique_remote_branch(&repo, &branch_name)?.ok_or_else(|| Error::BranchNotFound(branch_name.clone()))?)
             }
-            
-            let obj = repo.revparse_single(&format!("refs/heads/{}", branch_name))?;
-            repo.checkout_tree(&obj, None)?;
-            repo.set_head(&format!("refs/heads/{}", branch_name))?;
+            None => None,
+        };
+        
+        if let Some(remote_branch) = &track {
+            let start = remote_branch.get().peel_to_commit()?;
+            let mut branch = repo.branch(&branch_name, &start, false)?;
+            branch.set_upstream(remote_branch.name()?)?;
+        } else if options.create_branch.unwrap_or(false) {
+            self.create_branch(repo_path, &branch_name, None)?;
+        }
+        
+        let refname = format!("refs/heads/{}", branch_name);
+        let obj = repo.revparse_single(&refname)?;
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        if options.force.unwrap_or(false) {
+            checkout.force();
+        } else {
+            checkout.safe();
         }
+        repo.checkout_tree(&obj, Some(&mut checkout))?;
+        repo.set_head(&refname)?;
+        smudge_if_installed(&repo)?;
         
         Ok(())
     }
     
+    /// Move HEAD's branch to `options.target`. Mixed and hard resets also reset the index, hard
+    /// resets the working tree as well, and both end any merge, revert or cherry-pick in progress.
+    pub fn reset(&self, repo_path: &str, options: GitResetOptions) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let target = repo
+            .revparse_single(&options.target)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(|_| Error::CommitNotFound(options.target.clone()))?;
+
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout.force();
+        repo.reset(target.as_object(), convert_reset_mode(&options.mode), Some(&mut checkout))?;
+        if !matches!(options.mode, ResetMode::Soft) {
+            // libgit2 only clears its own merge state, not the plan of a stopped sequence
+            end_pick_or_revert(&repo)?;
+        }
+        if matches!(options.mode, ResetMode::Hard) {
+            smudge_if_installed(&repo)?;
+        }
+        Ok(())
+    }
+    
     pub fn get_remotes(&self, repo_path: &str) -> Result<Vec<GitRemote>> {
         let repo = self.get_repo(repo_path)?;
         let mut remotes = Vec::new();
         
-        for remote_name in repo.remotes()?.iter() {
-            if let Some(name) = remote_name {
-                let remote = repo.find_remote(name)?;
-                
-                remotes.push(GitRemote {
-                    name: name.to_string(),
-                    url: remote.url().unwrap_or("").to_string(),
-                    fetch_url: remote.url().map(String::from),
-                    push_url: remote.pushurl().map(String::from),
-                });
-            }
+        for name in repo.remotes()?.iter().flatten() {
+            let remote = repo.find_remote(name)?;
+            remotes.push(convert_remote(&repo, &remote)?);
         }
         
         Ok(remotes)
@@ -277,55 +488,145 @@ impl GitManager {
     
     pub fn get_log(&self, repo_path: &str, options: GitLogOptions) -> Result<Vec<GitCommit>> {
         let repo = self.get_repo(repo_path)?;
+        let filter = CommitFilter::new(
+            options.author.as_deref(),
+            options.grep.as_deref(),
+            options.since.as_deref(),
+            options.until.as_deref(),
+            options.path.as_deref(),
+        )?;
+
         let mut revwalk = repo.revwalk()?;
-        
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
         // Set starting point
         if options.all.unwrap_or(false) {
             revwalk.push_glob("refs/*")?;
-        } else {
+        } else if repo.head().is_ok() {
             revwalk.push_head()?;
+        } else {
+            // Nothing to walk on an unborn branch
+            return Ok(Vec::new());
         }
-        
-        // Apply filters
+
+        // Skip and count only commits that pass the filters
         let mut commits = Vec::new();
-        let skip = options.skip.unwrap_or(0);
+        let mut skip = options.skip.unwrap_or(0);
         let max_count = options.max_count.unwrap_or(100);
-        
-        for (idx, oid) in revwalk.enumerate() {
-            if idx < skip {
-                continue;
-            }
-            
+
+        for oid in revwalk {
             if commits.len() >= max_count {
                 break;
             }
-            
-            let oid = oid?;
-            let commit = repo.find_commit(oid)?;
-            
-            // Apply filters
-            if let Some(author) = &options.author {
-                if !commit.author().name().unwrap_or("").contains(author) &&
-                   !commit.author().email().unwrap_or("").contains(author) {
-                    continue;
-                }
+
+            let commit = repo.find_commit(oid?)?;
+            if !filter.matches(&commit)? {
+                continue;
             }
-            
-            if let Some(grep) = &options.grep {
-                if !commit.message().unwrap_or("").contains(grep) {
-                    continue;
-                }
+            if skip > 0 {
+                skip -= 1;
+                continue;
             }
-            
-            commits.push(convert_commit(&commit)?);
+
+            let mut entry = convert_commit(&commit)?;
+            if options.show_signature.unwrap_or(false) {
+                entry.signature = verify_commit(&repo, commit.id());
+            }
+            commits.push(entry);
         }
-        
+
         Ok(commits)
     }
 }
 
+/// Parse an identity in the form "Name <email>".
+fn parse_author(author: &str) -> Result<Signature<'static>> {
+    let invalid = || Error::InvalidAuthor(format!("'{}' is not in the form \"Name <email>\"", author));
+    let (name, rest) = author.rsplit_once('<').ok_or_else(invalid)?;
+    let email = rest.trim().strip_suffix('>').ok_or_else(invalid)?;
+    let name = name.trim();
+    if name.is_empty() || email.trim().is_empty() {
+        return Err(invalid());
+    }
+    Ok(Signature::now(name, email.trim())?)
+}
+
+/// Point HEAD, or the branch it refers to (even an unborn one), at `oid`.
+pub(crate) fn update_head(repo: &Repository, oid: git2::Oid, reflog_message: &str) -> Result<()> {
+    let head = repo.find_reference("HEAD")?;
+    let target = head.symbolic_target().unwrap_or("HEAD").to_string();
+    repo.reference(&target, oid, true, reflog_message)?;
+    Ok(())
+}
+
 impl Default for GitManager {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use crate::test_support::{init_repo, use_fake_signer};
+
+    #[test]
+    fn test_commit_amend_keeps_parents_and_author() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Committer").unwrap();
+        config.set_str("user.email", "committer@example.com").unwrap();
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+        let commit = |message: &str, amend: bool, author: Option<&str>| {
+            let options = GitCommitOptions {
+                message: message.to_string(),
+                amend: Some(amend),
+                author: author.map(String::from),
+                signoff: None,
+                sign: Some(false),
+                no_verify: None,
+            };
+            manager.commit(repo_path, options, |_| {})
+        };
+
+        let first = commit("first", false, None).unwrap();
+        commit("second", false, Some("Jane Q. Doe <jane@example.com>")).unwrap();
+        let amended = commit("second, amended", true, None).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.id().to_string(), amended);
+        assert_eq!(head.parent_id(0).unwrap().to_string(), first);
+        assert_eq!(head.author().name(), Some("Jane Q. Doe"));
+        assert_eq!(head.committer().email(), Some("committer@example.com"));
+
+        assert!(matches!(commit("bad", false, Some("nobody")), Err(Error::InvalidAuthor(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commit_signs_with_configured_program() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(&dir.path().join("repo"));
+        use_fake_signer(&repo, dir.path());
+
+        let manager = GitManager::new();
+        let options = GitCommitOptions {
+            message: "signed".to_string(),
+            amend: None,
+            author: None,
+            signoff: None,
+            sign: None,
+            no_verify: None,
+        };
+        let oid = manager.commit(repo.workdir().unwrap().to_str().unwrap(), options, |_| {}).unwrap();
+
+        let oid = git2::Oid::from_str(&oid).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(oid));
+        let (signature, _) = repo.extract_signature(&oid, None).unwrap();
+        assert!(signature.as_str().unwrap().contains("fake"));
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{
+    diff::find_similar,
+    error::{Error, Result},
+    models::*,
+    repository::GitManager,
+    utils::*,
+};
+use git2::{Commit, DiffFile, DiffLineType as Git2DiffLineType, DiffOptions, Patch, Repository, Sort};
+use regex::bytes::{Regex, RegexBuilder};
+
+impl GitManager {
+    /// Find the commits whose changes add or remove `query`, newest first, passing each one to
+    /// `on_match` as soon as it is found. Merge commits are not searched, as with `git log -S`.
+    pub fn search_history<F>(&self, repo_path: &str, options: GitSearchOptions, on_match: F) -> Result<Vec<GitSearchMatch>>
+    where
+        F: Fn(&GitSearchMatch),
+    {
+        let repo = self.get_repo(repo_path)?;
+        let operation = self.begin_operation(options.operation_id.as_deref());
+        let mode = options.mode.unwrap_or_default();
+        let pattern = build_pattern(&options, mode)?;
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        if options.all.unwrap_or(false) {
+            revwalk.push_glob("refs/*")?;
+        } else if let Some(revision) = &options.revision {
+            revwalk.push(repo.revparse_single(revision)?.peel_to_commit()?.id())?;
+        } else if repo.head().is_ok() {
+            revwalk.push_head()?;
+        } else {
+            return Ok(Vec::new());
+        }
+
+        let paths = options.paths.unwrap_or_default();
+        let max_count = options.max_count.unwrap_or(usize::MAX);
+        let mut matches = Vec::new();
+        for oid in revwalk {
+            if operation.is_cancelled() {
+                return Err(Error::Cancelled);
+            }
+            if matches.len() >= max_count {
+                break;
+            }
+
+            let commit = repo.find_commit(oid?)?;
+            let files = search_commit(&repo, &commit, &pattern, mode, &paths)?;
+            if files.is_empty() {
+                continue;
+            }
+            let found = GitSearchMatch {
+                operation_id: operation.id.clone(),
+                commit: convert_commit(&commit)?,
+                files,
+            };
+            on_match(&found);
+            matches.push(found);
+        }
+        Ok(matches)
+    }
+}
+
+fn build_pattern(options: &GitSearchOptions, mode: GitSearchMode) -> Result<Regex> {
+    if options.query.is_empty() {
+        return Err(Error::InvalidSearch("the query is empty".to_string()));
+    }
+    let pattern = match (mode, options.regex.unwrap_or(false)) {
+        (GitSearchMode::Occurrences, false) => regex::escape(&options.query),
+        _ => options.query.clone(),
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(options.ignore_case.unwrap_or(false))
+        .build()
+        .map_err(|e| Error::InvalidSearch(e.to_string()))
+}
+
+/// The files of a commit that match, compared with its parent (or nothing for a root commit).
+fn search_commit(
+    repo: &Repository,
+    commit: &Commit,
+    pattern: &Regex,
+    mode: GitSearchMode,
+    paths: &[String],
+) -> Result<Vec<GitSearchFileMatch>> {
+    if commit.parent_count() > 1 {
+        return Ok(Vec::new());
+    }
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+
+    let mut diff_opts = DiffOptions::new();
+    diff_opts.context_lines(0);
+    for path in paths {
+        diff_opts.pathspec(path);
+    }
+    let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), Some(&mut diff_opts))?;
+    // Renamed files only match when their content changes too
+    find_similar(&mut diff, &GitDiffOptions::default())?;
+
+    let mut files = Vec::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        if mode == GitSearchMode::Occurrences
+            && count_matches(repo, &delta.old_file(), pattern) == count_matches(repo, &delta.new_file(), pattern)
+        {
+            continue;
+        }
+        let Some(patch) = Patch::from_diff(&diff, idx)? else {
+            continue;
+        };
+        let lines = matching_lines(&patch, pattern)?;
+        if mode == GitSearchMode::Lines && lines.is_empty() {
+            continue;
+        }
+
+        let path = |file: DiffFile| file.path().map(|path| path.to_string_lossy().to_string());
+        let new_path = path(delta.new_file()).unwrap_or_default();
+        files.push(GitSearchFileMatch {
+            old_path: path(delta.old_file()).filter(|old_path| *old_path != new_path),
+            path: new_path,
+            lines,
+        });
+    }
+    Ok(files)
+}
+
+/// Occurrences of the pattern in one side of a delta; binary files never match.
+fn count_matches(repo: &Repository, file: &DiffFile, pattern: &Regex) -> usize {
+    if file.id().is_zero() {
+        return 0;
+    }
+    match repo.find_blob(file.id()) {
+        Ok(blob) if !blob.is_binary() => pattern.find_iter(blob.content()).count(),
+        _ => 0,
+    }
+}
+
+fn matching_lines(patch: &Patch, pattern: &Regex) -> Result<Vec<GitDiffLine>> {
+    let mut lines = Vec::new();
+    for hunk_idx in 0..patch.num_hunks() {
+        for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            let line_type = match line.origin_value() {
+                Git2DiffLineType::Addition => DiffLineType::Add,
+                Git2DiffLineType::Deletion => DiffLineType::Delete,
+                _ => continue,
+            };
+            if !pattern.is_match(line.content()) {
+                continue;
+            }
+            lines.push(GitDiffLine {
+                line_type,
+                content: String::from_utf8_lossy(line.content()).trim_end_matches('\n').to_string(),
+                old_line_number: line.old_lineno(),
+                new_line_number: line.new_lineno(),
+            });
+        }
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::commit_files;
+
+    fn search(manager: &GitManager, repo_path: &str, options: GitSearchOptions) -> Vec<String> {
+        manager
+            .search_history(repo_path, options, |_| {})
+            .unwrap()
+            .into_iter()
+            .map(|found| found.commit.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_search_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+
+        commit_files(&repo, &[("a.txt", "fn main() {\n    foo(1);\n}\n"), ("b.txt", "nothing\n")], "initial");
+        commit_files(&repo, &[("a.txt", "// TODO: tidy\nfn main() {\n    foo(1);\n}\n")], "add todo");
+        commit_files(&repo, &[("b.txt", "TODO later\n")], "todo in b");
+        commit_files(&repo, &[("a.txt", "// TODO: tidy\nfn main() {\n    foo(2);\n}\n")], "change argument");
+        commit_files(&repo, &[("a.txt", "fn main() {\n    foo(2);\n}\n")], "remove todo");
+
+        // Like -S, only commits that change the number of occurrences match
+        let query = |query: &str| GitSearchOptions {
+            query: query.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(search(&manager, repo_path, query("TODO")), ["remove todo", "todo in b", "add todo"]);
+        assert_eq!(search(&manager, repo_path, query("foo(")), ["initial"]);
+        assert!(search(&manager, repo_path, query("todo")).is_empty());
+
+        let options = GitSearchOptions {
+            ignore_case: Some(true),
+            paths: Some(vec!["a.txt".to_string()]),
+            ..query("todo")
+        };
+        let found = manager.search_history(repo_path, options, |_| {}).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].files[0].path, "a.txt");
+        let line = &found[0].files[0].lines[0];
+        assert!(matches!(line.line_type, DiffLineType::Delete));
+        assert_eq!((line.content.as_str(), line.old_line_number), ("// TODO: tidy", Some(1)));
+
+        // Like -G, any added or removed line matching the expression counts
+        let options = GitSearchOptions {
+            mode: Some(GitSearchMode::Lines),
+            ..query(r"foo\(\d\)")
+        };
+        assert_eq!(search(&manager, repo_path, options), ["change argument", "initial"]);
+        let options = GitSearchOptions {
+            regex: Some(true),
+            ..query("foo(")
+        };
+        assert!(matches!(manager.search_history(repo_path, options, |_| {}), Err(Error::InvalidSearch(_))));
+
+        // Results are streamed, and the search stops once cancelled
+        let options = GitSearchOptions {
+            operation_id: Some("search".to_string()),
+            ..query("TODO")
+        };
+        let result = manager.search_history(repo_path, options, |found| {
+            assert_eq!(found.operation_id, "search");
+            manager.cancel_operation("search");
+        });
+        assert!(matches!(result, Err(Error::Cancelled)));
+
+        let options = GitSearchOptions {
+            max_count: Some(1),
+            ..query("TODO")
+        };
+        assert_eq!(search(&manager, repo_path, options), ["remove todo"]);
+    }
+}
@@ -0,0 +1,606 @@
+use crate::{
+    conflicts::collect_conflicts,
+    error::{Error, Result},
+    merge::ensure_clean_state,
+    models::*,
+    repository::{update_head, GitManager},
+    signing::create_commit,
+};
+use git2::{
+    build::CheckoutBuilder, CherrypickOptions, Commit, Oid, Repository, ResetType, RevertOptions, Sort,
+    StatusOptions,
+};
+use std::path::PathBuf;
+
+/// State file kept in the git directory while a plan is in progress. It must not clash with
+/// git's own `sequencer/` directory.
+const STATE_FILE: &str = "plugin-sequencer.json";
+
+impl GitManager {
+    /// Start an interactive rebase: check out `onto` and apply the plan step by step.
+    pub fn interactive_rebase(&self, repo_path: &str, options: GitInteractiveRebaseOptions) -> Result<GitSequencerState> {
+        let repo = self.get_repo(repo_path)?;
+        ensure_clean_state(&repo)?;
+        ensure_clean_worktree(&repo)?;
+        validate_plan(&repo, &options.todo)?;
+
+        let onto = repo.revparse_single(&options.onto)?.peel_to_commit()?;
+        let head = repo.head()?;
+        let head_name = if head.is_branch() {
+            head.name().map(String::from)
+        } else {
+            None
+        };
+        let orig_head = head.peel_to_commit()?.id();
+
+        repo.checkout_tree(onto.as_object(), Some(CheckoutBuilder::new().safe()))?;
+        repo.set_head_detached(onto.id())?;
+
+        let mut state = GitSequencerState {
+            operation: GitSequencerOperation::Rebase,
+            status: GitSequencerStatus::Completed,
+            onto: onto.id().to_string(),
+            orig_head: orig_head.to_string(),
+            head_name,
+            done: Vec::new(),
+            todo: options.todo,
+            current: None,
+            conflicts: Vec::new(),
+        };
+        save_state(&repo, &state)?;
+        run_sequencer(&repo, &mut state)?;
+        Ok(state)
+    }
+
+    /// Apply commits on top of HEAD, like `git cherry-pick`. Stops on conflicts; resume with
+    /// `sequencer_continue` or roll back with `sequencer_abort`.
+    pub fn cherry_pick(&self, repo_path: &str, options: GitCherryPickOptions) -> Result<GitSequencerState> {
+        let repo = self.get_repo(repo_path)?;
+
+        let mut todo = Vec::new();
+        for rev in &options.commits {
+            for oid in resolve_commits(&repo, rev, true)? {
+                let message = match options.record_origin.unwrap_or(false) {
+                    true => {
+                        let commit = repo.find_commit(oid)?;
+                        let message = String::from_utf8_lossy(commit.message_bytes()).to_string();
+                        Some(format!("{}\n\n(cherry picked from commit {})\n", message.trim_end(), oid))
+                    }
+                    false => None,
+                };
+                todo.push(GitRebaseTodoItem {
+                    action: GitRebaseAction::Pick,
+                    commit: oid.to_string(),
+                    message,
+                });
+            }
+        }
+
+        start_sequence(&repo, GitSequencerOperation::CherryPick, todo)
+    }
+
+    /// Commit the inverse of each commit on top of HEAD, like `git revert`. Stops on conflicts
+    /// like `cherry_pick`.
+    pub fn revert(&self, repo_path: &str, options: GitRevertOptions) -> Result<GitSequencerState> {
+        let repo = self.get_repo(repo_path)?;
+
+        let mut todo = Vec::new();
+        for rev in &options.commits {
+            for oid in resolve_commits(&repo, rev, false)? {
+                todo.push(GitRebaseTodoItem {
+                    action: GitRebaseAction::Revert,
+                    commit: oid.to_string(),
+                    message: None,
+                });
+            }
+        }
+
+        start_sequence(&repo, GitSequencerOperation::Revert, todo)
+    }
+
+    /// Resume after resolving conflicts or amending an `edit` stop.
+    pub fn sequencer_continue(&self, repo_path: &str) -> Result<GitSequencerState> {
+        let repo = self.get_repo(repo_path)?;
+        let mut state = load_state(&repo)?;
+
+        match state.status {
+            GitSequencerStatus::Conflicts => {
+                let mut index = repo.index()?;
+                index.read(true)?;
+                if index.has_conflicts() {
+                    return Err(Error::MergeConflict);
+                }
+                if let Some(item) = state.current.take() {
+                    let commit = find_plan_commit(&repo, &item.commit)?;
+                    commit_step(&repo, &item, &commit)?;
+                    state.done.push(item.clone());
+                    if item.action == GitRebaseAction::Edit {
+                        state.current = Some(item);
+                        state.status = GitSequencerStatus::Edit;
+                        save_state(&repo, &state)?;
+                        return Ok(state);
+                    }
+                }
+            }
+            GitSequencerStatus::Edit => {
+                amend_with_index(&repo)?;
+                state.current = None;
+            }
+            GitSequencerStatus::Completed => {}
+        }
+
+        run_sequencer(&repo, &mut state)?;
+        Ok(state)
+    }
+
+    /// Drop the step that stopped on conflicts and carry on with the plan.
+    pub fn sequencer_skip(&self, repo_path: &str) -> Result<GitSequencerState> {
+        let repo = self.get_repo(repo_path)?;
+        let mut state = load_state(&repo)?;
+        if state.status != GitSequencerStatus::Conflicts {
+            return Err(Error::InvalidState("the current step has no conflicts to skip".to_string()));
+        }
+
+        let head = repo.head()?.peel_to_commit()?;
+        repo.reset(head.as_object(), ResetType::Hard, Some(CheckoutBuilder::new().force()))?;
+        repo.cleanup_state()?;
+        if let Some(mut item) = state.current.take() {
+            item.action = GitRebaseAction::Drop;
+            state.done.push(item);
+        }
+
+        run_sequencer(&repo, &mut state)?;
+        Ok(state)
+    }
+
+    /// Restore the branch and working tree to where they were before the operation started.
+    pub fn sequencer_abort(&self, repo_path: &str) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let state = load_state(&repo)?;
+
+        let orig_head = repo.find_commit(Oid::from_str(&state.orig_head)?)?;
+        match &state.head_name {
+            Some(name) => repo.set_head(name)?,
+            None => repo.set_head_detached(orig_head.id())?,
+        }
+        repo.reset(orig_head.as_object(), ResetType::Hard, Some(CheckoutBuilder::new().force()))?;
+        repo.cleanup_state()?;
+        clear_state(&repo)
+    }
+
+    /// The state of the interactive rebase, cherry-pick or revert in progress, if any.
+    pub fn sequencer_state(&self, repo_path: &str) -> Result<Option<GitSequencerState>> {
+        let repo = self.get_repo(repo_path)?;
+        if !state_path(&repo).exists() {
+            return Ok(None);
+        }
+
+        let mut state = load_state(&repo)?;
+        if state.status == GitSequencerStatus::Conflicts {
+            state.conflicts = collect_conflicts(&repo, &repo.index()?)?;
+        }
+        Ok(Some(state))
+    }
+}
+
+pub(crate) fn sequencer_in_progress(repo: &Repository) -> bool {
+    state_path(repo).exists()
+}
+
+fn state_path(repo: &Repository) -> PathBuf {
+    repo.path().join(STATE_FILE)
+}
+
+fn load_state(repo: &Repository) -> Result<GitSequencerState> {
+    let content = std::fs::read_to_string(state_path(repo))
+        .map_err(|_| Error::InvalidState("no interactive rebase, cherry-pick or revert in progress".to_string()))?;
+    serde_json::from_str(&content).map_err(|e| Error::Generic(e.into()))
+}
+
+fn save_state(repo: &Repository, state: &GitSequencerState) -> Result<()> {
+    let content = serde_json::to_string_pretty(state).map_err(|e| Error::Generic(e.into()))?;
+    std::fs::write(state_path(repo), content)?;
+    Ok(())
+}
+
+/// Forget a stopped cherry-pick or revert, as mixed and hard resets do. Interactive rebases
+/// survive a reset, as they do in git.
+pub(crate) fn end_pick_or_revert(repo: &Repository) -> Result<()> {
+    match load_state(repo) {
+        Ok(state) if state.operation != GitSequencerOperation::Rebase => clear_state(repo),
+        _ => Ok(()),
+    }
+}
+
+fn clear_state(repo: &Repository) -> Result<()> {
+    let path = state_path(repo);
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn ensure_clean_worktree(repo: &Repository) -> Result<()> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+    if repo.statuses(Some(&mut opts))?.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::UncommittedChanges)
+    }
+}
+
+fn validate_plan(repo: &Repository, todo: &[GitRebaseTodoItem]) -> Result<()> {
+    let mut has_base = false;
+    for item in todo {
+        find_plan_commit(repo, &item.commit)?;
+        match item.action {
+            GitRebaseAction::Drop => {}
+            GitRebaseAction::Squash | GitRebaseAction::Fixup if !has_base => {
+                return Err(Error::InvalidRebasePlan(format!(
+                    "{} has no previous commit to squash into",
+                    item.commit
+                )));
+            }
+            _ => has_base = true,
+        }
+    }
+    Ok(())
+}
+
+/// Begin a cherry-pick or revert of `todo` on the current HEAD. Unlike a rebase, the commits
+/// go straight onto the checked-out branch.
+fn start_sequence(repo: &Repository, operation: GitSequencerOperation, todo: Vec<GitRebaseTodoItem>) -> Result<GitSequencerState> {
+    ensure_clean_state(repo)?;
+    ensure_clean_worktree(repo)?;
+    if todo.is_empty() {
+        return Err(Error::InvalidSelection("no commits to apply".to_string()));
+    }
+
+    let head = repo.head()?;
+    let head_name = if head.is_branch() {
+        head.name().map(String::from)
+    } else {
+        None
+    };
+    let head_id = head.peel_to_commit()?.id().to_string();
+
+    let mut state = GitSequencerState {
+        operation,
+        status: GitSequencerStatus::Completed,
+        onto: head_id.clone(),
+        orig_head: head_id,
+        head_name,
+        done: Vec::new(),
+        todo,
+        current: None,
+        conflicts: Vec::new(),
+    };
+    save_state(repo, &state)?;
+    run_sequencer(repo, &mut state)?;
+    Ok(state)
+}
+
+/// The commits named by `rev`: a single revision, or every commit in an `A..B` range.
+pub(crate) fn resolve_commits(repo: &Repository, rev: &str, oldest_first: bool) -> Result<Vec<Oid>> {
+    if !rev.contains("..") {
+        return Ok(vec![find_plan_commit(repo, rev)?.id()]);
+    }
+
+    let spec = repo.revparse(rev).map_err(|_| Error::CommitNotFound(rev.to_string()))?;
+    let mut revwalk = repo.revwalk()?;
+    match oldest_first {
+        true => revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?,
+        false => revwalk.set_sorting(Sort::TOPOLOGICAL)?,
+    }
+    if let Some(to) = spec.to() {
+        revwalk.push(to.peel_to_commit()?.id())?;
+    }
+    if let Some(from) = spec.from() {
+        revwalk.hide(from.peel_to_commit()?.id())?;
+    }
+    Ok(revwalk.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+fn find_plan_commit<'r>(repo: &'r Repository, rev: &str) -> Result<Commit<'r>> {
+    repo.revparse_single(rev)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| Error::CommitNotFound(rev.to_string()))
+}
+
+/// Apply the remaining steps, stopping on conflicts and after `edit` steps. The state is saved
+/// after every step so the plan can be resumed from another call.
+fn run_sequencer(repo: &Repository, state: &mut GitSequencerState) -> Result<()> {
+    state.conflicts.clear();
+
+    while !state.todo.is_empty() {
+        let item = state.todo.remove(0);
+        if item.action == GitRebaseAction::Drop {
+            state.done.push(item);
+            continue;
+        }
+
+        let commit = find_plan_commit(repo, &item.commit)?;
+        let head = repo.head()?.peel_to_commit()?;
+        let keeps_commit = match item.action {
+            GitRebaseAction::Pick | GitRebaseAction::Edit => true,
+            GitRebaseAction::Reword => item.message.is_none(),
+            _ => false,
+        };
+
+        // A rebase reuses commits that are already on top of HEAD instead of recreating them
+        let reusable = state.operation == GitSequencerOperation::Rebase
+            && keeps_commit
+            && commit.parent_count() == 1
+            && commit.parent_id(0)? == head.id();
+
+        if reusable {
+            repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))?;
+            repo.set_head_detached(commit.id())?;
+        } else {
+            if item.action == GitRebaseAction::Revert {
+                let mut opts = RevertOptions::new();
+                if commit.parent_count() > 1 {
+                    opts.mainline(1);
+                }
+                repo.revert(&commit, Some(&mut opts))?;
+            } else {
+                let mut opts = CherrypickOptions::new();
+                if commit.parent_count() > 1 {
+                    opts.mainline(1);
+                }
+                repo.cherrypick(&commit, Some(&mut opts))?;
+            }
+
+            let index = repo.index()?;
+            if index.has_conflicts() {
+                state.conflicts = collect_conflicts(repo, &index)?;
+                state.current = Some(item);
+                state.status = GitSequencerStatus::Conflicts;
+                return save_state(repo, state);
+            }
+            commit_step(repo, &item, &commit)?;
+        }
+
+        state.done.push(item.clone());
+        if item.action == GitRebaseAction::Edit {
+            state.current = Some(item);
+            state.status = GitSequencerStatus::Edit;
+            return save_state(repo, state);
+        }
+        save_state(repo, state)?;
+    }
+
+    finish(repo, state)
+}
+
+/// Record the index as the result of `item`: a new commit for picks and reverts, or an amended
+/// HEAD for squash and fixup steps.
+fn commit_step(repo: &Repository, item: &GitRebaseTodoItem, commit: &Commit) -> Result<()> {
+    let mut index = repo.index()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let signature = repo.signature()?;
+    let original_message = String::from_utf8_lossy(commit.message_bytes()).to_string();
+
+    match item.action {
+        GitRebaseAction::Squash | GitRebaseAction::Fixup => {
+            let head_message = String::from_utf8_lossy(head.message_bytes()).to_string();
+            let message = match (item.action, &item.message) {
+                (GitRebaseAction::Squash, Some(message)) => message.clone(),
+                (GitRebaseAction::Squash, None) => format!("{}\n\n{}", head_message.trim_end(), original_message),
+                _ => head_message,
+            };
+            let parents: Vec<Commit> = head.parents().collect();
+            let parent_refs: Vec<&Commit> = parents.iter().collect();
+            let oid = create_commit(repo, &head.author(), &signature, &message, &tree, &parent_refs, None)?;
+            repo.set_head_detached(oid)?;
+        }
+        GitRebaseAction::Revert => {
+            let message = item.message.clone().unwrap_or_else(|| {
+                format!(
+                    "Revert \"{}\"\n\nThis reverts commit {}.\n",
+                    commit.summary().unwrap_or(""),
+                    commit.id()
+                )
+            });
+            if tree.id() != head.tree_id() {
+                let oid = create_commit(repo, &signature, &signature, &message, &tree, &[&head], None)?;
+                update_head(repo, oid, &format!("revert: {}", message.lines().next().unwrap_or("")))?;
+            }
+        }
+        _ => {
+            // Nothing left to commit when the change is already in the new base
+            if tree.id() != head.tree_id() {
+                let message = item.message.clone().unwrap_or(original_message);
+                let oid = create_commit(repo, &commit.author(), &signature, &message, &tree, &[&head], None)?;
+                update_head(repo, oid, &format!("commit: {}", message.lines().next().unwrap_or("")))?;
+            }
+        }
+    }
+
+    repo.cleanup_state()?;
+    Ok(())
+}
+
+/// Fold staged changes into HEAD after an `edit` stop, as `git commit --amend` would.
+fn amend_with_index(repo: &Repository) -> Result<()> {
+    let mut index = repo.index()?;
+    index.read(true)?;
+    let tree_id = index.write_tree()?;
+    let head = repo.head()?.peel_to_commit()?;
+    if tree_id != head.tree_id() {
+        let tree = repo.find_tree(tree_id)?;
+        let message = String::from_utf8_lossy(head.message_bytes()).to_string();
+        let parents: Vec<Commit> = head.parents().collect();
+        let parent_refs: Vec<&Commit> = parents.iter().collect();
+        let oid = create_commit(repo, &head.author(), &repo.signature()?, &message, &tree, &parent_refs, None)?;
+        update_head(repo, oid, &format!("commit (amend): {}", head.summary().unwrap_or("")))?;
+    }
+    Ok(())
+}
+
+fn finish(repo: &Repository, state: &mut GitSequencerState) -> Result<()> {
+    let head_id = repo.head()?.peel_to_commit()?.id();
+    // Cherry-picks and reverts commit onto the branch directly
+    if let Some(name) = state.head_name.as_ref().filter(|_| state.operation == GitSequencerOperation::Rebase) {
+        let reflog_msg = format!("rebase -i (finish): {} onto {}", name, state.onto);
+        repo.reference(name, head_id, true, &reflog_msg)?;
+        repo.set_head(name)?;
+    }
+
+    state.status = GitSequencerStatus::Completed;
+    state.current = None;
+    clear_state(repo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use std::path::Path;
+
+    fn item(action: GitRebaseAction, commit: Oid) -> GitRebaseTodoItem {
+        GitRebaseTodoItem {
+            action,
+            commit: commit.to_string(),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_reorder_squash_and_edit() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(dir.path());
+
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        let first = commit_file(&repo, "b.txt", "b\n", "add b");
+        let fixup = commit_file(&repo, "b.txt", "b fixed\n", "fix b");
+        let second = commit_file(&repo, "c.txt", "c\n", "add c");
+        let dropped = commit_file(&repo, "d.txt", "d\n", "add d");
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+        let options = GitInteractiveRebaseOptions {
+            onto: base.to_string(),
+            todo: vec![
+                item(GitRebaseAction::Edit, second),
+                item(GitRebaseAction::Pick, first),
+                item(GitRebaseAction::Fixup, fixup),
+                item(GitRebaseAction::Drop, dropped),
+            ],
+        };
+
+        let state = manager.interactive_rebase(repo_path, options).unwrap();
+        assert_eq!(state.status, GitSequencerStatus::Edit);
+        let merge = GitMergeOptions {
+            branch: dropped.to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(manager.merge(repo_path, merge), Err(Error::InvalidState(_))));
+
+        // Amend the stopped commit before continuing
+        std::fs::write(dir.path().join("c.txt"), "c edited\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        index.add_path(Path::new("c.txt")).unwrap();
+        index.write().unwrap();
+
+        let state = manager.sequencer_continue(repo_path).unwrap();
+        assert_eq!(state.status, GitSequencerStatus::Completed);
+        assert!(manager.sequencer_state(repo_path).unwrap().is_none());
+
+        let head = repo.head().unwrap();
+        assert!(head.is_branch());
+        let top = head.peel_to_commit().unwrap();
+        assert_eq!(top.summary(), Some("add b"));
+        assert_eq!(top.parent(0).unwrap().summary(), Some("add c"));
+        assert_eq!(top.parent(0).unwrap().parent_id(0).unwrap(), base);
+        assert_eq!(std::fs::read_to_string(dir.path().join("b.txt")).unwrap(), "b fixed\n");
+        assert_eq!(std::fs::read_to_string(dir.path().join("c.txt")).unwrap(), "c edited\n");
+        assert!(!dir.path().join("d.txt").exists());
+    }
+
+    #[test]
+    fn test_cherry_pick_range_and_revert_with_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(dir.path());
+
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        let main_branch = repo.head().unwrap().name().unwrap().to_string();
+        let base_commit = repo.find_commit(base).unwrap();
+        repo.branch("topic", &base_commit, false).unwrap();
+        let main_tip = commit_file(&repo, "a.txt", "main\n", "main edit");
+
+        repo.set_head("refs/heads/topic").unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force())).unwrap();
+        commit_file(&repo, "b.txt", "b\n", "add b");
+        commit_file(&repo, "a.txt", "topic\n", "topic edit");
+        let topic_tip = commit_file(&repo, "c.txt", "c\n", "add c");
+
+        repo.set_head(&main_branch).unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force())).unwrap();
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+        let options = GitCherryPickOptions {
+            commits: vec![format!("{}..topic", base)],
+            record_origin: Some(true),
+        };
+        let state = manager.cherry_pick(repo_path, options.clone()).unwrap();
+        assert_eq!(state.operation, GitSequencerOperation::CherryPick);
+        assert_eq!(state.status, GitSequencerStatus::Conflicts);
+        assert_eq!(state.done.len(), 1);
+        assert_eq!(state.conflicts[0].path, "a.txt");
+        assert_eq!(state.conflicts[0].ancestor.as_ref().unwrap().content.as_deref(), Some("a\n"));
+        assert_eq!(state.conflicts[0].ours.as_ref().unwrap().content.as_deref(), Some("main\n"));
+        assert_eq!(state.conflicts[0].theirs.as_ref().unwrap().content.as_deref(), Some("topic\n"));
+
+        // Abort puts the branch back
+        manager.sequencer_abort(repo_path).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(main_tip));
+        assert!(!dir.path().join("b.txt").exists());
+
+        manager.cherry_pick(repo_path, options).unwrap();
+        manager
+            .resolve_conflict(repo_path, "a.txt", GitConflictResolution::Theirs)
+            .unwrap();
+        let state = manager.sequencer_continue(repo_path).unwrap();
+        assert_eq!(state.status, GitSequencerStatus::Completed);
+        let head = repo.head().unwrap();
+        assert_eq!(head.name(), Some(main_branch.as_str()));
+        let top = head.peel_to_commit().unwrap();
+        assert_eq!(top.summary(), Some("add c"));
+        assert!(top.message().unwrap().contains(&format!("(cherry picked from commit {})", topic_tip)));
+        assert_eq!(top.parent(0).unwrap().parent(0).unwrap().parent_id(0).unwrap(), main_tip);
+
+        // Reverting the range newest first restores the original files
+        let options = GitRevertOptions {
+            commits: vec![format!("{}..HEAD", main_tip)],
+        };
+        let state = manager.revert(repo_path, options).unwrap();
+        assert_eq!(state.status, GitSequencerStatus::Completed);
+        let top = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(top.summary(), Some("Revert \"add b\""));
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "main\n");
+        assert!(!dir.path().join("b.txt").exists());
+
+        let reset = GitResetOptions {
+            mode: ResetMode::Hard,
+            target: main_tip.to_string(),
+        };
+        manager.reset(repo_path, reset.clone()).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(main_tip));
+        assert!(!dir.path().join("c.txt").exists());
+
+        // Resetting out of a stopped cherry-pick ends it
+        let options = GitCherryPickOptions {
+            commits: vec![format!("{}..topic", base)],
+            record_origin: None,
+        };
+        assert_eq!(manager.cherry_pick(repo_path, options).unwrap().status, GitSequencerStatus::Conflicts);
+        manager.reset(repo_path, reset).unwrap();
+        assert!(manager.sequencer_state(repo_path).unwrap().is_none());
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+    }
+}
@@ -0,0 +1,437 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+};
+use git2::{Commit, Config, Oid, Repository, Signature, Tree};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+const PGP_SIGNATURE: &str = "-----BEGIN PGP SIGNATURE-----";
+const X509_SIGNATURE: &str = "-----BEGIN SIGNED MESSAGE-----";
+const SSH_SIGNATURE: &str = "-----BEGIN SSH SIGNATURE-----";
+
+/// Split a raw tag object into the signed payload and the signature appended to its message.
+pub(crate) fn split_signature(content: &[u8]) -> Option<(&[u8], &str)> {
+    let text = std::str::from_utf8(content).ok()?;
+    let start = [PGP_SIGNATURE, X509_SIGNATURE, SSH_SIGNATURE]
+        .iter()
+        .filter_map(|marker| {
+            text.match_indices(marker)
+                .map(|(idx, _)| idx)
+                .filter(|&idx| idx == 0 || text.as_bytes()[idx - 1] == b'\n')
+                .last()
+        })
+        .min()?;
+    Some((&content[..start], &text[start..]))
+}
+
+/// Check `signature` over `payload` with gpg, gpgsm or ssh-keygen, as git would.
+pub(crate) fn verify_signature(repo: &Repository, payload: &[u8], signature: &str) -> GitSignature {
+    let format = signature_format(signature);
+    let unverified = GitSignature {
+        format,
+        status: GitSignatureStatus::Unverified,
+        signer: None,
+        key: None,
+    };
+    let Ok(config) = repo.config() else {
+        return unverified;
+    };
+
+    let result = match format {
+        GitSignatureFormat::Ssh => verify_ssh(&config, payload, signature),
+        _ => verify_gpg(&config, format, payload, signature),
+    };
+    result.unwrap_or(unverified)
+}
+
+/// Verify the signature of a commit, or `None` when it is unsigned.
+pub(crate) fn verify_commit(repo: &Repository, oid: Oid) -> Option<GitSignature> {
+    let (signature, payload) = repo.extract_signature(&oid, None).ok()?;
+    let signature = signature.as_str()?;
+    Some(verify_signature(repo, &payload, signature))
+}
+
+/// Whether `key` (e.g. `commit.gpgSign` or `tag.gpgSign`) asks for signing.
+pub(crate) fn signing_requested(config: &Config, key: &str) -> bool {
+    config.get_bool(key).unwrap_or(false)
+}
+
+/// Produce a detached signature over `payload` using `gpg.format` and `user.signingKey`.
+pub(crate) fn sign_payload(repo: &Repository, payload: &str) -> Result<String> {
+    let config = repo.config()?;
+    let format = configured_format(&config)?;
+    let key = config.get_string("user.signingkey").ok();
+
+    match format {
+        GitSignatureFormat::Ssh => {
+            let key = key.ok_or_else(|| Error::Signing("user.signingKey is required for SSH signing".to_string()))?;
+            sign_ssh(&config, &key, payload)
+        }
+        _ => {
+            // gpg picks the key from the committer identity when none is configured
+            let key = match key {
+                Some(key) => key,
+                None => {
+                    let signature = repo.signature()?;
+                    format!("{} <{}>", signature.name().unwrap_or(""), signature.email().unwrap_or(""))
+                }
+            };
+            sign_gpg(&config, format, &key, payload)
+        }
+    }
+}
+
+/// Write a commit object without moving any reference. It is signed when `sign` is set or, when
+/// `sign` is `None`, when `commit.gpgSign` asks for it.
+pub(crate) fn create_commit(
+    repo: &Repository,
+    author: &Signature,
+    committer: &Signature,
+    message: &str,
+    tree: &Tree,
+    parents: &[&Commit],
+    sign: Option<bool>,
+) -> Result<Oid> {
+    let sign = match sign {
+        Some(sign) => sign,
+        None => signing_requested(&repo.config()?, "commit.gpgsign"),
+    };
+    if !sign {
+        return Ok(repo.commit(None, author, committer, message, tree, parents)?);
+    }
+
+    let buffer = repo.commit_create_buffer(author, committer, message, tree, parents)?;
+    let buffer = buffer
+        .as_str()
+        .ok_or_else(|| Error::Signing("commit is not valid UTF-8".to_string()))?;
+    let signature = sign_payload(repo, buffer)?;
+    Ok(repo.commit_signed(buffer, &signature, None)?)
+}
+
+fn configured_format(config: &Config) -> Result<GitSignatureFormat> {
+    match config.get_string("gpg.format").ok().as_deref() {
+        None | Some("openpgp") => Ok(GitSignatureFormat::Openpgp),
+        Some("x509") => Ok(GitSignatureFormat::X509),
+        Some("ssh") => Ok(GitSignatureFormat::Ssh),
+        Some(other) => Err(Error::InvalidConfig(format!("unsupported gpg.format '{}'", other))),
+    }
+}
+
+fn signature_format(signature: &str) -> GitSignatureFormat {
+    if signature.starts_with(SSH_SIGNATURE) {
+        GitSignatureFormat::Ssh
+    } else if signature.starts_with(X509_SIGNATURE) {
+        GitSignatureFormat::X509
+    } else {
+        GitSignatureFormat::Openpgp
+    }
+}
+
+fn program(config: &Config, format: GitSignatureFormat) -> String {
+    let (key, default) = match format {
+        GitSignatureFormat::Openpgp => ("gpg.openpgp.program", "gpg"),
+        GitSignatureFormat::X509 => ("gpg.x509.program", "gpgsm"),
+        GitSignatureFormat::Ssh => ("gpg.ssh.program", "ssh-keygen"),
+    };
+    config
+        .get_string(key)
+        .ok()
+        .or_else(|| {
+            // `gpg.program` is the older spelling for OpenPGP
+            (format == GitSignatureFormat::Openpgp)
+                .then(|| config.get_string("gpg.program").ok())
+                .flatten()
+        })
+        .unwrap_or_else(|| default.to_string())
+}
+
+fn sign_gpg(config: &Config, format: GitSignatureFormat, key: &str, payload: &str) -> Result<String> {
+    let output = run(&program(config, format), &["--status-fd=2", "-bsau", key], Some(payload.as_bytes()))
+        .map_err(|e| Error::Signing(e.to_string()))?;
+
+    let status = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() || !status.contains("[GNUPG:] SIG_CREATED ") {
+        return Err(Error::Signing(status.trim().to_string()));
+    }
+    String::from_utf8(output.stdout).map_err(|e| Error::Signing(e.to_string()))
+}
+
+fn sign_ssh(config: &Config, key: &str, payload: &str) -> Result<String> {
+    let buffer = TempFile::new("ssh-payload", payload.as_bytes())?;
+    let signature_path = PathBuf::from(format!("{}.sig", buffer.path.display()));
+
+    // A literal public key means the private half lives in the SSH agent
+    let literal = key.strip_prefix("key::").or_else(|| key.starts_with("ssh-").then_some(key));
+    let key_file = match literal {
+        Some(public_key) => Some(TempFile::new("ssh-key", public_key.as_bytes())?),
+        None => None,
+    };
+    let key_path = match &key_file {
+        Some(file) => file.path.clone(),
+        None => expand_home(key),
+    };
+
+    let key_arg = key_path.to_string_lossy().into_owned();
+    let buffer_arg = buffer.path.to_string_lossy().into_owned();
+    let mut args = vec!["-Y", "sign", "-n", "git", "-f", key_arg.as_str()];
+    if key_file.is_some() {
+        args.push("-U");
+    }
+    args.push(buffer_arg.as_str());
+
+    let output = run(&program(config, GitSignatureFormat::Ssh), &args, None);
+    let signature = std::fs::read_to_string(&signature_path);
+    let _ = std::fs::remove_file(&signature_path);
+
+    let output = output.map_err(|e| Error::Signing(e.to_string()))?;
+    if !output.status.success() {
+        return Err(Error::Signing(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    signature.map_err(|e| Error::Signing(e.to_string()))
+}
+
+fn verify_gpg(config: &Config, format: GitSignatureFormat, payload: &[u8], signature: &str) -> Option<GitSignature> {
+    let signature_file = TempFile::new("signature", signature.as_bytes()).ok()?;
+    let signature_arg = signature_file.path.to_string_lossy().into_owned();
+    let mut args = vec!["--status-fd=1", "--verify", signature_arg.as_str(), "-"];
+    // gpgsm rejects gpg's key id option
+    if format == GitSignatureFormat::Openpgp {
+        args.insert(0, "--keyid-format=long");
+    }
+    let output = run(&program(config, format), &args, Some(payload)).ok()?;
+
+    let mut result = GitSignature {
+        format,
+        status: GitSignatureStatus::Bad,
+        signer: None,
+        key: None,
+    };
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some(status) = line.strip_prefix("[GNUPG:] ") else {
+            continue;
+        };
+        let mut fields = status.splitn(3, ' ');
+        let keyword = fields.next().unwrap_or("");
+        let key = fields.next().map(String::from);
+        let signer = fields.next().map(String::from);
+
+        let status = match keyword {
+            "GOODSIG" => GitSignatureStatus::Good,
+            "BADSIG" => GitSignatureStatus::Bad,
+            "EXPSIG" | "EXPKEYSIG" => GitSignatureStatus::Expired,
+            "REVKEYSIG" => GitSignatureStatus::Revoked,
+            "ERRSIG" | "NO_PUBKEY" => GitSignatureStatus::UnknownKey,
+            _ => continue,
+        };
+        result.status = status;
+        result.key = key;
+        if status != GitSignatureStatus::UnknownKey {
+            result.signer = signer;
+        }
+        break;
+    }
+
+    // A good signature from a key without trust is only as good as an unknown one
+    if result.status == GitSignatureStatus::Good {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.contains("[GNUPG:] TRUST_UNDEFINED") || stdout.contains("[GNUPG:] TRUST_NEVER") {
+            result.status = GitSignatureStatus::UnknownKey;
+        }
+    }
+    Some(result)
+}
+
+fn verify_ssh(config: &Config, payload: &[u8], signature: &str) -> Option<GitSignature> {
+    let program = program(config, GitSignatureFormat::Ssh);
+    let signature_file = TempFile::new("signature", signature.as_bytes()).ok()?;
+    let signature_arg = signature_file.path.to_string_lossy().into_owned();
+    let mut result = GitSignature {
+        format: GitSignatureFormat::Ssh,
+        status: GitSignatureStatus::Unverified,
+        signer: None,
+        key: None,
+    };
+
+    // Without an allowed signers file there is nobody to check the key against
+    let Ok(allowed_signers) = config.get_path("gpg.ssh.allowedsignersfile") else {
+        return Some(result);
+    };
+    let allowed_arg = allowed_signers.to_string_lossy().into_owned();
+
+    let principals = run(
+        &program,
+        &["-Y", "find-principals", "-f", allowed_arg.as_str(), "-s", signature_arg.as_str()],
+        None,
+    )
+    .ok()?;
+    let principal = String::from_utf8_lossy(&principals.stdout)
+        .lines()
+        .next()
+        .map(str::to_string)
+        .filter(|_| principals.status.success());
+
+    let output = match &principal {
+        Some(principal) => run(
+            &program,
+            &[
+                "-Y",
+                "verify",
+                "-n",
+                "git",
+                "-f",
+                allowed_arg.as_str(),
+                "-I",
+                principal.as_str(),
+                "-s",
+                signature_arg.as_str(),
+            ],
+            Some(payload),
+        ),
+        None => run(
+            &program,
+            &["-Y", "check-novalidate", "-n", "git", "-s", signature_arg.as_str()],
+            Some(payload),
+        ),
+    }
+    .ok()?;
+
+    // ssh-keygen reports e.g. `Good "git" signature for me@example.com with ED25519 key SHA256:...`
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    result.key = stdout
+        .split_once(" key ")
+        .map(|(_, key)| key.trim().to_string())
+        .filter(|key| !key.is_empty());
+    result.status = match (output.status.success(), principal.is_some()) {
+        (true, true) => GitSignatureStatus::Good,
+        (true, false) => GitSignatureStatus::UnknownKey,
+        (false, _) => GitSignatureStatus::Bad,
+    };
+    result.signer = principal;
+    Some(result)
+}
+
+fn run(program: &str, args: &[&str], input: Option<&[u8]>) -> std::io::Result<Output> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input)?;
+    }
+    child.wait_with_output()
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// A scratch file for the signing tools, removed when dropped.
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn new(prefix: &str, contents: &[u8]) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!(".git-{}-{}", prefix, uuid::Uuid::new_v4()));
+        std::fs::write(&path, contents)?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::{
+        repository::GitManager,
+        test_support::{commit_file, init_repo, use_fake_signer},
+    };
+    use std::os::unix::fs::PermissionsExt;
+
+    fn is_signed(repo: &Repository, oid: Oid) -> bool {
+        repo.extract_signature(&oid, None).is_ok()
+    }
+
+    #[test]
+    fn test_verify_x509_without_gpg_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path().join("repo")).unwrap();
+        // Like gpgsm, refuses the options only gpg understands
+        let gpgsm = dir.path().join("fake-gpgsm");
+        std::fs::write(
+            &gpgsm,
+            "#!/bin/sh\ncat >/dev/null\ncase \"$*\" in *keyid-format*) exit 2;; esac\n\
+             echo '[GNUPG:] GOODSIG 0123ABCD CN=Test'\necho '[GNUPG:] TRUST_FULLY'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&gpgsm, std::fs::Permissions::from_mode(0o755)).unwrap();
+        repo.config().unwrap().set_str("gpg.x509.program", gpgsm.to_str().unwrap()).unwrap();
+
+        let signature = format!("{}\nfake\n-----END SIGNED MESSAGE-----\n", X509_SIGNATURE);
+        let result = verify_signature(&repo, b"payload", &signature);
+        assert_eq!(result.format, GitSignatureFormat::X509);
+        assert_eq!(result.status, GitSignatureStatus::Good);
+        assert_eq!(result.signer.as_deref(), Some("CN=Test"));
+    }
+
+    #[test]
+    fn test_rewriting_commands_sign_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(&dir.path().join("repo"));
+        let repo_path = repo.workdir().unwrap().to_str().unwrap().to_string();
+        let manager = GitManager::new();
+
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        let main_branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.branch("feature", &repo.find_commit(base).unwrap(), false).unwrap();
+        let main_tip = commit_file(&repo, "b.txt", "b\n", "add b");
+        let squashed = commit_file(&repo, "b.txt", "b2\n", "edit b");
+        repo.set_head("refs/heads/feature").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        let picked = commit_file(&repo, "c.txt", "c\n", "add c");
+        use_fake_signer(&repo, dir.path());
+
+        let rebase = GitRebaseOptions { upstream: main_branch.clone(), onto: None, branch: None };
+        manager.rebase(&repo_path, rebase).unwrap();
+        let rebased = repo.head().unwrap().target().unwrap();
+        assert_ne!(rebased, picked);
+        assert!(is_signed(&repo, rebased));
+
+        let revert = GitRevertOptions { commits: vec![rebased.to_string()] };
+        manager.revert(&repo_path, revert).unwrap();
+        assert!(is_signed(&repo, repo.head().unwrap().target().unwrap()));
+
+        manager
+            .checkout(&repo_path, GitCheckoutOptions { branch: Some(main_branch.clone()), ..Default::default() })
+            .unwrap();
+        let plan = GitInteractiveRebaseOptions {
+            onto: base.to_string(),
+            todo: [(GitRebaseAction::Pick, main_tip), (GitRebaseAction::Squash, squashed)]
+                .into_iter()
+                .map(|(action, oid)| GitRebaseTodoItem { action, commit: oid.to_string(), message: None })
+                .collect(),
+        };
+        manager.interactive_rebase(&repo_path, plan).unwrap();
+        let squash = repo.head().unwrap().target().unwrap();
+        assert_eq!(repo.find_commit(squash).unwrap().parent_id(0).unwrap(), base);
+        assert!(is_signed(&repo, squash));
+
+        let merge = GitMergeOptions { branch: "feature".to_string(), ..Default::default() };
+        let result = manager.merge(&repo_path, merge).unwrap();
+        assert_eq!(result.status, GitMergeStatus::Merged);
+        assert!(is_signed(&repo, Oid::from_str(&result.commit.unwrap()).unwrap()));
+    }
+}
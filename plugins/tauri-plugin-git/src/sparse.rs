@@ -0,0 +1,215 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+    repository::GitManager,
+};
+use git2::{build::CheckoutBuilder, Repository, Status};
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+/// `GIT_INDEX_ENTRY_SKIP_WORKTREE`: the entry is outside the sparse checkout.
+const SKIP_WORKTREE: u16 = 1 << 14;
+
+impl GitManager {
+    pub fn get_sparse_checkout(&self, repo_path: &str) -> Result<GitSparseCheckout> {
+        let repo = self.get_repo(repo_path)?;
+        Ok(GitSparseCheckout {
+            enabled: sparse_enabled(&repo),
+            paths: read_sparse_paths(&repo)?,
+        })
+    }
+
+    /// Limit the working tree to the files at the top level and everything under `paths`,
+    /// like `git sparse-checkout set --cone`. Files leaving the checkout are removed unless
+    /// they have local changes.
+    pub fn set_sparse_checkout(&self, repo_path: &str, paths: Vec<String>) -> Result<GitSparseCheckout> {
+        let repo = self.get_repo(repo_path)?;
+        let paths = enable_sparse(&repo, &paths)?;
+        apply_sparse(&repo, Some(&paths))?;
+        Ok(GitSparseCheckout { enabled: true, paths })
+    }
+
+    /// Restore the full working tree and turn sparse checkout off.
+    pub fn disable_sparse_checkout(&self, repo_path: &str) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        apply_sparse(&repo, None)?;
+        repo.config()?.set_bool("core.sparseCheckout", false)?;
+        Ok(())
+    }
+}
+
+/// Write the cone patterns for `paths` and turn sparse checkout on, without touching the
+/// working tree. Returns the normalized directories.
+pub(crate) fn enable_sparse(repo: &Repository, paths: &[String]) -> Result<Vec<String>> {
+    let paths = normalize_paths(paths)?;
+    std::fs::create_dir_all(repo.path().join("info"))?;
+    std::fs::write(repo.path().join("info").join("sparse-checkout"), cone_patterns(&paths))?;
+    let mut config = repo.config()?;
+    config.set_bool("core.sparseCheckout", true)?;
+    config.set_bool("core.sparseCheckoutCone", true)?;
+    Ok(paths)
+}
+
+pub(crate) fn sparse_enabled(repo: &Repository) -> bool {
+    repo.config()
+        .and_then(|config| config.get_bool("core.sparseCheckout"))
+        .unwrap_or(false)
+}
+
+/// Index paths hidden by the sparse checkout; their absence is not a deletion.
+pub(crate) fn skip_worktree_paths(repo: &Repository) -> Result<HashSet<String>> {
+    if !sparse_enabled(repo) {
+        return Ok(HashSet::new());
+    }
+    Ok(repo
+        .index()?
+        .iter()
+        .filter(|entry| entry.flags_extended & SKIP_WORKTREE != 0)
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        .collect())
+}
+
+/// Mark index entries outside `paths` as skip-worktree and remove their files, and bring
+/// back the files of entries that are now inside. `None` includes everything.
+pub(crate) fn apply_sparse(repo: &Repository, paths: Option<&[String]>) -> Result<()> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| Error::InvalidState("sparse checkout needs a working tree".to_string()))?
+        .to_path_buf();
+    let mut index = repo.index()?;
+
+    let mut restore = Vec::new();
+    let mut remove = Vec::new();
+    for mut entry in index.iter().collect::<Vec<_>>() {
+        // Conflicted entries are left for conflict resolution
+        if (entry.flags >> 12) & 0x3 != 0 {
+            continue;
+        }
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        let included = paths.map_or(true, |paths| in_cone(paths, &path));
+        let skipped = entry.flags_extended & SKIP_WORKTREE != 0;
+
+        if included && skipped {
+            entry.flags_extended &= !SKIP_WORKTREE;
+            index.add(&entry)?;
+            restore.push(path);
+        } else if !included && !skipped {
+            // Keep files with local changes in the checkout, like git does. A file that is
+            // already gone has nothing to keep.
+            let modified = repo
+                .status_file(Path::new(&path))
+                .map(|status| !(status - Status::WT_DELETED).is_empty())
+                .unwrap_or(false);
+            if modified {
+                continue;
+            }
+            entry.flags_extended |= SKIP_WORKTREE;
+            index.add(&entry)?;
+            remove.push(path);
+        }
+    }
+    index.write()?;
+
+    for path in &remove {
+        let file = workdir.join(path);
+        if std::fs::remove_file(&file).is_ok() {
+            remove_empty_parents(&workdir, &file);
+        }
+    }
+
+    if !restore.is_empty() {
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force();
+        for path in &restore {
+            checkout.path(path.as_str());
+        }
+        repo.checkout_index(Some(&mut index), Some(&mut checkout))?;
+    }
+
+    Ok(())
+}
+
+/// Whether `path` is in a cone-mode checkout of `dirs`: top-level files, files directly in a
+/// parent of a listed directory, and everything under a listed directory.
+pub(crate) fn in_cone(dirs: &[String], path: &str) -> bool {
+    let parent = match path.rfind('/') {
+        Some(idx) => &path[..idx],
+        None => return true,
+    };
+    dirs.iter().any(|dir| {
+        path.starts_with(&format!("{}/", dir)) || dir.starts_with(&format!("{}/", parent))
+    })
+}
+
+fn normalize_paths(paths: &[String]) -> Result<Vec<String>> {
+    let mut normalized = BTreeSet::new();
+    for path in paths {
+        let path = path.trim().trim_matches('/').replace('\\', "/");
+        if path.is_empty() || path.contains('*') || path.split('/').any(|part| part == "..") {
+            return Err(Error::InvalidState(format!("'{}' is not a directory for a cone sparse checkout", path)));
+        }
+        normalized.insert(path);
+    }
+    // A directory inside another listed one adds nothing to the cone
+    Ok(normalized
+        .iter()
+        .filter(|path| !normalized.iter().any(|other| path.starts_with(&format!("{}/", other))))
+        .cloned()
+        .collect())
+}
+
+/// The `info/sparse-checkout` contents git writes for a cone of `dirs`.
+fn cone_patterns(dirs: &[String]) -> String {
+    let mut parents = BTreeSet::new();
+    for dir in dirs {
+        let mut prefix = String::new();
+        let parts: Vec<&str> = dir.split('/').collect();
+        for part in &parts[..parts.len() - 1] {
+            prefix.push('/');
+            prefix.push_str(part);
+            parents.insert(prefix.clone());
+        }
+    }
+
+    let mut patterns = String::from("/*\n!/*/\n");
+    for parent in &parents {
+        patterns.push_str(&format!("{}/\n!{}/*/\n", parent, parent));
+    }
+    for dir in dirs {
+        patterns.push_str(&format!("/{}/\n", dir));
+    }
+    patterns
+}
+
+/// Recover the directories from a cone-mode pattern file: listed directories that are not
+/// only there as parents of others.
+fn read_sparse_paths(repo: &Repository) -> Result<Vec<String>> {
+    let contents = match std::fs::read_to_string(repo.path().join("info").join("sparse-checkout")) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut dirs = BTreeSet::new();
+    let mut parents = HashSet::new();
+    for line in contents.lines().map(str::trim) {
+        if let Some(parent) = line.strip_prefix('!').and_then(|l| l.strip_suffix("/*/")) {
+            parents.insert(parent.trim_start_matches('/').to_string());
+        } else if let Some(dir) = line.strip_prefix('/').and_then(|l| l.strip_suffix('/')) {
+            if !dir.is_empty() && !dir.contains('*') {
+                dirs.insert(dir.to_string());
+            }
+        }
+    }
+    Ok(dirs.into_iter().filter(|dir| !parents.contains(dir)).collect())
+}
+
+fn remove_empty_parents(workdir: &Path, file: &Path) {
+    let mut dir = file.parent();
+    while let Some(current) = dir {
+        if current == workdir || std::fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
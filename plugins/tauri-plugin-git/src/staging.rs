@@ -0,0 +1,322 @@
+use crate::{
+    diff::{diff_workdir_or_index, is_eofnl_marker},
+    error::{Error, Result},
+    models::*,
+    repository::GitManager,
+};
+use git2::{ApplyLocation, Delta, Diff, DiffLineType, Patch, Repository};
+
+/// Which way a selected hunk is applied relative to the diff it was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatchDirection {
+    Forward,
+    Reverse,
+}
+
+struct SelectedLine {
+    origin: DiffLineType,
+    content: Vec<u8>,
+    selected: bool,
+    no_newline: bool,
+}
+
+impl GitManager {
+    /// Stage a single hunk of the working-tree diff, or a range of its lines, like `git add -p`.
+    pub fn stage_hunk(&self, repo_path: &str, selection: GitHunkSelection) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        apply_selection(&repo, &selection, false, PatchDirection::Forward, ApplyLocation::Index)
+    }
+
+    /// Remove a hunk (or some of its lines) of the staged diff from the index.
+    pub fn unstage_hunk(&self, repo_path: &str, selection: GitHunkSelection) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        apply_selection(&repo, &selection, true, PatchDirection::Reverse, ApplyLocation::Index)
+    }
+
+    /// Revert a hunk (or some of its lines) of the working-tree diff back to the index version.
+    pub fn discard_hunk(&self, repo_path: &str, selection: GitHunkSelection) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        apply_selection(&repo, &selection, false, PatchDirection::Reverse, ApplyLocation::WorkDir)
+    }
+}
+
+fn apply_selection(
+    repo: &Repository,
+    selection: &GitHunkSelection,
+    cached: bool,
+    direction: PatchDirection,
+    location: ApplyLocation,
+) -> Result<()> {
+    let patch_text = build_selection_patch(repo, selection, cached, direction)?;
+    let diff = Diff::from_buffer(&patch_text)?;
+    repo.apply(&diff, location, None)?;
+    Ok(())
+}
+
+/// Render the selected part of a file's diff as a standalone patch. Line indices match the
+/// `GitDiffHunk::lines` produced by the diff module, which omits end-of-file markers.
+fn build_selection_patch(
+    repo: &Repository,
+    selection: &GitHunkSelection,
+    cached: bool,
+    direction: PatchDirection,
+) -> Result<Vec<u8>> {
+    let options = GitDiffOptions {
+        paths: Some(vec![selection.file_path.clone()]),
+        include_untracked: Some(!cached),
+        detect_renames: Some(false),
+        ..Default::default()
+    };
+    let diff = diff_workdir_or_index(repo, cached, &options)?;
+    let patch = if diff.deltas().len() > 0 {
+        Patch::from_diff(&diff, 0)?
+    } else {
+        None
+    };
+    let patch = patch.ok_or_else(|| Error::FileNotFound(selection.file_path.clone()))?;
+
+    let delta = patch.delta();
+    if delta.flags().is_binary() {
+        return Err(Error::InvalidSelection(format!(
+            "{} is binary and cannot be partially staged",
+            selection.file_path
+        )));
+    }
+    if selection.hunk_index >= patch.num_hunks() {
+        return Err(Error::InvalidSelection(format!(
+            "hunk {} does not exist in {}",
+            selection.hunk_index, selection.file_path
+        )));
+    }
+
+    let (hunk, line_count) = patch.hunk(selection.hunk_index)?;
+    let mut lines: Vec<SelectedLine> = Vec::with_capacity(line_count);
+    for line_idx in 0..line_count {
+        let line = patch.line_in_hunk(selection.hunk_index, line_idx)?;
+        if is_eofnl_marker(line.origin_value()) {
+            if let Some(last) = lines.last_mut() {
+                last.no_newline = true;
+            }
+            continue;
+        }
+
+        let model_idx = lines.len();
+        let selected = selection
+            .line_range
+            .as_ref()
+            .map_or(true, |range| model_idx >= range.start && model_idx <= range.end);
+
+        lines.push(SelectedLine {
+            origin: line.origin_value(),
+            content: line.content().to_vec(),
+            selected,
+            no_newline: false,
+        });
+    }
+
+    if let Some(range) = &selection.line_range {
+        if range.start > range.end || range.end >= lines.len() {
+            return Err(Error::InvalidSelection(format!(
+                "lines {}..={} are outside hunk {}",
+                range.start, range.end, selection.hunk_index
+            )));
+        }
+    }
+    if !lines.iter().any(|l| l.selected && l.origin != DiffLineType::Context) {
+        return Err(Error::InvalidSelection("selection contains no changes".to_string()));
+    }
+
+    // A line's `no_newline` flag describes the side of the patch it ends up on: the old side for
+    // context and `-` lines, the new side for `+` lines.
+    let mut ops: Vec<(u8, &SelectedLine)> = Vec::with_capacity(lines.len());
+    for line in &lines {
+        let op = match (line.origin, direction, line.selected) {
+            (DiffLineType::Context, _, _) => b' ',
+            (DiffLineType::Addition, PatchDirection::Forward, true) => b'+',
+            (DiffLineType::Deletion, PatchDirection::Forward, true) => b'-',
+            (DiffLineType::Deletion, PatchDirection::Forward, false) => b' ',
+            (DiffLineType::Addition, PatchDirection::Reverse, true) => b'-',
+            (DiffLineType::Addition, PatchDirection::Reverse, false) => b' ',
+            (DiffLineType::Deletion, PatchDirection::Reverse, true) => b'+',
+            // Unselected additions (forward) or deletions (reverse) never reach the target
+            _ => continue,
+        };
+        ops.push((op, line));
+    }
+    // Reversing swaps the sides of each change, so put removals first again
+    let mut run_start = 0;
+    while run_start < ops.len() {
+        let run_end = (run_start..ops.len()).find(|&i| ops[i].0 == b' ').unwrap_or(ops.len());
+        ops[run_start..run_end].sort_by_key(|(op, _)| *op != b'-');
+        run_start = run_end + 1;
+    }
+
+    // Only the last line of each side can lack a newline. A line that no longer ends the target
+    // gets one, and a context line ending just the old side is split into a removal and an addition.
+    let last_old = ops.iter().rposition(|(op, _)| *op != b'+');
+    let last_new = ops.iter().rposition(|(op, _)| *op != b'-');
+    let mut body = Vec::new();
+    let (mut old_count, mut new_count) = (0u32, 0u32);
+    for (idx, (op, line)) in ops.iter().enumerate() {
+        let ends_old = Some(idx) == last_old && line.no_newline;
+        let ends_new = Some(idx) == last_new && line.no_newline;
+        let mut emit = |op: u8, no_newline: bool| {
+            match op {
+                b'+' => new_count += 1,
+                b'-' => old_count += 1,
+                _ => {
+                    old_count += 1;
+                    new_count += 1;
+                }
+            }
+            body.push(op);
+            body.extend_from_slice(&line.content);
+            if !line.content.ends_with(b"\n") {
+                body.push(b'\n');
+            }
+            if no_newline {
+                body.extend_from_slice(b"\\ No newline at end of file\n");
+            }
+        };
+        match *op {
+            b' ' if ends_old && !ends_new => {
+                emit(b'-', true);
+                emit(b'+', false);
+            }
+            b' ' => emit(b' ', ends_old && ends_new),
+            b'-' => emit(b'-', ends_old),
+            _ => emit(b'+', ends_new),
+        }
+    }
+
+    let old_start = match direction {
+        PatchDirection::Forward => hunk.old_start(),
+        PatchDirection::Reverse => hunk.new_start(),
+    };
+    let new_start = if new_count == 0 {
+        old_start.saturating_sub(1)
+    } else if old_count == 0 {
+        old_start + 1
+    } else {
+        old_start
+    };
+
+    let status = delta.status();
+    let is_new_side = matches!(status, Delta::Added | Delta::Untracked);
+    let old_side_missing = match direction {
+        PatchDirection::Forward => is_new_side,
+        PatchDirection::Reverse => status == Delta::Deleted,
+    };
+    let new_side_missing = new_count == 0
+        && match direction {
+            PatchDirection::Forward => status == Delta::Deleted,
+            PatchDirection::Reverse => is_new_side,
+        };
+
+    let path = &selection.file_path;
+    let mut text = format!("diff --git a/{path} b/{path}\n").into_bytes();
+    if old_side_missing {
+        let mode = u32::from(delta.new_file().mode()).max(u32::from(delta.old_file().mode()));
+        text.extend_from_slice(format!("new file mode {mode:o}\n--- /dev/null\n+++ b/{path}\n").as_bytes());
+    } else if new_side_missing {
+        let mode = u32::from(delta.old_file().mode()).max(u32::from(delta.new_file().mode()));
+        text.extend_from_slice(format!("deleted file mode {mode:o}\n--- a/{path}\n+++ /dev/null\n").as_bytes());
+    } else {
+        text.extend_from_slice(format!("--- a/{path}\n+++ b/{path}\n").as_bytes());
+    }
+    text.extend_from_slice(format!("@@ -{old_start},{old_count} +{new_start},{new_count} @@\n").as_bytes());
+    text.extend_from_slice(&body);
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use std::path::Path;
+
+    fn index_content(repo: &Repository, path: &str) -> String {
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        let entry = index.get_path(Path::new(path), 0).unwrap();
+        String::from_utf8(repo.find_blob(entry.id).unwrap().content().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_stage_and_unstage_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(dir.path());
+        let base: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        commit_file(&repo, "file.txt", &base, "init");
+
+        let modified = base
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "line eighteen\nextra\n");
+        std::fs::write(dir.path().join("file.txt"), &modified).unwrap();
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+        let selection = |hunk_index, line_range| GitHunkSelection {
+            file_path: "file.txt".to_string(),
+            hunk_index,
+            line_range,
+        };
+
+        // Whole second hunk
+        manager.stage_hunk(repo_path, selection(1, None)).unwrap();
+        assert_eq!(index_content(&repo, "file.txt"), base.replace("line 18\n", "line eighteen\nextra\n"));
+
+        // Only the "extra" addition back out of the index
+        let staged = manager.diff_file(repo_path, "file.txt", true).unwrap();
+        let extra = staged.hunks[0].lines.iter().position(|l| l.content == "extra").unwrap();
+        manager
+            .unstage_hunk(repo_path, selection(0, Some(GitLineRange { start: extra, end: extra })))
+            .unwrap();
+        assert_eq!(index_content(&repo, "file.txt"), base.replace("line 18\n", "line eighteen\n"));
+
+        // Only the deletion of "line 2", leaving its replacement unstaged
+        let unstaged = manager.diff_file(repo_path, "file.txt", false).unwrap();
+        let deletion = unstaged.hunks[0].lines.iter().position(|l| l.content == "line 2").unwrap();
+        manager
+            .stage_hunk(repo_path, selection(0, Some(GitLineRange { start: deletion, end: deletion })))
+            .unwrap();
+        assert_eq!(
+            index_content(&repo, "file.txt"),
+            base.replace("line 2\n", "").replace("line 18\n", "line eighteen\n")
+        );
+
+        // Discarding the remaining working-tree hunk drops "line two" from disk
+        manager.discard_hunk(repo_path, selection(0, None)).unwrap();
+        let on_disk = std::fs::read_to_string(dir.path().join("file.txt")).unwrap();
+        assert!(!on_disk.contains("line two"));
+        assert!(on_disk.contains("extra"));
+    }
+    #[test]
+    fn test_selection_around_missing_final_newline() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(dir.path());
+        commit_file(&repo, "file.txt", "a\nb", "init");
+        std::fs::write(dir.path().join("file.txt"), "a\nb\nc\nd").unwrap();
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+        let selection = |line_range| GitHunkSelection {
+            file_path: "file.txt".to_string(),
+            hunk_index: 0,
+            line_range,
+        };
+
+        // "b" keeps its old line, which now needs a newline before "c"; "d" stays unstaged
+        let unstaged = manager.diff_file(repo_path, "file.txt", false).unwrap();
+        let c = unstaged.hunks[0].lines.iter().position(|l| l.content == "c").unwrap();
+        manager.stage_hunk(repo_path, selection(Some(GitLineRange { start: c, end: c }))).unwrap();
+        assert_eq!(index_content(&repo, "file.txt"), "a\nb\nc\n");
+
+        // Reversed hunks whose old side lacks the final newline
+        manager.unstage_hunk(repo_path, selection(None)).unwrap();
+        assert_eq!(index_content(&repo, "file.txt"), "a\nb");
+        manager.discard_hunk(repo_path, selection(None)).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("file.txt")).unwrap(), "a\nb");
+    }
+}
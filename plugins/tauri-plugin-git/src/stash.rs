@@ -0,0 +1,222 @@
+use crate::{
+    diff::{build_diff_options, collect_diffs},
+    error::{Error, Result},
+    models::*,
+    repository::GitManager,
+    utils::*,
+};
+use git2::{build::CheckoutBuilder, ErrorCode, Oid, Repository, StashApplyOptions, StashFlags, StashSaveOptions};
+
+impl GitManager {
+    /// Stash local changes, optionally limited to `paths`. Returns the id of the stash commit.
+    pub fn stash_save(&self, repo_path: &str, options: GitStashOptions) -> Result<String> {
+        let mut repo = self.get_repo(repo_path)?;
+        let signature = repo.signature()?;
+
+        let mut flags = StashFlags::DEFAULT;
+        if options.keep_index.unwrap_or(false) {
+            flags |= StashFlags::KEEP_INDEX;
+        }
+        if options.include_untracked.unwrap_or(false) {
+            flags |= StashFlags::INCLUDE_UNTRACKED;
+        }
+
+        let paths = options.paths.unwrap_or_default();
+        let oid = if paths.is_empty() {
+            repo.stash_save2(&signature, options.message.as_deref(), Some(flags))?
+        } else {
+            // libgit2 resets the whole working tree after a path-limited stash, so keep
+            // everything and clean up just the stashed paths below
+            let mut save_opts = StashSaveOptions::new(signature.clone());
+            save_opts.flags(Some(flags | StashFlags::KEEP_ALL));
+            for path in &paths {
+                save_opts.pathspec(path.as_str());
+            }
+            let oid = repo.stash_save_ext(Some(&mut save_opts))?;
+
+            let mut checkout = CheckoutBuilder::new();
+            checkout
+                .force()
+                .remove_untracked(options.include_untracked.unwrap_or(false));
+            for path in &paths {
+                checkout.path(path.as_str());
+            }
+            if options.keep_index.unwrap_or(false) {
+                repo.checkout_index(None, Some(&mut checkout))?;
+            } else {
+                repo.checkout_head(Some(&mut checkout))?;
+            }
+
+            // The extended API cannot take a message, so reword the new stash entry afterwards
+            if let Some(message) = &options.message {
+                let branch = get_current_branch_name(&repo).unwrap_or_else(|_| "(no branch)".to_string());
+                let mut reflog = repo.reflog("refs/stash")?;
+                reflog.remove(0, false)?;
+                reflog.append(oid, &signature, Some(&format!("On {}: {}", branch, message)))?;
+                reflog.write()?;
+            }
+            oid
+        };
+
+        Ok(oid.to_string())
+    }
+
+    pub fn stash_list(&self, repo_path: &str) -> Result<Vec<GitStashEntry>> {
+        let mut repo = self.get_repo(repo_path)?;
+
+        let mut stashes = Vec::new();
+        repo.stash_foreach(|index, message, oid| {
+            stashes.push((index, message.to_string(), *oid));
+            true
+        })?;
+
+        let mut entries = Vec::with_capacity(stashes.len());
+        for (index, message, oid) in stashes {
+            let commit = repo.find_commit(oid)?;
+            entries.push(GitStashEntry {
+                id: oid.to_string(),
+                index,
+                branch: stash_branch(&message).unwrap_or_default(),
+                commit: commit.parent_id(0)?.to_string(),
+                message,
+                timestamp: convert_time_to_datetime(&commit.time()),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Apply a stash to the working tree. Conflicting changes are left in the index and
+    /// reported as `MergeConflict`.
+    pub fn stash_apply(&self, repo_path: &str, stash_id: &str) -> Result<()> {
+        let mut repo = self.get_repo(repo_path)?;
+        let (index, _) = resolve_stash(&mut repo, stash_id)?;
+        apply_stash(&mut repo, index)
+    }
+
+    /// Apply a stash and drop it; the stash is kept when applying it conflicts.
+    pub fn stash_pop(&self, repo_path: &str, stash_id: &str) -> Result<()> {
+        let mut repo = self.get_repo(repo_path)?;
+        let (index, _) = resolve_stash(&mut repo, stash_id)?;
+        apply_stash(&mut repo, index)?;
+        repo.stash_drop(index)?;
+        Ok(())
+    }
+
+    pub fn stash_drop(&self, repo_path: &str, stash_id: &str) -> Result<()> {
+        let mut repo = self.get_repo(repo_path)?;
+        let (index, _) = resolve_stash(&mut repo, stash_id)?;
+        repo.stash_drop(index)?;
+        Ok(())
+    }
+
+    /// Diff a stash against the commit it was made on, including any untracked files it saved.
+    pub fn stash_show(&self, repo_path: &str, stash_id: &str, options: GitDiffOptions) -> Result<Vec<GitDiff>> {
+        let mut repo = self.get_repo(repo_path)?;
+        let (_, oid) = resolve_stash(&mut repo, stash_id)?;
+
+        let stash = repo.find_commit(oid)?;
+        let base_tree = stash.parent(0)?.tree()?;
+        let mut diff_opts = build_diff_options(&options);
+        let mut diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&stash.tree()?), Some(&mut diff_opts))?;
+        let mut diffs = collect_diffs(&repo, &mut diff, &options)?;
+
+        // Untracked files are stored as a third parent holding only those files
+        if stash.parent_count() > 2 {
+            let untracked_tree = stash.parent(2)?.tree()?;
+            let mut diff_opts = build_diff_options(&options);
+            let mut diff = repo.diff_tree_to_tree(None, Some(&untracked_tree), Some(&mut diff_opts))?;
+            diffs.extend(collect_diffs(&repo, &mut diff, &options)?);
+        }
+
+        Ok(diffs)
+    }
+}
+
+/// Accept a stash as `stash@{n}`, a bare index or the full stash commit id.
+fn resolve_stash(repo: &mut Repository, stash_id: &str) -> Result<(usize, Oid)> {
+    let trimmed = stash_id
+        .strip_prefix("stash@{")
+        .and_then(|rest| rest.strip_suffix('}'))
+        .unwrap_or(stash_id);
+
+    let mut stashes = Vec::new();
+    repo.stash_foreach(|index, _, oid| {
+        stashes.push((index, *oid));
+        true
+    })?;
+
+    let found = match trimmed.parse::<usize>() {
+        Ok(index) => stashes.iter().find(|(i, _)| *i == index),
+        Err(_) => stashes.iter().find(|(_, oid)| oid.to_string() == trimmed),
+    };
+
+    found
+        .copied()
+        .ok_or_else(|| Error::CommitNotFound(stash_id.to_string()))
+}
+
+/// Branch name from stash messages such as "WIP on main: abc1234 subject" or "On main: message".
+fn stash_branch(message: &str) -> Option<String> {
+    let rest = message
+        .strip_prefix("WIP on ")
+        .or_else(|| message.strip_prefix("On "))?;
+    rest.split_once(':').map(|(branch, _)| branch.to_string())
+}
+
+fn apply_stash(repo: &mut Repository, index: usize) -> Result<()> {
+    match repo.stash_apply(index, Some(&mut StashApplyOptions::new())) {
+        Ok(()) => {}
+        Err(e) if matches!(e.code(), ErrorCode::Conflict | ErrorCode::MergeConflict) => {
+            return Err(Error::MergeConflict);
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    // libgit2 reports success when the stash applied with conflicts
+    if repo.index()?.has_conflicts() {
+        return Err(Error::MergeConflict);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_files, init_repo};
+
+    #[test]
+    fn test_stash_paths_keeps_other_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(dir.path());
+        commit_files(&repo, &[("a.txt", "a\n"), ("b.txt", "b\n")], "init");
+
+        std::fs::write(dir.path().join("a.txt"), "a changed\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b changed\n").unwrap();
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+        let options = GitStashOptions {
+            message: Some("only a".to_string()),
+            paths: Some(vec!["a.txt".to_string()]),
+            ..Default::default()
+        };
+        let id = manager.stash_save(repo_path, options).unwrap();
+
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "a\n");
+        assert_eq!(std::fs::read_to_string(dir.path().join("b.txt")).unwrap(), "b changed\n");
+
+        let stashes = manager.stash_list(repo_path).unwrap();
+        assert_eq!(stashes.len(), 1);
+        assert_eq!(stashes[0].id, id);
+        assert!(stashes[0].message.ends_with(": only a"));
+
+        let diffs = manager.stash_show(repo_path, "stash@{0}", GitDiffOptions::default()).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].new_path, "a.txt");
+
+        manager.stash_pop(repo_path, &id).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "a changed\n");
+        assert!(manager.stash_list(repo_path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+    remote::remote_callbacks,
+    repository::{GitManager, Operation},
+};
+use git2::{FetchOptions, Repository, Submodule, SubmoduleIgnore, SubmoduleStatus, SubmoduleUpdateOptions};
+
+impl GitManager {
+    pub fn list_submodules(&self, repo_path: &str) -> Result<Vec<GitSubmodule>> {
+        let repo = self.get_repo(repo_path)?;
+
+        let mut submodules = Vec::new();
+        for submodule in repo.submodules()? {
+            let name = submodule.name().unwrap_or("").to_string();
+            let status = repo.submodule_status(&name, SubmoduleIgnore::None)?;
+            submodules.push(GitSubmodule {
+                path: submodule.path().to_string_lossy().to_string(),
+                url: submodule.url().map(String::from),
+                branch: submodule.branch().map(String::from),
+                recorded_commit: recorded_commit(&submodule),
+                checked_out_commit: submodule.workdir_id().map(|oid| oid.to_string()),
+                is_initialized: status.contains(SubmoduleStatus::IN_CONFIG),
+                is_checked_out: !status.contains(SubmoduleStatus::WD_UNINITIALIZED)
+                    && submodule.open().is_ok(),
+                is_dirty: status.intersects(
+                    SubmoduleStatus::WD_INDEX_MODIFIED | SubmoduleStatus::WD_WD_MODIFIED | SubmoduleStatus::WD_UNTRACKED,
+                ),
+                name,
+            });
+        }
+
+        Ok(submodules)
+    }
+
+    /// Register submodules from `.gitmodules` in `.git/config`, like `git submodule init`.
+    pub fn init_submodules(&self, repo_path: &str, paths: Option<Vec<String>>) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        for mut submodule in selected_submodules(&repo, paths.as_deref())? {
+            submodule.init(false)?;
+        }
+        Ok(())
+    }
+
+    /// Copy submodule URLs from `.gitmodules` into the config and the submodules' remotes,
+    /// like `git submodule sync`.
+    pub fn sync_submodules(&self, repo_path: &str, paths: Option<Vec<String>>, recursive: bool) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        sync_recursive(&repo, paths.as_deref(), recursive)
+    }
+
+    /// Fetch and check out the recorded commit of each submodule, like `git submodule update`.
+    pub fn update_submodules<F: Fn(GitTransferProgress)>(
+        &self,
+        repo_path: &str,
+        options: GitSubmoduleUpdateOptions,
+        on_progress: F,
+    ) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let operation = self.begin_operation(options.operation_id.as_deref());
+        update_recursive(&repo, options.paths.as_deref(), &options, &operation, &on_progress)
+    }
+
+    /// Changed submodules of `repo` for its status, with their own status when recursing.
+    pub(crate) fn submodule_statuses(&self, repo: &Repository, options: &GitStatusOptions) -> Result<Vec<GitSubmoduleStatus>> {
+        let mut changed = Vec::new();
+        for submodule in repo.submodules()? {
+            let name = submodule.name().unwrap_or("").to_string();
+            let status = repo.submodule_status(&name, SubmoduleIgnore::None)?;
+
+            let staged = status.intersects(
+                SubmoduleStatus::INDEX_ADDED | SubmoduleStatus::INDEX_DELETED | SubmoduleStatus::INDEX_MODIFIED,
+            );
+            let new_commits = status.contains(SubmoduleStatus::WD_MODIFIED);
+            let modified_content = status.intersects(SubmoduleStatus::WD_INDEX_MODIFIED | SubmoduleStatus::WD_WD_MODIFIED);
+            let untracked_content = status.contains(SubmoduleStatus::WD_UNTRACKED);
+            if !(staged || new_commits || modified_content || untracked_content) {
+                continue;
+            }
+
+            let nested = match (options.recurse_submodules.unwrap_or(false), submodule.open()) {
+                (true, Ok(sub_repo)) => match sub_repo.workdir() {
+                    Some(workdir) => Some(Box::new(self.status_with_options(&workdir.to_string_lossy(), options.clone())?)),
+                    None => None,
+                },
+                _ => None,
+            };
+
+            changed.push(GitSubmoduleStatus {
+                path: submodule.path().to_string_lossy().to_string(),
+                recorded_commit: recorded_commit(&submodule),
+                checked_out_commit: submodule.workdir_id().map(|oid| oid.to_string()),
+                staged,
+                new_commits,
+                modified_content,
+                untracked_content,
+                status: nested,
+                name,
+            });
+        }
+
+        Ok(changed)
+    }
+}
+
+fn recorded_commit(submodule: &Submodule) -> Option<String> {
+    submodule
+        .index_id()
+        .or_else(|| submodule.head_id())
+        .map(|oid| oid.to_string())
+}
+
+/// Submodules whose path is in `paths`, or all of them. Unknown paths are an error.
+fn selected_submodules<'r>(repo: &'r Repository, paths: Option<&[String]>) -> Result<Vec<Submodule<'r>>> {
+    let submodules = repo.submodules()?;
+    let Some(paths) = paths else {
+        return Ok(submodules);
+    };
+
+    for path in paths {
+        let path = path.trim_end_matches('/');
+        if !submodules.iter().any(|submodule| submodule.path().to_string_lossy() == path) {
+            return Err(Error::FileNotFound(format!("no submodule at '{}'", path)));
+        }
+    }
+    Ok(submodules
+        .into_iter()
+        .filter(|submodule| {
+            let path = submodule.path().to_string_lossy();
+            paths.iter().any(|wanted| wanted.trim_end_matches('/') == path)
+        })
+        .collect())
+}
+
+/// Sync the submodules of `repo` at `paths` (default: all), descending into the checked-out
+/// nested ones when `recursive` is set.
+fn sync_recursive(repo: &Repository, paths: Option<&[String]>, recursive: bool) -> Result<()> {
+    for mut submodule in selected_submodules(repo, paths)? {
+        submodule.sync()?;
+        if recursive {
+            if let Ok(sub_repo) = submodule.open() {
+                sync_recursive(&sub_repo, None, recursive)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Update the submodules of `repo` at `paths` (default: all), descending into nested ones when
+/// `options.recursive` is set.
+pub(crate) fn update_recursive(
+    repo: &Repository,
+    paths: Option<&[String]>,
+    options: &GitSubmoduleUpdateOptions,
+    operation: &Operation,
+    on_progress: &dyn Fn(GitTransferProgress),
+) -> Result<()> {
+    let config = repo.config()?;
+    for mut submodule in selected_submodules(repo, paths)? {
+        if operation.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+
+        let mut fetch_opts = FetchOptions::new();
+        fetch_opts.remote_callbacks(remote_callbacks(
+            &config,
+            options.credentials.as_ref(),
+            operation,
+            GitRemoteOperation::Submodule,
+            on_progress,
+        ));
+        let mut update_opts = SubmoduleUpdateOptions::new();
+        update_opts.fetch(fetch_opts);
+        submodule
+            .update(options.init.unwrap_or(false), Some(&mut update_opts))
+            .map_err(|e| operation.map_err(e))?;
+
+        if options.recursive.unwrap_or(false) {
+            let sub_repo = submodule.open()?;
+            update_recursive(&sub_repo, None, options, operation, on_progress)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::commit_all;
+    use std::path::Path;
+
+    #[test]
+    fn test_submodule_status_and_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib_dir = dir.path().join("lib");
+        let lib = Repository::init(&lib_dir).unwrap();
+        std::fs::write(lib_dir.join("lib.txt"), "v1\n").unwrap();
+        commit_all(&lib, "lib v1");
+
+        let app_dir = dir.path().join("app");
+        let app = Repository::init(&app_dir).unwrap();
+        let url = format!("file://{}", lib_dir.display());
+        let mut submodule = app.submodule(&url, Path::new("vendor/lib"), true).unwrap();
+        submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+        commit_all(&app, "add lib");
+
+        let manager = GitManager::new();
+        let app_path = app_dir.to_str().unwrap();
+        let listed = manager.list_submodules(app_path).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].path, "vendor/lib");
+        assert!(listed[0].is_checked_out);
+        assert!(!listed[0].is_dirty);
+        assert_eq!(listed[0].recorded_commit, listed[0].checked_out_commit);
+        assert!(manager.status(app_path).unwrap().submodules.is_empty());
+
+        // Dirty content shows up as a submodule entry, with the inner status on request
+        std::fs::write(app_dir.join("vendor/lib/lib.txt"), "local edit\n").unwrap();
+        let options = GitStatusOptions {
+            recurse_submodules: Some(true),
+        };
+        let status = manager.status_with_options(app_path, options).unwrap();
+        assert!(status.unstaged.is_empty());
+        assert_eq!(status.submodules.len(), 1);
+        assert!(status.submodules[0].modified_content);
+        assert!(!status.submodules[0].new_commits);
+        let inner = status.submodules[0].status.as_ref().unwrap();
+        assert_eq!(inner.unstaged[0].path, "lib.txt");
+
+        // Moving the submodule to another commit is reported, and update restores the recorded one
+        std::fs::write(app_dir.join("vendor/lib/lib.txt"), "v2\n").unwrap();
+        let sub_repo = Repository::open(app_dir.join("vendor/lib")).unwrap();
+        commit_all(&sub_repo, "lib v2");
+        let status = manager.status(app_path).unwrap();
+        assert!(status.submodules[0].new_commits);
+
+        manager
+            .update_submodules(app_path, GitSubmoduleUpdateOptions::default(), |_| {})
+            .unwrap();
+        let listed = manager.list_submodules(app_path).unwrap();
+        assert_eq!(listed[0].recorded_commit, listed[0].checked_out_commit);
+        assert_eq!(std::fs::read_to_string(app_dir.join("vendor/lib/lib.txt")).unwrap(), "v1\n");
+    }
+
+    #[test]
+    fn test_sync_descends_into_every_level() {
+        let dir = tempfile::tempdir().unwrap();
+        // app > lib > mid > leaf, each adding the next one as a submodule
+        let mut child: Option<(String, String)> = None;
+        for name in ["leaf", "mid", "lib", "app"] {
+            let repo = Repository::init(dir.path().join(name)).unwrap();
+            std::fs::write(dir.path().join(name).join("README"), name).unwrap();
+            if let Some((child_name, url)) = &child {
+                let mut submodule = repo.submodule(url, Path::new(child_name), true).unwrap();
+                submodule.clone(None).unwrap();
+                submodule.add_finalize().unwrap();
+            }
+            commit_all(&repo, name);
+            child = Some((name.to_string(), format!("file://{}", dir.path().join(name).display())));
+        }
+
+        let manager = GitManager::new();
+        let app_path = dir.path().join("app");
+        let options = GitSubmoduleUpdateOptions {
+            init: Some(true),
+            recursive: Some(true),
+            ..Default::default()
+        };
+        manager.update_submodules(app_path.to_str().unwrap(), options, |_| {}).unwrap();
+
+        // Point the deepest submodule elsewhere and sync from the top
+        let mid_path = app_path.join("lib/mid");
+        let moved = format!("file://{}", dir.path().join("moved").display());
+        let gitmodules = std::fs::read_to_string(mid_path.join(".gitmodules")).unwrap();
+        let gitmodules = gitmodules.replace(&format!("file://{}", dir.path().join("leaf").display()), &moved);
+        std::fs::write(mid_path.join(".gitmodules"), gitmodules).unwrap();
+        manager.sync_submodules(app_path.to_str().unwrap(), None, true).unwrap();
+
+        let mid = Repository::open(&mid_path).unwrap();
+        assert_eq!(mid.config().unwrap().snapshot().unwrap().get_str("submodule.leaf.url").unwrap(), moved);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+    remote::{push_refspecs, resolve_remote_name},
+    repository::GitManager,
+    signing::{sign_payload, signing_requested, split_signature, verify_signature},
+    utils::*,
+};
+use git2::{ObjectType, Oid, Repository, Signature};
+
+impl GitManager {
+    /// All tags, sorted by name, with their targets, tagger details and signature status.
+    pub fn get_tags(&self, repo_path: &str) -> Result<Vec<GitTag>> {
+        let repo = self.get_repo(repo_path)?;
+
+        let mut tags = Vec::new();
+        for name in repo.tag_names(None)?.iter().flatten() {
+            let reference = repo.find_reference(&format!("refs/tags/{}", name))?;
+            let Some(oid) = reference.target() else {
+                continue;
+            };
+            tags.push(read_tag(&repo, name, oid)?);
+        }
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(tags)
+    }
+
+    /// Tag `options.target` (HEAD by default). A message makes an annotated tag; signing
+    /// follows `options.sign` or `tag.gpgSign`.
+    pub fn create_tag(
+        &self,
+        repo_path: &str,
+        tag_name: &str,
+        message: Option<String>,
+        options: GitCreateTagOptions,
+    ) -> Result<String> {
+        let repo = self.get_repo(repo_path)?;
+        let target = match &options.target {
+            Some(rev) => repo
+                .revparse_single(rev)
+                .map_err(|_| Error::CommitNotFound(rev.clone()))?,
+            None => repo.head()?.peel(ObjectType::Any)?,
+        };
+        let force = options.force.unwrap_or(false);
+        let sign = match options.sign {
+            Some(sign) => sign,
+            None => signing_requested(&repo.config()?, "tag.gpgsign"),
+        };
+
+        let oid = match (message, sign) {
+            (None, false) => repo.tag_lightweight(tag_name, &target, force)?,
+            (message, false) => repo.tag(tag_name, &target, &repo.signature()?, &message.unwrap_or_default(), force)?,
+            (message, true) => {
+                let message = message.unwrap_or_default();
+                let oid = write_signed_tag(&repo, tag_name, target.id(), target.kind(), &message)?;
+                repo.reference(&format!("refs/tags/{}", tag_name), oid, force, &format!("tag: {}", tag_name))?;
+                oid
+            }
+        };
+
+        Ok(oid.to_string())
+    }
+
+    pub fn delete_tag(&self, repo_path: &str, tag_name: &str) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        repo.tag_delete(tag_name)?;
+        Ok(())
+    }
+
+    /// Push tags to a remote, or delete them there when `options.delete` is set.
+    pub fn push_tags<F: Fn(GitTransferProgress)>(
+        &self,
+        repo_path: &str,
+        options: GitTagPushOptions,
+        on_progress: F,
+    ) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        if options.tags.is_empty() {
+            return Ok(());
+        }
+
+        let operation = self.begin_operation(options.operation_id.as_deref());
+        let remote_name = resolve_remote_name(&repo, options.remote.as_deref())?;
+        let force = if options.force.unwrap_or(false) { "+" } else { "" };
+
+        let mut refspecs = Vec::with_capacity(options.tags.len());
+        for tag in &options.tags {
+            let refname = format!("refs/tags/{}", tag.trim_start_matches("refs/tags/"));
+            if options.delete.unwrap_or(false) {
+                refspecs.push(format!(":{}", refname));
+            } else {
+                repo.find_reference(&refname)
+                    .map_err(|_| Error::InvalidState(format!("tag '{}' does not exist", tag)))?;
+                refspecs.push(format!("{}{}:{}", force, refname, refname));
+            }
+        }
+
+        push_refspecs(&repo, &remote_name, &refspecs, options.credentials.as_ref(), &operation, &on_progress)
+    }
+}
+
+fn read_tag(repo: &Repository, name: &str, oid: Oid) -> Result<GitTag> {
+    let Ok(tag) = repo.find_tag(oid) else {
+        // Lightweight tag: the reference points straight at the object
+        let timestamp = repo
+            .find_commit(oid)
+            .ok()
+            .map(|commit| convert_time_to_datetime(&commit.time()));
+        return Ok(GitTag {
+            name: name.to_string(),
+            target: oid.to_string(),
+            annotated: false,
+            tagger: None,
+            message: None,
+            timestamp,
+            signature: None,
+        });
+    };
+
+    let odb = repo.odb()?;
+    let odb_object = odb.read(oid)?;
+    let (message, signature) = match split_signature(odb_object.data()) {
+        Some((payload, signature)) => {
+            // libgit2 leaves the signature in the message, so take the message from the payload
+            let message = String::from_utf8_lossy(payload)
+                .split_once("\n\n")
+                .map(|(_, message)| message.to_string());
+            (message, Some(verify_signature(repo, payload, signature)))
+        }
+        None => (tag.message().map(String::from), None),
+    };
+
+    let tagger = tag.tagger().map(|tagger| convert_author(&tagger)).transpose()?;
+    Ok(GitTag {
+        name: name.to_string(),
+        target: tag.peel()?.id().to_string(),
+        annotated: true,
+        timestamp: tagger.as_ref().map(|tagger| tagger.timestamp),
+        tagger,
+        message,
+        signature,
+    })
+}
+
+/// Write an annotated tag object with a detached signature appended to its message, the way
+/// `git tag -s` does. libgit2 has no signed-tag API.
+fn write_signed_tag(repo: &Repository, name: &str, target: Oid, kind: Option<ObjectType>, message: &str) -> Result<Oid> {
+    let tagger = repo.signature()?;
+    let mut payload = format!(
+        "object {}\ntype {}\ntag {}\ntagger {}\n\n{}",
+        target,
+        kind.unwrap_or(ObjectType::Commit).str(),
+        name,
+        format_signature(&tagger),
+        message
+    );
+    if !payload.ends_with('\n') {
+        payload.push('\n');
+    }
+
+    let signature = sign_payload(repo, &payload)?;
+    payload.push_str(&signature);
+    Ok(repo.odb()?.write(ObjectType::Tag, payload.as_bytes())?)
+}
+
+fn format_signature(signature: &Signature) -> String {
+    let time = signature.when();
+    let offset = time.offset_minutes();
+    format!(
+        "{} <{}> {} {}{:02}{:02}",
+        signature.name().unwrap_or(""),
+        signature.email().unwrap_or(""),
+        time.seconds(),
+        if offset < 0 { '-' } else { '+' },
+        offset.abs() / 60,
+        offset.abs() % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, commit_index, init_repo, signature};
+
+    #[test]
+    fn test_tags_list_annotated_and_lightweight() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(dir.path());
+        let first = commit_file(&repo, "a.txt", "a\n", "first");
+        let second = commit_index(&repo, "second");
+
+        let manager = GitManager::new();
+        let repo_path = dir.path().to_str().unwrap();
+        let options = GitCreateTagOptions {
+            target: Some("HEAD~1".to_string()),
+            ..Default::default()
+        };
+        manager.create_tag(repo_path, "v1.0", Some("Release 1.0\n".to_string()), options).unwrap();
+        manager.create_tag(repo_path, "latest", None, GitCreateTagOptions::default()).unwrap();
+
+        let tags = manager.get_tags(repo_path).unwrap();
+        assert_eq!(tags.len(), 2);
+        let latest = &tags[0];
+        assert_eq!(latest.name, "latest");
+        assert!(!latest.annotated);
+        assert_eq!(latest.target, second.to_string());
+        let release = &tags[1];
+        assert!(release.annotated);
+        assert_eq!(release.target, first.to_string());
+        assert_eq!(release.message.as_deref(), Some("Release 1.0\n"));
+        assert_eq!(release.tagger.as_ref().unwrap().email, "test@example.com");
+        assert!(release.signature.is_none());
+
+        // A tag object with a signature appended reports it, even without a verifier
+        let payload = format!(
+            "object {}\ntype commit\ntag signed\ntagger {}\n\nSigned\n",
+            first,
+            format_signature(&signature())
+        );
+        let signed = format!("{}-----BEGIN SSH SIGNATURE-----\nAAAA\n-----END SSH SIGNATURE-----\n", payload);
+        let oid = repo.odb().unwrap().write(ObjectType::Tag, signed.as_bytes()).unwrap();
+        repo.reference("refs/tags/signed", oid, false, "test").unwrap();
+        let tags = manager.get_tags(repo_path).unwrap();
+        let names: Vec<_> = tags.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(names, ["latest", "signed", "v1.0"]);
+        let signed = &tags[1];
+        assert_eq!(signed.message.as_deref(), Some("Signed\n"));
+        let signature = signed.signature.as_ref().unwrap();
+        assert_eq!(signature.format, GitSignatureFormat::Ssh);
+        assert_ne!(signature.status, GitSignatureStatus::Good);
+
+        manager.delete_tag(repo_path, "latest").unwrap();
+        assert!(manager.get_tags(repo_path).unwrap().iter().all(|tag| tag.name != "latest"));
+    }
+}
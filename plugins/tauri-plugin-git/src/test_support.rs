@@ -0,0 +1,78 @@
+//! Repository fixtures shared by the unit tests.
+
+use git2::{IndexAddOption, Oid, Repository, Signature};
+use std::path::Path;
+
+/// The identity used for test commits, also configured by [`init_repo`].
+pub(crate) fn signature() -> Signature<'static> {
+    Signature::now("Test", "test@example.com").unwrap()
+}
+
+/// A new repository with `user.name` and `user.email` set, as the manager needs them to commit.
+pub(crate) fn init_repo(path: &Path) -> Repository {
+    let repo = Repository::init(path).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+    repo
+}
+
+/// Write `files` to the working tree and add them to the index.
+pub(crate) fn stage_files(repo: &Repository, files: &[(&str, &str)]) {
+    let mut index = repo.index().unwrap();
+    for (name, content) in files {
+        std::fs::write(repo.workdir().unwrap().join(name), content).unwrap();
+        index.add_path(Path::new(name)).unwrap();
+    }
+    index.write().unwrap();
+}
+
+/// Commit the index on top of HEAD, if there is one.
+pub(crate) fn commit_index(repo: &Repository, message: &str) -> Oid {
+    commit_index_as(repo, &signature(), message)
+}
+
+pub(crate) fn commit_index_as(repo: &Repository, author: &Signature, message: &str) -> Oid {
+    let mut index = repo.index().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<_> = parent.iter().collect();
+    repo.commit(Some("HEAD"), author, author, message, &tree, &parents).unwrap()
+}
+
+pub(crate) fn commit_files(repo: &Repository, files: &[(&str, &str)], message: &str) -> Oid {
+    stage_files(repo, files);
+    commit_index(repo, message)
+}
+
+pub(crate) fn commit_file(repo: &Repository, name: &str, content: &str, message: &str) -> Oid {
+    commit_files(repo, &[(name, content)], message)
+}
+
+/// Stage every change in the working tree, deletions included, and commit it.
+pub(crate) fn commit_all(repo: &Repository, message: &str) -> Oid {
+    let mut index = repo.index().unwrap();
+    index.add_all(["*"], IndexAddOption::DEFAULT, None).unwrap();
+    index.update_all(["*"], None).unwrap();
+    index.write().unwrap();
+    commit_index(repo, message)
+}
+
+/// Have commits signed by a stand-in for gpg, whose signatures all read "fake".
+#[cfg(unix)]
+pub(crate) fn use_fake_signer(repo: &Repository, dir: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let signer = dir.join("fake-gpg");
+    std::fs::write(
+        &signer,
+        "#!/bin/sh\ncat >/dev/null\necho '[GNUPG:] SIG_CREATED D' >&2\n\
+         printf -- '-----BEGIN PGP SIGNATURE-----\\nfake\\n-----END PGP SIGNATURE-----\\n'\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&signer, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut config = repo.config().unwrap();
+    config.set_bool("commit.gpgsign", true).unwrap();
+    config.set_str("gpg.program", signer.to_str().unwrap()).unwrap();
+}
@@ -85,6 +85,7 @@ pub fn convert_commit(commit: &git2::Commit) -> Result<GitCommit> {
         timestamp: convert_time_to_datetime(&commit.time()),
         parents: commit.parent_ids().map(|oid| oid.to_string()).collect(),
         files: None,
+        signature: None,
     })
 }
 
@@ -145,4 +146,42 @@ pub fn find_repository(path: &str) -> Result<Repository> {
     Repository::discover(path)
         .or_else(|_| Repository::open(path))
         .map_err(|e| e.into())
-}
\ No newline at end of file
+}
+/// Name of the local branch HEAD points at, including an unborn branch. Fails when HEAD is detached.
+pub fn get_head_branch(repo: &Repository) -> Result<String> {
+    let head = repo.find_reference("HEAD")?;
+    head.symbolic_target()
+        .and_then(|target| target.strip_prefix("refs/heads/"))
+        .map(String::from)
+        .ok_or_else(|| crate::error::Error::BranchNotFound("HEAD is detached".to_string()))
+}
+
+pub fn convert_repository_state(state: git2::RepositoryState) -> GitRepositoryState {
+    match state {
+        git2::RepositoryState::Clean => GitRepositoryState::Clean,
+        git2::RepositoryState::Merge => GitRepositoryState::Merge,
+        git2::RepositoryState::Revert => GitRepositoryState::Revert,
+        git2::RepositoryState::RevertSequence => GitRepositoryState::RevertSequence,
+        git2::RepositoryState::CherryPick => GitRepositoryState::CherryPick,
+        git2::RepositoryState::CherryPickSequence => GitRepositoryState::CherryPickSequence,
+        git2::RepositoryState::Bisect => GitRepositoryState::Bisect,
+        git2::RepositoryState::Rebase => GitRepositoryState::Rebase,
+        git2::RepositoryState::RebaseInteractive => GitRepositoryState::RebaseInteractive,
+        git2::RepositoryState::RebaseMerge => GitRepositoryState::RebaseMerge,
+        git2::RepositoryState::ApplyMailbox => GitRepositoryState::ApplyMailbox,
+        git2::RepositoryState::ApplyMailboxOrRebase => GitRepositoryState::ApplyMailboxOrRebase,
+    }
+}
+
+/// Resolve a branch name, remote branch, tag or any revision to an annotated commit.
+pub fn resolve_annotated_commit<'r>(repo: &'r Repository, name: &str) -> Result<git2::AnnotatedCommit<'r>> {
+    if let Ok(reference) = repo.resolve_reference_from_short_name(name) {
+        return Ok(repo.reference_to_annotated_commit(&reference)?);
+    }
+
+    let commit = repo
+        .revparse_single(name)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| crate::error::Error::CommitNotFound(name.to_string()))?;
+    Ok(repo.find_annotated_commit(commit.id())?)
+}
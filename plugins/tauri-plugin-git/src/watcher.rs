@@ -0,0 +1,458 @@
+use crate::{error::Result, models::*, repository::GitManager, worktree::common_dir};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+const DEFAULT_DEBOUNCE_MS: u64 = 300;
+
+/// A running repository watcher. Dropping it stops the file system watch, which closes the
+/// event channel and ends the debounce thread.
+pub(crate) struct RepoWatcher {
+    _watcher: Arc<Mutex<RecommendedWatcher>>,
+}
+
+/// Where the git directories of a repository live, to classify file system events.
+struct RepoLayout {
+    workdir: Option<PathBuf>,
+    /// Per-worktree git dir holding HEAD and the index
+    git_dir: PathBuf,
+    /// Shared git dir holding refs; the same as `git_dir` outside linked worktrees
+    common_dir: PathBuf,
+}
+
+/// What a watcher follows. Each working tree directory gets its own non-recursive watch and
+/// ignored ones are left out, so trees such as `node_modules` or `target` do not use up the
+/// system's watch limit.
+struct WatchedRepo {
+    layout: RepoLayout,
+    /// Held weakly by the debounce thread so dropping the `RepoWatcher` still ends the watch
+    watcher: Weak<Mutex<RecommendedWatcher>>,
+    dirs: HashSet<PathBuf>,
+}
+
+/// What a batch of file system events touched.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ChangeSet {
+    paths: BTreeSet<String>,
+    index: bool,
+    head: bool,
+    refs: bool,
+}
+
+impl ChangeSet {
+    fn is_empty(&self) -> bool {
+        self.paths.is_empty() && !self.index && !self.head && !self.refs
+    }
+}
+
+impl GitManager {
+    /// Watch a repository and call `on_change` with a fresh status whenever the index, HEAD,
+    /// refs or non-ignored working tree files change. Bursts of events are coalesced and
+    /// nothing is reported when the status did not actually change. Returns the watch id.
+    pub fn watch_repository<F>(&self, repo_path: &str, options: GitWatchOptions, on_change: F) -> Result<String>
+    where
+        F: Fn(GitStatusChange) + Send + 'static,
+    {
+        let repo = self.get_repo(repo_path)?;
+        let layout = RepoLayout::new(&repo);
+        let watch_id = uuid::Uuid::new_v4().to_string();
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let _ = tx.send(event);
+        })?;
+        // Only the files that classify() looks at; objects and logs change on every operation
+        for dir in [&layout.git_dir, &layout.common_dir] {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            let refs = dir.join("refs");
+            if refs.is_dir() {
+                watcher.watch(&refs, RecursiveMode::Recursive)?;
+            }
+        }
+        let mut watched = WatchedRepo {
+            layout,
+            watcher: Weak::new(),
+            dirs: HashSet::new(),
+        };
+        if let Some(workdir) = watched.layout.workdir.clone() {
+            watcher.watch(&workdir, RecursiveMode::NonRecursive)?;
+            watched.dirs.insert(workdir.clone());
+            watched.watch_tree(&mut watcher, &repo, &workdir);
+        }
+        let watcher = Arc::new(Mutex::new(watcher));
+        watched.watcher = Arc::downgrade(&watcher);
+
+        let initial = self.status_with_options(repo_path, status_options(&options))?;
+        let repo_path = repo_path.to_string();
+        let id = watch_id.clone();
+        std::thread::spawn(move || run_watch_loop(&id, &repo_path, watched, &options, initial, rx, &on_change));
+
+        if let Ok(mut watchers) = self.watchers.lock() {
+            watchers.insert(watch_id.clone(), RepoWatcher { _watcher: watcher });
+        }
+        Ok(watch_id)
+    }
+
+    /// Stop a watcher. Returns false if no watcher has this id.
+    pub fn unwatch_repository(&self, watch_id: &str) -> bool {
+        self.watchers
+            .lock()
+            .ok()
+            .and_then(|mut watchers| watchers.remove(watch_id))
+            .is_some()
+    }
+}
+
+impl RepoLayout {
+    fn new(repo: &git2::Repository) -> Self {
+        Self {
+            workdir: repo.workdir().map(canonical),
+            git_dir: canonical(repo.path()),
+            common_dir: canonical(&common_dir(repo)),
+        }
+    }
+
+    /// Record `path` in `changes` if it can affect the status.
+    fn classify(&self, repo: &git2::Repository, path: &Path, changes: &mut ChangeSet) {
+        let path = canonical(path);
+
+        for dir in [&self.git_dir, &self.common_dir] {
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let name = relative.to_string_lossy();
+            // Lock files and objects come and go during every operation; the renames that
+            // finish them are what matter
+            if name.ends_with(".lock") {
+                return;
+            }
+            if name == "index" {
+                changes.index = true;
+            } else if name == "HEAD" || name.ends_with("_HEAD") {
+                changes.head = true;
+            } else if name == "packed-refs" || relative.starts_with("refs") {
+                changes.refs = true;
+            }
+            return;
+        }
+
+        let Some(workdir) = &self.workdir else {
+            return;
+        };
+        let Ok(relative) = path.strip_prefix(workdir) else {
+            return;
+        };
+        if relative.as_os_str().is_empty() || relative.starts_with(".git") {
+            return;
+        }
+        if repo.status_should_ignore(relative).unwrap_or(false) {
+            return;
+        }
+        changes.paths.insert(relative.to_string_lossy().replace('\\', "/"));
+    }
+}
+
+impl WatchedRepo {
+    /// Watch `dir` and the directories below it, skipping `.git`, ignored directories and
+    /// symlinks. Directories already watched are walked again, as ignore rules may have changed.
+    fn watch_tree(&mut self, watcher: &mut RecommendedWatcher, repo: &git2::Repository, dir: &Path) {
+        let Some(relative) = self.layout.workdir.as_ref().and_then(|workdir| dir.strip_prefix(workdir).ok()) else {
+            return;
+        };
+        if relative.starts_with(".git")
+            || (!relative.as_os_str().is_empty() && repo.status_should_ignore(relative).unwrap_or(false))
+        {
+            return;
+        }
+        if !self.dirs.contains(dir) {
+            if watcher.watch(dir, RecursiveMode::NonRecursive).is_err() {
+                return;
+            }
+            self.dirs.insert(dir.to_path_buf());
+        }
+
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                self.watch_tree(watcher, repo, &entry.path());
+            }
+        }
+    }
+
+    /// Keep the watches in step with directories appearing and disappearing at `path`.
+    fn track(&mut self, repo: &git2::Repository, path: &Path) {
+        if !path.exists() {
+            self.dirs.retain(|dir| !dir.starts_with(path));
+        } else if path.is_dir() && !self.dirs.contains(path) {
+            self.rewatch(repo, path);
+        }
+    }
+
+    fn rewatch(&mut self, repo: &git2::Repository, dir: &Path) {
+        let Some(watcher) = self.watcher.upgrade() else {
+            return;
+        };
+        let Ok(mut guard) = watcher.lock() else {
+            return;
+        };
+        self.watch_tree(&mut guard, repo, dir);
+    }
+}
+
+fn run_watch_loop(
+    watch_id: &str,
+    repo_path: &str,
+    mut watched: WatchedRepo,
+    options: &GitWatchOptions,
+    initial: GitStatus,
+    rx: Receiver<notify::Result<Event>>,
+    on_change: &dyn Fn(GitStatusChange),
+) {
+    let debounce = Duration::from_millis(options.debounce_ms.unwrap_or(DEFAULT_DEBOUNCE_MS));
+    let manager = GitManager::new();
+    let mut last = initial;
+
+    while let Ok(first) = rx.recv() {
+        // Reopen every round so config and ignore rule changes are picked up
+        let Ok(repo) = manager.get_repo(repo_path) else {
+            continue;
+        };
+
+        let mut changes = ChangeSet::default();
+        collect(&mut watched, &repo, first, &mut changes);
+        loop {
+            match rx.recv_timeout(debounce) {
+                Ok(event) => collect(&mut watched, &repo, event, &mut changes),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        if changes.is_empty() {
+            continue;
+        }
+        // Directories that were ignored until now may need watching
+        if changes.paths.iter().any(|path| path == ".gitignore" || path.ends_with("/.gitignore")) {
+            if let Some(workdir) = watched.layout.workdir.clone() {
+                watched.rewatch(&repo, &workdir);
+            }
+        }
+
+        let Ok(status) = manager.status_with_options(repo_path, status_options(options)) else {
+            continue;
+        };
+        if status == last {
+            continue;
+        }
+        let delta = status_delta(&last, &status);
+        last = status.clone();
+
+        on_change(GitStatusChange {
+            watch_id: watch_id.to_string(),
+            repo_path: repo_path.to_string(),
+            status,
+            delta,
+            changed_paths: changes.paths.into_iter().collect(),
+            index_changed: changes.index,
+            head_changed: changes.head,
+            refs_changed: changes.refs,
+        });
+    }
+}
+
+fn collect(watched: &mut WatchedRepo, repo: &git2::Repository, event: notify::Result<Event>, changes: &mut ChangeSet) {
+    let Ok(event) = event else {
+        return;
+    };
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+    for path in &event.paths {
+        watched.track(repo, &canonical(path));
+        watched.layout.classify(repo, path, changes);
+    }
+}
+
+fn status_delta(old: &GitStatus, new: &GitStatus) -> GitStatusDelta {
+    GitStatusDelta {
+        staged: list_delta(&old.staged, &new.staged, |change| &change.path),
+        unstaged: list_delta(&old.unstaged, &new.unstaged, |change| &change.path),
+        untracked: list_delta(&old.untracked, &new.untracked, |path| path),
+        conflicted: list_delta(&old.conflicted, &new.conflicted, |path| path),
+        submodules: list_delta(&old.submodules, &new.submodules, |submodule| &submodule.path),
+        lfs: list_delta(&old.lfs, &new.lfs, |file| &file.path),
+    }
+}
+
+fn list_delta<T: Clone + PartialEq>(old: &[T], new: &[T], path: fn(&T) -> &str) -> GitListDelta<T> {
+    let previous: HashMap<&str, &T> = old.iter().map(|entry| (path(entry), entry)).collect();
+    let current: HashSet<&str> = new.iter().map(path).collect();
+
+    let mut delta = GitListDelta {
+        added: Vec::new(),
+        removed: old.iter().filter(|entry| !current.contains(path(entry))).cloned().collect(),
+        changed: Vec::new(),
+    };
+    for entry in new {
+        match previous.get(path(entry)) {
+            None => delta.added.push(entry.clone()),
+            Some(before) if *before != entry => delta.changed.push(entry.clone()),
+            Some(_) => {}
+        }
+    }
+    delta
+}
+
+fn status_options(options: &GitWatchOptions) -> GitStatusOptions {
+    GitStatusOptions {
+        recurse_submodules: options.recurse_submodules,
+    }
+}
+
+/// Resolve symlinks (e.g. /tmp on macOS) so event paths compare equal to repository paths.
+/// Deleted files cannot be resolved, so fall back to resolving their parent.
+fn canonical(path: &Path) -> PathBuf {
+    if let Ok(resolved) = path.canonicalize() {
+        return resolved;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => canonical(parent).join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, ModifyKind};
+
+    #[test]
+    fn test_watch_loop_debounces_and_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+
+        let watched = WatchedRepo {
+            layout: RepoLayout::new(&repo),
+            watcher: Weak::new(),
+            dirs: HashSet::new(),
+        };
+        let repo_path = dir.path().to_str().unwrap().to_string();
+        let initial = GitManager::new().status(&repo_path).unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let (out_tx, out_rx) = mpsc::channel();
+        let options = GitWatchOptions {
+            debounce_ms: Some(50),
+            ..Default::default()
+        };
+        let handle = std::thread::spawn(move || {
+            run_watch_loop("w", &repo_path, watched, &options, initial, rx, &move |change| {
+                out_tx.send(change).unwrap();
+            })
+        });
+
+        let send = |kind: EventKind, path: PathBuf| tx.send(Ok(Event::new(kind).add_path(path))).unwrap();
+
+        // Ignored files and lock files alone do not produce an update
+        std::fs::write(dir.path().join("target/out.bin"), "x").unwrap();
+        send(EventKind::Create(CreateKind::File), dir.path().join("target/out.bin"));
+        send(EventKind::Create(CreateKind::File), dir.path().join(".git/index.lock"));
+        assert!(out_rx.recv_timeout(Duration::from_millis(300)).is_err());
+
+        // A burst of working tree changes becomes a single update
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        send(EventKind::Create(CreateKind::File), dir.path().join("a.txt"));
+        send(EventKind::Modify(ModifyKind::Any), dir.path().join("b.txt"));
+        send(EventKind::Modify(ModifyKind::Any), dir.path().join("a.txt"));
+        let change = out_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(change.changed_paths, vec!["a.txt".to_string(), "b.txt".to_string()]);
+        assert!(change.status.untracked.contains(&"a.txt".to_string()));
+        assert_eq!(change.delta.untracked.added, vec!["a.txt".to_string(), "b.txt".to_string()]);
+        assert!(change.delta.untracked.removed.is_empty());
+        assert!(out_rx.recv_timeout(Duration::from_millis(300)).is_err());
+
+        // Index updates are flagged
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        send(EventKind::Modify(ModifyKind::Any), dir.path().join(".git/index"));
+        let change = out_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(change.index_changed);
+        assert_eq!(change.status.staged.len(), 1);
+        assert_eq!(change.delta.staged.added[0].path, "a.txt");
+        assert_eq!(change.delta.untracked.removed, vec!["a.txt".to_string()]);
+        assert!(change.delta.unstaged.added.is_empty());
+
+        drop(tx);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_list_delta_matches_entries_by_path() {
+        let change = |path: &str, status| GitFileChange {
+            path: path.to_string(),
+            status,
+            old_path: None,
+        };
+        let old = vec![change("a", FileStatus::Modified), change("b", FileStatus::Modified)];
+        let new = vec![change("b", FileStatus::Deleted), change("c", FileStatus::Added)];
+
+        let delta = list_delta(&old, &new, |change| &change.path);
+        assert_eq!(delta.added, vec![change("c", FileStatus::Added)]);
+        assert_eq!(delta.removed, vec![change("a", FileStatus::Modified)]);
+        assert_eq!(delta.changed, vec![change("b", FileStatus::Deleted)]);
+        assert!(list_delta(&new, &new, |change| &change.path).changed.is_empty());
+    }
+
+    #[test]
+    fn test_watch_tree_skips_ignored_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        for path in ["src/nested", "target/debug"] {
+            std::fs::create_dir_all(dir.path().join(path)).unwrap();
+        }
+
+        let watcher = Arc::new(Mutex::new(notify::recommended_watcher(|_: notify::Result<Event>| {}).unwrap()));
+        let mut watched = WatchedRepo {
+            layout: RepoLayout::new(&repo),
+            watcher: Arc::downgrade(&watcher),
+            dirs: HashSet::new(),
+        };
+        let workdir = watched.layout.workdir.clone().unwrap();
+        let relative = |watched: &WatchedRepo| {
+            let mut dirs: Vec<String> = watched
+                .dirs
+                .iter()
+                .map(|dir| dir.strip_prefix(&workdir).unwrap().to_string_lossy().replace('\\', "/"))
+                .collect();
+            dirs.sort();
+            dirs
+        };
+
+        watched.rewatch(&repo, &workdir);
+        assert_eq!(relative(&watched), vec!["", "src", "src/nested"]);
+
+        // New directories are picked up, removed ones forgotten
+        std::fs::create_dir_all(workdir.join("docs/api")).unwrap();
+        watched.track(&repo, &workdir.join("docs"));
+        std::fs::remove_dir_all(workdir.join("src")).unwrap();
+        watched.track(&repo, &workdir.join("src"));
+        assert_eq!(relative(&watched), vec!["", "docs", "docs/api"]);
+
+        // Ignored directories are only watched once they stop being ignored
+        watched.track(&repo, &workdir.join("target"));
+        assert!(!relative(&watched).contains(&"target".to_string()));
+        std::fs::write(workdir.join(".gitignore"), "").unwrap();
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        watched.rewatch(&repo, &workdir);
+        assert!(relative(&watched).contains(&"target/debug".to_string()));
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    error::{Error, Result},
+    models::*,
+    repository::GitManager,
+    utils::*,
+};
+use git2::{BranchType, Repository, Worktree, WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions};
+use std::path::{Path, PathBuf};
+
+impl GitManager {
+    /// The main working tree followed by every linked worktree.
+    pub fn list_worktrees(&self, repo_path: &str) -> Result<Vec<GitWorktree>> {
+        let repo = main_repository(&self.get_repo(repo_path)?)?;
+
+        let mut worktrees = Vec::new();
+        if let Some(workdir) = repo.workdir() {
+            worktrees.push(GitWorktree {
+                name: None,
+                path: workdir.to_string_lossy().to_string(),
+                is_main: true,
+                branch: get_head_branch(&repo).ok(),
+                head: repo.head().ok().and_then(|head| head.target()).map(|oid| oid.to_string()),
+                is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
+            });
+        }
+
+        for name in repo.worktrees()?.iter().flatten() {
+            let worktree = repo.find_worktree(name)?;
+            worktrees.push(convert_worktree(&worktree)?);
+        }
+
+        Ok(worktrees)
+    }
+
+    /// Create a linked worktree at `options.path`, checking out an existing branch or a new one.
+    pub fn add_worktree(&self, repo_path: &str, options: GitWorktreeAddOptions) -> Result<GitWorktree> {
+        let repo = self.get_repo(repo_path)?;
+        let path = Path::new(&options.path);
+        let name = match &options.name {
+            Some(name) => name.clone(),
+            None => path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .ok_or_else(|| Error::InvalidState(format!("cannot name a worktree at '{}'", options.path)))?,
+        };
+
+        let reference = match &options.branch {
+            Some(branch) if options.create_branch.unwrap_or(false) => {
+                let start = match &options.start_point {
+                    Some(rev) => repo
+                        .revparse_single(rev)
+                        .and_then(|obj| obj.peel_to_commit())
+                        .map_err(|_| Error::CommitNotFound(rev.clone()))?,
+                    None => repo.head()?.peel_to_commit()?,
+                };
+                Some(repo.branch(branch, &start, false)?.into_reference())
+            }
+            Some(branch) => Some(
+                repo.find_branch(branch, BranchType::Local)
+                    .map_err(|_| Error::BranchNotFound(branch.clone()))?
+                    .into_reference(),
+            ),
+            None => None,
+        };
+
+        let mut add_opts = WorktreeAddOptions::new();
+        add_opts.lock(options.lock.unwrap_or(false));
+        add_opts.reference(reference.as_ref());
+        let worktree = repo.worktree(&name, path, Some(&add_opts))?;
+
+        convert_worktree(&worktree)
+    }
+
+    pub fn lock_worktree(&self, repo_path: &str, worktree: &str, reason: Option<&str>) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        find_worktree(&repo, worktree)?.lock(reason)?;
+        Ok(())
+    }
+
+    pub fn unlock_worktree(&self, repo_path: &str, worktree: &str) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        find_worktree(&repo, worktree)?.unlock()?;
+        Ok(())
+    }
+
+    /// Remove the records of worktrees whose directories are gone. Locked worktrees are kept.
+    /// Returns the names that were pruned.
+    pub fn prune_worktrees(&self, repo_path: &str) -> Result<Vec<String>> {
+        let repo = self.get_repo(repo_path)?;
+
+        let mut pruned = Vec::new();
+        for name in repo.worktrees()?.iter().flatten() {
+            let worktree = repo.find_worktree(name)?;
+            if worktree.is_prunable(None)? {
+                worktree.prune(None)?;
+                pruned.push(name.to_string());
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    /// Delete a linked worktree and its directory. Without `force`, worktrees that are locked
+    /// or have uncommitted changes are refused.
+    pub fn remove_worktree(&self, repo_path: &str, worktree: &str, force: bool) -> Result<()> {
+        let repo = self.get_repo(repo_path)?;
+        let worktree = find_worktree(&repo, worktree)?;
+
+        if !force {
+            if let WorktreeLockStatus::Locked(reason) = worktree.is_locked()? {
+                let name = worktree.name().unwrap_or("");
+                return Err(Error::InvalidState(match reason {
+                    Some(reason) => format!("worktree '{}' is locked: {}", name, reason),
+                    None => format!("worktree '{}' is locked", name),
+                }));
+            }
+            if worktree.validate().is_ok() && !is_repo_clean(&Repository::open_from_worktree(&worktree)?)? {
+                return Err(Error::UncommittedChanges);
+            }
+        }
+
+        let mut prune_opts = WorktreePruneOptions::new();
+        prune_opts.valid(true).locked(force).working_tree(true);
+        worktree.prune(Some(&mut prune_opts))?;
+        Ok(())
+    }
+}
+
+/// The repository owning the main working tree, also when `repo` is a linked worktree.
+fn main_repository(repo: &Repository) -> Result<Repository> {
+    Ok(Repository::open(common_dir(repo))?)
+}
+
+/// The git dir shared by all worktrees of `repo`, holding refs and objects. It is `repo`'s own
+/// git dir unless `repo` is a linked worktree.
+pub(crate) fn common_dir(repo: &Repository) -> PathBuf {
+    // A linked worktree's git dir names the shared one in its `commondir` file
+    match std::fs::read_to_string(repo.path().join("commondir")) {
+        Ok(common) => repo.path().join(common.trim()),
+        Err(_) => repo.path().to_path_buf(),
+    }
+}
+
+/// Look a worktree up by name, falling back to its path.
+fn find_worktree(repo: &Repository, worktree: &str) -> Result<Worktree> {
+    if let Ok(found) = repo.find_worktree(worktree) {
+        return Ok(found);
+    }
+
+    let wanted = Path::new(worktree).canonicalize().ok();
+    for name in repo.worktrees()?.iter().flatten() {
+        let candidate = repo.find_worktree(name)?;
+        if wanted.is_some() && candidate.path().canonicalize().ok() == wanted {
+            return Ok(candidate);
+        }
+    }
+    Err(Error::WorktreeNotFound(worktree.to_string()))
+}
+
+fn convert_worktree(worktree: &Worktree) -> Result<GitWorktree> {
+    let (is_locked, lock_reason) = match worktree.is_locked()? {
+        WorktreeLockStatus::Locked(reason) => (true, reason),
+        WorktreeLockStatus::Unlocked => (false, None),
+    };
+    let repo = Repository::open_from_worktree(worktree).ok();
+
+    Ok(GitWorktree {
+        name: worktree.name().map(String::from),
+        path: worktree.path().to_string_lossy().to_string(),
+        is_main: false,
+        branch: repo.as_ref().and_then(|repo| get_head_branch(repo).ok()),
+        head: repo
+            .as_ref()
+            .and_then(|repo| repo.head().ok())
+            .and_then(|head| head.target())
+            .map(|oid| oid.to_string()),
+        is_locked,
+        lock_reason,
+        is_prunable: worktree.is_prunable(None)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+
+    #[test]
+    fn test_worktree_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_dir = dir.path().join("main");
+        let repo = init_repo(&repo_dir);
+        commit_file(&repo, "a.txt", "a\n", "init");
+
+        let manager = GitManager::new();
+        let repo_path = repo_dir.to_str().unwrap();
+        let feature_path = dir.path().join("feature");
+        let options = GitWorktreeAddOptions {
+            path: feature_path.to_string_lossy().to_string(),
+            branch: Some("feature".to_string()),
+            create_branch: Some(true),
+            ..Default::default()
+        };
+        let added = manager.add_worktree(repo_path, options).unwrap();
+        assert_eq!(added.name.as_deref(), Some("feature"));
+        assert_eq!(added.branch.as_deref(), Some("feature"));
+
+        // Status of the worktree reflects its own branch and changes
+        std::fs::write(feature_path.join("a.txt"), "changed\n").unwrap();
+        let status = manager.status(feature_path.to_str().unwrap()).unwrap();
+        assert_eq!(status.branch, "feature");
+        assert_eq!(status.worktree.as_deref(), Some("feature"));
+        assert_eq!(status.unstaged.len(), 1);
+        assert!(manager.status(repo_path).unwrap().unstaged.is_empty());
+
+        let worktrees = manager.list_worktrees(feature_path.to_str().unwrap()).unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main);
+
+        manager.lock_worktree(repo_path, "feature", Some("agent running")).unwrap();
+        assert!(matches!(manager.remove_worktree(repo_path, "feature", false), Err(Error::InvalidState(_))));
+        manager.unlock_worktree(repo_path, feature_path.to_str().unwrap()).unwrap();
+        assert!(matches!(manager.remove_worktree(repo_path, "feature", false), Err(Error::UncommittedChanges)));
+        manager.remove_worktree(repo_path, "feature", true).unwrap();
+        assert!(!feature_path.exists());
+
+        // A worktree whose directory disappeared is pruned
+        let options = GitWorktreeAddOptions {
+            path: dir.path().join("gone").to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        manager.add_worktree(repo_path, options).unwrap();
+        std::fs::remove_dir_all(dir.path().join("gone")).unwrap();
+        assert_eq!(manager.prune_worktrees(repo_path).unwrap(), vec!["gone".to_string()]);
+        assert_eq!(manager.list_worktrees(repo_path).unwrap().len(), 1);
+    }
+}